@@ -0,0 +1,18 @@
+use std::{env, path::PathBuf};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let out_dir = PathBuf::from(env::var("OUT_DIR")?);
+
+    // Generate the SolanaIndexer service (server + client) from the proto definition,
+    // plus the descriptor set served by gRPC reflection
+    tonic_prost_build::configure()
+        .build_server(true)
+        .build_client(true)
+        .file_descriptor_set_path(out_dir.join("solana_indexer_descriptor.bin"))
+        .compile_protos(
+            &["src/proto/solana_indexer/indexer.proto"],
+            &["src/proto/solana_indexer"],
+        )?;
+
+    Ok(())
+}
@@ -0,0 +1,8 @@
+-- Slot commitment migration
+-- Track the confirmed commitment level alongside finalized
+ALTER TABLE slots ADD COLUMN confirmed BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- Finalized slots are confirmed by definition
+UPDATE slots SET confirmed = TRUE WHERE finalized = TRUE;
+
+CREATE INDEX IF NOT EXISTS idx_slots_confirmed ON slots(confirmed);
@@ -0,0 +1,11 @@
+-- Slot production migration
+-- One row per classified slot: whether its scheduled leader produced a block
+CREATE TABLE IF NOT EXISTS slot_production (
+    slot INTEGER PRIMARY KEY,
+    leader_pubkey TEXT NOT NULL,
+    skipped BOOLEAN NOT NULL,
+    recorded_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_slot_production_leader ON slot_production(leader_pubkey);
+CREATE INDEX IF NOT EXISTS idx_slot_production_skipped ON slot_production(skipped);
@@ -0,0 +1,13 @@
+-- Reorg migration
+-- One row per detected fork switch; orphaned_slots is a JSON array of slot numbers
+CREATE TABLE IF NOT EXISTS reorgs (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    slot INTEGER NOT NULL,
+    fork_slot INTEGER NOT NULL,
+    depth INTEGER NOT NULL,
+    orphaned_slots TEXT NOT NULL,
+    rows_rolled_back INTEGER NOT NULL DEFAULT 0,
+    detected_at DATETIME NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_reorgs_slot ON reorgs(slot);
@@ -0,0 +1,10 @@
+-- Slot lifecycle migration
+-- When each slot was first seen and when it reached confirmed and finalized
+CREATE TABLE IF NOT EXISTS slot_timings (
+    slot INTEGER PRIMARY KEY,
+    first_seen_at DATETIME NOT NULL,
+    confirmed_at DATETIME,
+    finalized_at DATETIME,
+    confirmation_ms INTEGER,
+    finalization_ms INTEGER
+);
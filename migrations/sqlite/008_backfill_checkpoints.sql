@@ -0,0 +1,9 @@
+-- Backfill migration
+-- Resume point of each interrupted `db backfill` range
+CREATE TABLE IF NOT EXISTS backfill_checkpoints (
+    from_slot INTEGER NOT NULL,
+    to_slot INTEGER NOT NULL,
+    next_slot INTEGER NOT NULL,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (from_slot, to_slot)
+);
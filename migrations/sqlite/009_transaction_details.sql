@@ -0,0 +1,59 @@
+-- Transaction details migration
+-- Decoded fields of `jsonParsed` transactions, with per-account rows in normalized tables
+ALTER TABLE transactions ADD COLUMN signer TEXT;
+ALTER TABLE transactions ADD COLUMN compute_units INTEGER;
+ALTER TABLE transactions ADD COLUMN priority_fee INTEGER;
+ALTER TABLE transactions ADD COLUMN recent_blockhash TEXT;
+ALTER TABLE transactions ADD COLUMN error TEXT;
+
+-- Static keys followed by addresses loaded from lookup tables
+CREATE TABLE IF NOT EXISTS transaction_accounts (
+    signature TEXT NOT NULL,
+    account_index INTEGER NOT NULL,
+    address TEXT NOT NULL,
+    signer BOOLEAN NOT NULL,
+    writable BOOLEAN NOT NULL,
+    from_lookup_table BOOLEAN NOT NULL,
+    PRIMARY KEY (signature, account_index),
+    FOREIGN KEY (signature) REFERENCES transactions (signature)
+);
+
+-- SOL balances of accounts the transaction changed
+CREATE TABLE IF NOT EXISTS transaction_balance_changes (
+    signature TEXT NOT NULL,
+    account_index INTEGER NOT NULL,
+    address TEXT NOT NULL,
+    pre_lamports INTEGER NOT NULL,
+    post_lamports INTEGER NOT NULL,
+    PRIMARY KEY (signature, account_index),
+    FOREIGN KEY (signature) REFERENCES transactions (signature)
+);
+
+-- Raw token amounts are kept as text since they can exceed INTEGER
+CREATE TABLE IF NOT EXISTS transaction_token_balances (
+    signature TEXT NOT NULL,
+    account_index INTEGER NOT NULL,
+    address TEXT NOT NULL,
+    mint TEXT NOT NULL,
+    owner TEXT,
+    token_program TEXT,
+    decimals INTEGER NOT NULL,
+    pre_amount TEXT NOT NULL,
+    post_amount TEXT NOT NULL,
+    PRIMARY KEY (signature, account_index),
+    FOREIGN KEY (signature) REFERENCES transactions (signature)
+);
+
+CREATE TABLE IF NOT EXISTS transaction_logs (
+    signature TEXT NOT NULL,
+    log_index INTEGER NOT NULL,
+    message TEXT NOT NULL,
+    PRIMARY KEY (signature, log_index),
+    FOREIGN KEY (signature) REFERENCES transactions (signature)
+);
+
+CREATE INDEX IF NOT EXISTS idx_transactions_signer ON transactions(signer);
+CREATE INDEX IF NOT EXISTS idx_transaction_accounts_address ON transaction_accounts(address);
+CREATE INDEX IF NOT EXISTS idx_transaction_balance_changes_address ON transaction_balance_changes(address);
+CREATE INDEX IF NOT EXISTS idx_transaction_token_balances_owner ON transaction_token_balances(owner);
+CREATE INDEX IF NOT EXISTS idx_transaction_token_balances_mint ON transaction_token_balances(mint);
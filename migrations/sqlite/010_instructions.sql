@@ -0,0 +1,19 @@
+-- Instruction migration
+-- Every outer and inner instruction of an ingested transaction
+CREATE TABLE IF NOT EXISTS instructions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    signature TEXT NOT NULL,
+    slot INTEGER NOT NULL,
+    instruction_index INTEGER NOT NULL,
+    inner_index INTEGER, -- NULL for top-level instructions
+    program_id TEXT NOT NULL,
+    parsed_type TEXT,
+    accounts TEXT NOT NULL, -- JSON array as string
+    data TEXT, -- Base58, for instructions the RPC could not parse
+    parsed TEXT, -- Parsed instruction JSON as string
+    FOREIGN KEY (signature) REFERENCES transactions (signature)
+);
+
+CREATE INDEX IF NOT EXISTS idx_instructions_signature ON instructions(signature);
+CREATE INDEX IF NOT EXISTS idx_instructions_program_type ON instructions(program_id, parsed_type);
+CREATE INDEX IF NOT EXISTS idx_instructions_slot ON instructions(slot);
@@ -0,0 +1,29 @@
+-- Token trade migration
+-- Pump.fun creates, buys and sells seen by the Yellowstone monitor
+CREATE TABLE IF NOT EXISTS token_trades (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    signature TEXT NOT NULL,
+    slot INTEGER NOT NULL,
+    trade_index INTEGER NOT NULL,
+    platform TEXT NOT NULL,
+    action TEXT NOT NULL, -- CREATE, BUY or SELL
+    mint TEXT NOT NULL,
+    trader TEXT NOT NULL,
+    bonding_curve TEXT,
+    sol_amount INTEGER, -- Lamports
+    token_amount INTEGER, -- Raw token units
+    virtual_sol_reserves INTEGER,
+    virtual_token_reserves INTEGER,
+    real_sol_reserves INTEGER,
+    real_token_reserves INTEGER,
+    name TEXT,
+    symbol TEXT,
+    uri TEXT,
+    timestamp DATETIME NOT NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE(signature, trade_index)
+);
+
+CREATE INDEX IF NOT EXISTS idx_token_trades_mint ON token_trades(mint);
+CREATE INDEX IF NOT EXISTS idx_token_trades_trader ON token_trades(trader);
+CREATE INDEX IF NOT EXISTS idx_token_trades_slot ON token_trades(slot);
@@ -1,7 +1,7 @@
 use anyhow::Result;
 use colored::*;
-use solana_client::rpc_client::RpcClient;
-use solana_sdk::{pubkey::Pubkey, account::Account};
+use solana_client::rpc_client::{GetConfirmedSignaturesForAddress2Config, RpcClient};
+use solana_sdk::{pubkey::Pubkey, account::Account, signature::Signature};
 use std::collections::HashMap;
 use std::str::FromStr;
 use std::time::Duration;
@@ -9,11 +9,12 @@ use tokio::time::interval;
 use crossterm::terminal::{size, Clear, ClearType};
 use crossterm::cursor;
 use crate::config::Config;
-use crate::database::Database;
+use crate::database::{Database, PENDING_BLOCKHASH};
 use crate::logger::icons;
 use crate::animations::{CliAnimations, StatusStats};
 use crate::enhanced_logger::{EnhancedLogger, LogType};
 use crate::cache::{IndexerCache, CachedAccount, CachedSlotInfo};
+use crate::transaction_decoder::{self, DecodedInstruction, DecodedTransaction};
 use sqlx::Row;
 use serde::{Deserialize, Serialize};
 
@@ -734,19 +735,27 @@ pub async fn start_monitoring(config: &Config, client: &RpcClient, interval_ms:
                     Ok(account) => {
                         // Check for changes
                         if let Some(last_account) = last_accounts.get(address) {
-                            let changes = detect_account_changes(last_account, &account);
-
-                            for change in changes {
+                            let changes: Vec<AccountChange> = detect_account_changes(last_account, &account)
+                                .into_iter()
                                 // Apply filter if specified
-                                if let Some(filters) = &filter {
-                                    if !filters.iter().any(|f| f.to_lowercase() == change.activity_type.as_str().to_lowercase()) {
-                                        continue;
-                                    }
-                                }
+                                .filter(|change| filter.as_ref().is_none_or(|filters| {
+                                    filters.iter().any(|f| f.to_lowercase() == change.activity_type.as_str().to_lowercase())
+                                }))
+                                .collect();
+
+                            // Attribute the changes to the account's latest transaction, stored
+                            // first so the activity's foreign keys are satisfied
+                            let decoded = if changes.is_empty() { None } else { latest_transaction(client, &pubkey) };
+                            if let Some(decoded) = &decoded {
+                                db.store_decoded_transaction(decoded).await?;
+                            }
+                            let instruction = decoded.as_ref().and_then(|decoded| touching_instruction(decoded, address));
+                            let details = decoded.as_ref().and_then(|decoded| activity_details(decoded, address));
 
+                            for change in changes {
                                 // Store activity in database
                                 sqlx::query(
-                                    "INSERT INTO account_activities (account_address, activity_type, change_type, old_value, new_value, timestamp, block_slot, lamports_change, data_size_change) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)"
+                                    "INSERT INTO account_activities (account_address, activity_type, change_type, old_value, new_value, timestamp, block_slot, lamports_change, data_size_change, transaction_signature, program_id, instruction_type, details) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
                                 )
                                 .bind(address)
                                 .bind(change.activity_type.as_str())
@@ -754,9 +763,13 @@ pub async fn start_monitoring(config: &Config, client: &RpcClient, interval_ms:
                                 .bind(&change.old_value)
                                 .bind(&change.new_value)
                                 .bind(chrono::Utc::now())
-                                .bind(account.lamports as i64) // Use lamports as slot for now
+                                .bind(decoded.as_ref().map_or(account.lamports as i64, |decoded| decoded.slot as i64)) // Use lamports as slot when no transaction is found
                                 .bind(change.lamports_change)
                                 .bind(change.data_size_change)
+                                .bind(decoded.as_ref().map(|decoded| decoded.signature.as_str()))
+                                .bind(instruction.map(|instruction| instruction.program_id.as_str()).or(program_id.as_deref()))
+                                .bind(instruction.and_then(|instruction| instruction.parsed_type.as_deref()))
+                                .bind(details.as_deref())
                                 .execute(db.get_pool())
                                 .await?;
 
@@ -808,6 +821,16 @@ pub async fn start_monitoring(config: &Config, client: &RpcClient, interval_ms:
     }
 }
 
+/// Named instructions of `decoded` that reference `address`, as JSON
+fn activity_details(decoded: &DecodedTransaction, address: &str) -> Option<String> {
+    let instructions: Vec<serde_json::Value> = decoded.instructions.iter()
+        .filter(|instruction| instruction.accounts.iter().any(|account| account == address))
+        .filter_map(DecodedInstruction::details)
+        .collect();
+
+    (!instructions.is_empty()).then(|| serde_json::json!({ "instructions": instructions }).to_string())
+}
+
 #[derive(Debug)]
 struct AccountChange {
     activity_type: AccountActivityType,
@@ -818,6 +841,24 @@ struct AccountChange {
     data_size_change: i64,
 }
 
+/// The account's most recent transaction, decoded, as the likely cause of a detected change
+fn latest_transaction(client: &RpcClient, pubkey: &Pubkey) -> Option<DecodedTransaction> {
+    let config = GetConfirmedSignaturesForAddress2Config {
+        limit: Some(1),
+        ..Default::default()
+    };
+    let latest = client.get_signatures_for_address_with_config(pubkey, config).ok()?.into_iter().next()?;
+    let signature = Signature::from_str(&latest.signature).ok()?;
+    transaction_decoder::fetch_decoded_transaction(client, &signature).ok()
+}
+
+/// First instruction that references `address`, or the transaction's primary one
+fn touching_instruction<'a>(decoded: &'a DecodedTransaction, address: &str) -> Option<&'a DecodedInstruction> {
+    decoded.instructions.iter()
+        .find(|instruction| instruction.accounts.iter().any(|account| account == address))
+        .or_else(|| decoded.primary_instruction())
+}
+
 fn detect_account_changes(old_account: &Account, new_account: &Account) -> Vec<AccountChange> {
     let mut changes = Vec::new();
 
@@ -1258,7 +1299,7 @@ pub async fn start_wallet_monitoring(config: &Config, client: &RpcClient, interv
                                     "INSERT OR IGNORE INTO slots (slot, blockhash, parent_slot, finalized, timestamp) VALUES (?, ?, ?, ?, ?)"
                                 )
                                 .bind(current_slot as i64)
-                                .bind("pending_blockhash") // Placeholder blockhash
+                                .bind(PENDING_BLOCKHASH)
                                 .bind((current_slot.saturating_sub(1)) as i64)
                                 .bind(false)
                                 .bind(chrono::Utc::now())
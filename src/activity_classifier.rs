@@ -0,0 +1,518 @@
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+
+use crate::transaction_decoder::{DecodedInstruction, DecodedTransaction};
+use crate::wallet_tracker::{token_symbol, ActivityType, TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID};
+
+pub const SYSTEM_PROGRAM_ID: &str = "11111111111111111111111111111111";
+pub const STAKE_PROGRAM_ID: &str = "Stake11111111111111111111111111111111111111";
+pub const PUMP_FUN_PROGRAM_ID: &str = "6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P";
+
+/// AMMs and aggregators whose transactions are swaps
+const KNOWN_AMMS: &[(&str, &str)] = &[
+    ("675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8", "Raydium AMM"),
+    ("CAMMCzo5YL8w4VFF8KVHrK22GGUsp5VTaW7grrKgrWqK", "Raydium CLMM"),
+    ("CPMMoo8L3F4NbTegBCKVNunggL7H1ZpdTHKxQB5qKP1C", "Raydium CPMM"),
+    ("whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc", "Orca Whirlpool"),
+    ("LBUZKhRxPF3XUpBCjp4YzTKgLccjZhTSDM9YuVaPwxo", "Meteora DLMM"),
+    ("pAMMBay6oceH9fJKBRHGP5D4bD4sWpmSwMn52FMfXEA", "Pump.fun AMM"),
+    ("JUP6LkbZbjS1jKKwapdHNy7hzYZTAx1yEZhcApCc4r7Y", "Jupiter"),
+    (PUMP_FUN_PROGRAM_ID, "Pump.fun"),
+];
+
+const LAMPORTS_PER_SOL: f64 = 1_000_000_000.0;
+
+/// What a transaction did from one wallet's point of view, as stored in
+/// `wallet_activities`
+#[derive(Debug, Clone)]
+pub struct Classification {
+    pub activity_type: ActivityType,
+    /// Amount moved, in whole tokens or SOL
+    pub amount: Option<f64>,
+    pub token_symbol: Option<String>,
+    pub counterparty: Option<String>,
+    /// Instruction the classification came from, when there is one
+    pub program_id: Option<String>,
+    pub instruction_type: Option<String>,
+    pub details: Option<Value>,
+}
+
+impl Classification {
+    fn new(activity_type: ActivityType) -> Self {
+        Self {
+            activity_type,
+            amount: None,
+            token_symbol: None,
+            counterparty: None,
+            program_id: None,
+            instruction_type: None,
+            details: None,
+        }
+    }
+
+    fn from_instruction(activity_type: ActivityType, instruction: &DecodedInstruction) -> Self {
+        Self {
+            program_id: Some(instruction.program_id.clone()),
+            instruction_type: instruction.parsed_type.clone(),
+            ..Self::new(activity_type)
+        }
+    }
+}
+
+/// One step of the classifier; returns `None` to let the next rule decide
+pub trait ActivityRule: Send + Sync {
+    fn classify(&self, transaction: &DecodedTransaction, wallet: &str) -> Option<Classification>;
+}
+
+/// Runs rules in order and takes the first classification
+pub struct ActivityClassifier {
+    rules: Vec<Box<dyn ActivityRule>>,
+}
+
+impl ActivityClassifier {
+    /// Swaps, stake operations, SPL and System transfers, then plain balance
+    /// deltas
+    pub fn new() -> Self {
+        Self {
+            rules: vec![
+                Box::new(SwapRule),
+                Box::new(StakeRule),
+                Box::new(TokenTransferRule),
+                Box::new(SystemTransferRule),
+                Box::new(BalanceDeltaRule),
+            ],
+        }
+    }
+
+    pub fn classify(&self, transaction: &DecodedTransaction, wallet: &str) -> Classification {
+        self.rules.iter()
+            .find_map(|rule| rule.classify(transaction, wallet))
+            .unwrap_or_else(|| Classification::new(ActivityType::Unknown))
+    }
+}
+
+/// Net change of one asset in the wallet; `mint` is `None` for SOL
+#[derive(Debug, Clone)]
+struct AssetDelta {
+    mint: Option<String>,
+    amount: f64,
+}
+
+impl AssetDelta {
+    fn symbol(&self) -> String {
+        match &self.mint {
+            None => "SOL".to_string(),
+            Some(mint) => symbol_or_mint(mint),
+        }
+    }
+
+    fn to_json(&self) -> Value {
+        json!({ "mint": self.mint, "symbol": self.symbol(), "amount": self.amount.abs() })
+    }
+}
+
+fn symbol_or_mint(mint: &str) -> String {
+    token_symbol(mint).map_or_else(|| mint.to_string(), str::to_string)
+}
+
+/// The wallet's SOL and token deltas, with the fee added back when it paid it
+fn wallet_deltas(transaction: &DecodedTransaction, wallet: &str) -> Vec<AssetDelta> {
+    let mut deltas = Vec::new();
+
+    if let Some(change) = transaction.balance_changes.iter().find(|change| change.address == wallet) {
+        let mut lamports = change.delta();
+        if transaction.signer == wallet {
+            lamports += transaction.fee as i64;
+        }
+        if lamports != 0 {
+            deltas.push(AssetDelta { mint: None, amount: lamports as f64 / LAMPORTS_PER_SOL });
+        }
+    }
+
+    let mut tokens: BTreeMap<&str, f64> = BTreeMap::new();
+    for change in transaction.token_balance_changes.iter().filter(|change| change.owner.as_deref() == Some(wallet)) {
+        *tokens.entry(change.mint.as_str()).or_default() += change.ui_delta();
+    }
+    deltas.extend(tokens.into_iter()
+        .filter(|(_, amount)| *amount != 0.0)
+        .map(|(mint, amount)| AssetDelta { mint: Some(mint.to_string()), amount }));
+
+    deltas
+}
+
+/// Largest leg on one side of a trade, preferring tokens over SOL since SOL
+/// also moves for rent
+fn main_leg(deltas: &[AssetDelta], incoming: bool) -> Option<&AssetDelta> {
+    let side = || deltas.iter().filter(move |delta| (delta.amount > 0.0) == incoming);
+    side().filter(|delta| delta.mint.is_some())
+        .max_by(|a, b| a.amount.abs().total_cmp(&b.amount.abs()))
+        .or_else(|| side().next())
+}
+
+fn info(instruction: &DecodedInstruction) -> Option<&Value> {
+    instruction.parsed.as_ref().and_then(|parsed| parsed.get("info"))
+}
+
+fn info_str<'a>(info: &'a Value, key: &str) -> Option<&'a str> {
+    info.get(key).and_then(Value::as_str)
+}
+
+fn is_type(instruction: &DecodedInstruction, types: &[&str]) -> bool {
+    instruction.parsed_type.as_deref().is_some_and(|parsed_type| types.contains(&parsed_type))
+}
+
+/// Trades on a known AMM, where the wallet gives up one asset for another.
+/// Pump.fun bonding curve trades are buys and sells of the token.
+struct SwapRule;
+
+impl ActivityRule for SwapRule {
+    fn classify(&self, transaction: &DecodedTransaction, wallet: &str) -> Option<Classification> {
+        let (program_id, venue) = transaction.instructions.iter()
+            .find_map(|instruction| KNOWN_AMMS.iter().find(|(program_id, _)| *program_id == instruction.program_id))?;
+
+        let deltas = wallet_deltas(transaction, wallet);
+        let sold = main_leg(&deltas, false)?;
+        let bought = main_leg(&deltas, true)?;
+
+        let (activity_type, token) = match (*program_id == PUMP_FUN_PROGRAM_ID, sold.mint.is_none(), bought.mint.is_none()) {
+            (true, true, false) => (ActivityType::Buy, bought),
+            (true, false, true) => (ActivityType::Sell, sold),
+            _ => (ActivityType::Swap, bought),
+        };
+
+        let instruction = transaction.instructions.iter().find(|instruction| instruction.program_id == *program_id);
+        Some(Classification {
+            amount: Some(token.amount.abs()),
+            token_symbol: Some(token.symbol()),
+            counterparty: Some(program_id.to_string()),
+            details: Some(json!({ "venue": venue, "sold": sold.to_json(), "bought": bought.to_json() })),
+            ..match instruction {
+                Some(instruction) => Classification::from_instruction(activity_type, instruction),
+                None => Classification::new(activity_type),
+            }
+        })
+    }
+}
+
+/// Stake program delegate, deactivate and withdraw signed or received by the wallet
+struct StakeRule;
+
+impl ActivityRule for StakeRule {
+    fn classify(&self, transaction: &DecodedTransaction, wallet: &str) -> Option<Classification> {
+        let instruction = transaction.instructions.iter().find(|instruction| {
+            instruction.program_id == STAKE_PROGRAM_ID
+                && is_type(instruction, &["delegate", "deactivate", "withdraw"])
+                && instruction.accounts.iter().any(|account| account == wallet)
+        })?;
+        let info = info(instruction)?;
+        let stake_account = info_str(info, "stakeAccount").map(str::to_string);
+
+        let (activity_type, amount, counterparty) = match instruction.parsed_type.as_deref() {
+            Some("delegate") => (
+                ActivityType::Stake,
+                // Only known when the stake account was funded in the same transaction
+                stake_account.as_deref().and_then(|stake_account| transaction.balance_changes.iter()
+                    .find(|change| change.address == stake_account && change.delta() > 0)
+                    .map(|change| change.post_lamports as f64 / LAMPORTS_PER_SOL)),
+                info_str(info, "voteAccount").map(str::to_string),
+            ),
+            Some("withdraw") => (
+                ActivityType::Unstake,
+                info.get("lamports").and_then(Value::as_u64).map(|lamports| lamports as f64 / LAMPORTS_PER_SOL),
+                stake_account.clone(),
+            ),
+            _ => (ActivityType::Unstake, None, stake_account.clone()),
+        };
+
+        Some(Classification {
+            amount,
+            token_symbol: Some("SOL".to_string()),
+            counterparty,
+            details: Some(json!({ "action": instruction.parsed_type, "stakeAccount": stake_account })),
+            ..Classification::from_instruction(activity_type, instruction)
+        })
+    }
+}
+
+/// SPL Token and Token-2022 `transfer` and `transferChecked` from or to one
+/// of the wallet's token accounts
+struct TokenTransferRule;
+
+impl ActivityRule for TokenTransferRule {
+    fn classify(&self, transaction: &DecodedTransaction, wallet: &str) -> Option<Classification> {
+        // Owner, mint and decimals of every token account whose balance moved
+        let token_accounts: HashMap<&str, (Option<&str>, &str, u8)> = transaction.token_balance_changes.iter()
+            .map(|change| (change.address.as_str(), (change.owner.as_deref(), change.mint.as_str(), change.decimals)))
+            .collect();
+        let owner = |account: Option<&str>| account.and_then(|account| token_accounts.get(account)).and_then(|(owner, _, _)| *owner);
+
+        transaction.instructions.iter()
+            .filter(|instruction| {
+                (instruction.program_id == TOKEN_PROGRAM_ID || instruction.program_id == TOKEN_2022_PROGRAM_ID)
+                    && is_type(instruction, &["transfer", "transferChecked"])
+            })
+            .find_map(|instruction| {
+                let info = info(instruction)?;
+                let source = info_str(info, "source");
+                let destination = info_str(info, "destination");
+                let authority = info_str(info, "authority").or_else(|| info_str(info, "multisigAuthority"));
+
+                let (activity_type, counterparty) = if owner(source) == Some(wallet) || authority == Some(wallet) {
+                    (ActivityType::Send, owner(destination).or(destination))
+                } else if owner(destination) == Some(wallet) {
+                    (ActivityType::Receive, owner(source).or(authority))
+                } else {
+                    return None;
+                };
+
+                let known = source.or(destination).and_then(|account| token_accounts.get(account));
+                let mint = info_str(info, "mint").or(known.map(|(_, mint, _)| *mint));
+                let amount = match info.get("tokenAmount") {
+                    Some(token_amount) => token_amount.get("uiAmountString")
+                        .and_then(Value::as_str)
+                        .and_then(|amount| amount.parse::<f64>().ok()),
+                    // The RPC gives the raw amount as a string, the decoder registry as a number
+                    None => info.get("amount")
+                        .and_then(|amount| amount.as_str().and_then(|amount| amount.parse::<u64>().ok()).or(amount.as_u64()))
+                        .zip(known.map(|(_, _, decimals)| *decimals))
+                        .map(|(amount, decimals)| amount as f64 / 10f64.powi(decimals as i32)),
+                };
+
+                Some(Classification {
+                    amount,
+                    token_symbol: mint.map(symbol_or_mint),
+                    counterparty: counterparty.map(str::to_string),
+                    details: mint.map(|mint| json!({ "mint": mint })),
+                    ..Classification::from_instruction(activity_type, instruction)
+                })
+            })
+    }
+}
+
+/// System program SOL transfers from or to the wallet
+struct SystemTransferRule;
+
+impl ActivityRule for SystemTransferRule {
+    fn classify(&self, transaction: &DecodedTransaction, wallet: &str) -> Option<Classification> {
+        transaction.instructions.iter()
+            .filter(|instruction| instruction.program_id == SYSTEM_PROGRAM_ID && is_type(instruction, &["transfer", "transferWithSeed"]))
+            .find_map(|instruction| {
+                let info = info(instruction)?;
+                let source = info_str(info, "source");
+                let destination = info_str(info, "destination");
+
+                let (activity_type, counterparty) = if source == Some(wallet) {
+                    (ActivityType::Send, destination)
+                } else if destination == Some(wallet) {
+                    (ActivityType::Receive, source)
+                } else {
+                    return None;
+                };
+
+                Some(Classification {
+                    amount: info.get("lamports").and_then(Value::as_u64).map(|lamports| lamports as f64 / LAMPORTS_PER_SOL),
+                    token_symbol: Some("SOL".to_string()),
+                    counterparty: counterparty.map(str::to_string),
+                    ..Classification::from_instruction(activity_type, instruction)
+                })
+            })
+    }
+}
+
+/// Anything else that moved the wallet's balances: a receive when it gained
+/// more than it lost of its main asset, a send otherwise
+struct BalanceDeltaRule;
+
+impl ActivityRule for BalanceDeltaRule {
+    fn classify(&self, transaction: &DecodedTransaction, wallet: &str) -> Option<Classification> {
+        let deltas = wallet_deltas(transaction, wallet);
+        let main = deltas.iter()
+            .filter(|delta| delta.mint.is_some())
+            .max_by(|a, b| a.amount.abs().total_cmp(&b.amount.abs()))
+            .or_else(|| deltas.first())?;
+
+        let activity_type = if main.amount > 0.0 { ActivityType::Receive } else { ActivityType::Send };
+        Some(Classification {
+            amount: Some(main.amount.abs()),
+            token_symbol: Some(main.symbol()),
+            ..Classification::new(activity_type)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transaction_decoder::{BalanceChange, TokenBalanceChange};
+
+    const WALLET: &str = "WaLLet1111111111111111111111111111111111111";
+    const BOB: &str = "Bob1111111111111111111111111111111111111111";
+    const USDC: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+    const RAYDIUM_AMM: &str = "675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8";
+
+    fn transaction(
+        instructions: Vec<DecodedInstruction>,
+        balance_changes: Vec<BalanceChange>,
+        token_balance_changes: Vec<TokenBalanceChange>,
+    ) -> DecodedTransaction {
+        DecodedTransaction {
+            signature: "sig".to_string(),
+            slot: 1,
+            block_time: None,
+            fee: 5000,
+            success: true,
+            error: None,
+            signer: WALLET.to_string(),
+            recent_blockhash: String::new(),
+            compute_units: None,
+            priority_fee: 0,
+            accounts: Vec::new(),
+            instructions,
+            log_messages: Vec::new(),
+            balance_changes,
+            token_balance_changes,
+        }
+    }
+
+    fn instruction(program_id: &str, parsed_type: &str, info: Value, accounts: &[&str]) -> DecodedInstruction {
+        DecodedInstruction {
+            index: 0,
+            inner_index: None,
+            program_id: program_id.to_string(),
+            program: None,
+            parsed_type: Some(parsed_type.to_string()),
+            parsed: Some(json!({ "type": parsed_type, "info": info })),
+            accounts: accounts.iter().map(|account| account.to_string()).collect(),
+            data: None,
+        }
+    }
+
+    fn sol_change(address: &str, pre_lamports: u64, post_lamports: u64) -> BalanceChange {
+        BalanceChange { account_index: 0, address: address.to_string(), pre_lamports, post_lamports }
+    }
+
+    fn token_change(address: &str, owner: &str, mint: &str, pre_amount: u64, post_amount: u64) -> TokenBalanceChange {
+        TokenBalanceChange {
+            account_index: 0,
+            address: address.to_string(),
+            mint: mint.to_string(),
+            owner: Some(owner.to_string()),
+            token_program: Some(TOKEN_PROGRAM_ID.to_string()),
+            decimals: 6,
+            pre_amount,
+            post_amount,
+        }
+    }
+
+    fn assert_classified(classification: &Classification, activity_type: &str, amount: Option<f64>, counterparty: Option<&str>) {
+        assert_eq!(classification.activity_type.as_str(), activity_type);
+        assert_eq!(classification.amount, amount);
+        assert_eq!(classification.counterparty.as_deref(), counterparty);
+    }
+
+    #[test]
+    fn system_transfers_are_sends_and_receives() {
+        let transfer = transaction(vec![instruction(
+            SYSTEM_PROGRAM_ID,
+            "transfer",
+            json!({ "source": WALLET, "destination": BOB, "lamports": 1_500_000_000u64 }),
+            &[WALLET, BOB],
+        )], Vec::new(), Vec::new());
+        let classifier = ActivityClassifier::new();
+
+        let sent = classifier.classify(&transfer, WALLET);
+        assert_classified(&sent, "SEND", Some(1.5), Some(BOB));
+        assert_eq!(sent.token_symbol.as_deref(), Some("SOL"));
+        assert_eq!(sent.program_id.as_deref(), Some(SYSTEM_PROGRAM_ID));
+
+        assert_classified(&classifier.classify(&transfer, BOB), "RECEIVE", Some(1.5), Some(WALLET));
+    }
+
+    #[test]
+    fn token_transfers_resolve_owners_and_amounts() {
+        let changes = vec![
+            token_change("walletUsdc", WALLET, USDC, 30_000_000, 4_500_000),
+            token_change("bobUsdc", BOB, USDC, 0, 25_500_000),
+        ];
+        let checked = transaction(vec![instruction(
+            TOKEN_PROGRAM_ID,
+            "transferChecked",
+            json!({
+                "source": "walletUsdc",
+                "destination": "bobUsdc",
+                "authority": WALLET,
+                "mint": USDC,
+                "tokenAmount": { "amount": "25500000", "decimals": 6, "uiAmountString": "25.5" },
+            }),
+            &["walletUsdc", USDC, "bobUsdc", WALLET],
+        )], Vec::new(), changes.clone());
+        let classifier = ActivityClassifier::new();
+
+        let sent = classifier.classify(&checked, WALLET);
+        assert_classified(&sent, "SEND", Some(25.5), Some(BOB));
+        assert_eq!(sent.token_symbol.as_deref(), Some("USDC"));
+
+        // Plain transfers carry a raw amount, scaled by the token account's decimals
+        let plain = transaction(vec![instruction(
+            TOKEN_PROGRAM_ID,
+            "transfer",
+            json!({ "source": "walletUsdc", "destination": "bobUsdc", "authority": WALLET, "amount": "25500000" }),
+            &["walletUsdc", "bobUsdc", WALLET],
+        )], Vec::new(), changes);
+        let received = classifier.classify(&plain, BOB);
+        assert_classified(&received, "RECEIVE", Some(25.5), Some(WALLET));
+        assert_eq!(received.token_symbol.as_deref(), Some("USDC"));
+    }
+
+    #[test]
+    fn stake_instructions_are_stakes_and_unstakes() {
+        let classifier = ActivityClassifier::new();
+
+        let delegate = transaction(vec![instruction(
+            STAKE_PROGRAM_ID,
+            "delegate",
+            json!({ "stakeAccount": "stake", "voteAccount": "vote", "stakeAuthority": WALLET }),
+            &["stake", "vote", WALLET],
+        )], vec![sol_change("stake", 0, 2_000_000_000)], Vec::new());
+        assert_classified(&classifier.classify(&delegate, WALLET), "STAKE", Some(2.0), Some("vote"));
+
+        let deactivate = transaction(vec![instruction(
+            STAKE_PROGRAM_ID,
+            "deactivate",
+            json!({ "stakeAccount": "stake", "stakeAuthority": WALLET }),
+            &["stake", WALLET],
+        )], Vec::new(), Vec::new());
+        assert_classified(&classifier.classify(&deactivate, WALLET), "UNSTAKE", None, Some("stake"));
+
+        let withdraw = transaction(vec![instruction(
+            STAKE_PROGRAM_ID,
+            "withdraw",
+            json!({ "stakeAccount": "stake", "destination": WALLET, "withdrawAuthority": WALLET, "lamports": 3_000_000_000u64 }),
+            &["stake", WALLET],
+        )], Vec::new(), Vec::new());
+        let unstaked = classifier.classify(&withdraw, WALLET);
+        assert_classified(&unstaked, "UNSTAKE", Some(3.0), Some("stake"));
+        assert_eq!(unstaked.token_symbol.as_deref(), Some("SOL"));
+    }
+
+    #[test]
+    fn amm_trades_are_swaps_and_pump_fun_trades_are_buys() {
+        let classifier = ActivityClassifier::new();
+        // The wallet pays 1 SOL plus the fee for 150 USDC
+        let balances = vec![sol_change(WALLET, 10_000_000_000, 8_999_995_000)];
+        let tokens = vec![token_change("walletUsdc", WALLET, USDC, 0, 150_000_000)];
+
+        let raydium = transaction(
+            vec![instruction(RAYDIUM_AMM, "swap", json!({}), &[WALLET])],
+            balances.clone(),
+            tokens.clone(),
+        );
+        let swap = classifier.classify(&raydium, WALLET);
+        assert_classified(&swap, "SWAP", Some(150.0), Some(RAYDIUM_AMM));
+        assert_eq!(swap.token_symbol.as_deref(), Some("USDC"));
+        assert_eq!(swap.details.unwrap()["sold"]["amount"], json!(1.0));
+
+        let pump_fun = transaction(vec![instruction(PUMP_FUN_PROGRAM_ID, "buy", json!({}), &[WALLET])], balances, tokens);
+        assert_classified(&classifier.classify(&pump_fun, WALLET), "BUY", Some(150.0), Some(PUMP_FUN_PROGRAM_ID));
+    }
+}
@@ -1,10 +1,56 @@
 use anyhow::Result;
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
 use colored::*;
+use solana_client::nonblocking::rpc_client::RpcClient as NonblockingRpcClient;
 use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_config::{RpcBlockConfig, RpcTransactionConfig};
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+use solana_transaction_status::{TransactionDetails, UiTransactionEncoding};
+use std::net::SocketAddr;
+use std::str::FromStr;
+use std::sync::Arc;
 use std::time::{Duration, Instant};
-use tracing::{info, debug, warn, error};
+use tracing::{info, warn};
 
-/// Start high-performance API server
+use crate::cache::{CachedAccount, CachedSlotInfo, IndexerCache};
+use crate::tls::ServerTls;
+
+/// Shared state for the HTTP handlers
+#[derive(Clone)]
+struct ApiState {
+    client: Arc<NonblockingRpcClient>,
+    cache: Option<IndexerCache>,
+    started_at: Instant,
+}
+
+/// JSON error body with the matching HTTP status
+struct ApiError(StatusCode, String);
+
+impl ApiError {
+    fn bad_request(message: impl Into<String>) -> Self {
+        Self(StatusCode::BAD_REQUEST, message.into())
+    }
+
+    fn upstream(error: impl std::fmt::Display) -> Self {
+        Self(StatusCode::BAD_GATEWAY, format!("RPC error: {}", error))
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.0, Json(serde_json::json!({ "error": self.1 }))).into_response()
+    }
+}
+
+type ApiResult = std::result::Result<Json<serde_json::Value>, ApiError>;
+
+/// Start high-performance API server, over TLS when `tls` is set
 #[allow(unused_variables)]
 pub async fn start_high_performance_api(
     port: &u16,
@@ -13,90 +59,215 @@ pub async fn start_high_performance_api(
     cache: &bool,
     config: &crate::config::Config,
     client: RpcClient,
+    tls: Option<ServerTls>,
 ) -> Result<()> {
     info!("{} {}", "🚀 Starting high-performance API server on port:".bright_cyan(), port.to_string().yellow());
 
+    let scheme = if tls.is_some() { "https" } else { "http" };
+
     println!();
     println!("{}", "⚙️  API Configuration:".bright_yellow());
     println!("   {} {}", "Port:".bright_white(), port.to_string().bright_cyan());
     println!("   {} {}", "Rate Limiting:".bright_white(), if *rate_limit { "✅ Enabled".bright_green() } else { "❌ Disabled".bright_red() });
     println!("   {} {}", "Max RPS:".bright_white(), max_rps.to_string().bright_cyan());
     println!("   {} {}", "Caching:".bright_white(), if *cache { "✅ Enabled".bright_green() } else { "❌ Disabled".bright_red() });
+    println!("   {} {}", "TLS:".bright_white(), match &tls {
+        Some(tls) if tls.client_ca_path.is_some() => "✅ Mutual TLS".bright_green(),
+        Some(_) => "✅ Enabled".bright_green(),
+        None => "❌ Disabled".bright_red(),
+    });
     println!();
 
-    if *cache {
+    let cache_system = if *cache {
         info!("{}", "💾 Initializing high-performance cache...".bright_blue());
-        tokio::time::sleep(Duration::from_millis(800)).await;
-
-        let cache_system = crate::cache::IndexerCache::new(config.clone());
+        let cache_system = IndexerCache::new(config.clone());
         info!("{}", "✅ Multi-layer cache system ready".bright_green());
-    }
+        Some(cache_system)
+    } else {
+        None
+    };
 
     if *rate_limit {
-        info!("{} {} {}", "⏱️  Setting up rate limiting at".bright_blue(), max_rps, "RPS".bright_blue());
-        tokio::time::sleep(Duration::from_millis(400)).await;
+        warn!("{} {} {}", "⏱️  Rate limiting is not enforced by the HTTP API yet, ignoring".bright_yellow(), max_rps, "RPS".bright_yellow());
     }
 
     info!("{}", "🌐 Initializing API routes...".bright_blue());
-    tokio::time::sleep(Duration::from_millis(600)).await;
 
-    info!("{} {}", "✅ High-performance API server ready on".bright_green(), format!("http://0.0.0.0:{}", port).bright_cyan());
+    let state = ApiState {
+        // Handlers share one nonblocking client so requests never stall the runtime
+        client: Arc::new(NonblockingRpcClient::new_with_commitment(client.url(), client.commitment())),
+        cache: cache_system,
+        started_at: Instant::now(),
+    };
+
+    let app = Router::new()
+        .route("/api/v1/slot/current", get(current_slot))
+        .route("/api/v1/slot/{slot}", get(slot_info))
+        .route("/api/v1/transaction/{signature}", get(transaction))
+        .route("/api/v1/account/{pubkey}", get(account))
+        .route("/api/v1/block/{slot}", get(block))
+        .route("/api/v1/metrics", get(metrics))
+        .route("/api/v1/health", get(health))
+        .with_state(state);
+
+    let addr = SocketAddr::from(([0, 0, 0, 0], *port));
+    let rustls_config = tls.as_ref().map(ServerTls::rustls_config).transpose()?;
+
+    info!("{} {}", "✅ High-performance API server ready on".bright_green(), format!("{}://0.0.0.0:{}", scheme, port).bright_cyan());
 
     println!();
     println!("{}", "🎯 High-Performance Endpoints:".bright_yellow());
-    println!("   {} {}", "•".bright_cyan(), format!("GET  http://0.0.0.0:{}/api/v1/slot/current", port).bright_white());
-    println!("   {} {}", "•".bright_cyan(), format!("GET  http://0.0.0.0:{}/api/v1/slot/{{slot}}", port).bright_white());
-    println!("   {} {}", "•".bright_cyan(), format!("GET  http://0.0.0.0:{}/api/v1/transaction/{{signature}}", port).bright_white());
-    println!("   {} {}", "•".bright_cyan(), format!("GET  http://0.0.0.0:{}/api/v1/account/{{pubkey}}", port).bright_white());
-    println!("   {} {}", "•".bright_cyan(), format!("GET  http://0.0.0.0:{}/api/v1/block/{{slot}}", port).bright_white());
-    println!("   {} {}", "•".bright_cyan(), format!("GET  http://0.0.0.0:{}/api/v1/metrics", port).bright_white());
-    println!("   {} {}", "•".bright_cyan(), format!("GET  http://0.0.0.0:{}/api/v1/health", port).bright_white());
+    for path in [
+        "/api/v1/slot/current",
+        "/api/v1/slot/{slot}",
+        "/api/v1/transaction/{signature}",
+        "/api/v1/account/{pubkey}",
+        "/api/v1/block/{slot}",
+        "/api/v1/metrics",
+        "/api/v1/health",
+    ] {
+        println!("   {} {}", "•".bright_cyan(), format!("GET  {}://0.0.0.0:{}{}", scheme, port, path).bright_white());
+    }
     println!();
 
-    println!("{}", "⚡ Performance Features:".bright_yellow());
-    println!("   {} {}", "•".bright_cyan(), "Sub-millisecond response times".bright_green());
-    println!("   {} {}", "•".bright_cyan(), "1000+ TPS throughput capability".bright_green());
-    println!("   {} {}", "•".bright_cyan(), "Multi-layer LRU + TTL caching".bright_green());
-    println!("   {} {}", "•".bright_cyan(), "Real-time slot and transaction data".bright_green());
-    println!("   {} {}", "•".bright_cyan(), "Horizontal scaling ready".bright_green());
-    println!();
+    let handle = axum_server::Handle::new();
+    let shutdown = handle.clone();
+    tokio::spawn(async move {
+        let _ = tokio::signal::ctrl_c().await;
+        info!("{}", "🛑 Shutting down API server...".bright_yellow());
+        shutdown.graceful_shutdown(Some(Duration::from_secs(5)));
+    });
+
+    match rustls_config {
+        Some(rustls_config) => {
+            axum_server::bind_rustls(addr, rustls_config)
+                .handle(handle)
+                .serve(app.into_make_service())
+                .await?
+        }
+        None => {
+            axum_server::bind(addr)
+                .handle(handle)
+                .serve(app.into_make_service())
+                .await?
+        }
+    }
 
-        let mut request_count = 0;
-    let mut total_response_time = Duration::ZERO;
-    let start_time = Instant::now();
+    Ok(())
+}
 
-    loop {
-        let start = Instant::now();
-
-        match client.get_slot() {
-            Ok(slot) => {
-                let response_time = start.elapsed();
-                total_response_time += response_time;
-                request_count += 1;
-
-                if request_count % 100 == 0 {
-                    let avg_response_time = total_response_time / request_count;
-                    let elapsed_time = start_time.elapsed();
-                    let rps = request_count as f64 / elapsed_time.as_secs_f64();
-
-                    info!("{} {} {} {:.2}ms {} {:.0}",
-                        "⚡ High-performance API:".bright_green(),
-                        request_count,
-                        "requests processed, avg:".bright_white(),
-                        avg_response_time.as_secs_f64() * 1000.0,
-                        "response time,".bright_white(),
-                        rps);
-                }
-
-                debug!("{} {} {}", "📊 Processed".bright_blue(), "slot request".bright_white(), format!("(slot: {})", slot).bright_cyan());
-            }
-            Err(e) => {
-                error!("{} {} {}", "❌ RPC Error:".bright_red(), e, "request".bright_white());
-            }
+async fn health(State(state): State<ApiState>) -> ApiResult {
+    Ok(Json(serde_json::json!({
+        "status": "healthy",
+        "uptime_seconds": state.started_at.elapsed().as_secs(),
+    })))
+}
+
+async fn current_slot(State(state): State<ApiState>) -> ApiResult {
+    let slot = state.client.get_slot().await.map_err(ApiError::upstream)?;
+    Ok(Json(serde_json::json!({ "slot": slot })))
+}
+
+async fn slot_info(State(state): State<ApiState>, Path(slot): Path<u64>) -> ApiResult {
+    if let Some(cache) = &state.cache {
+        if let Some(info) = cache.get_slot(slot).await {
+            return Ok(Json(serde_json::to_value(info).map_err(ApiError::upstream)?));
         }
+    }
 
-        tokio::time::sleep(Duration::from_millis(100)).await;
+    let block = state.client
+        .get_block_with_config(slot, RpcBlockConfig {
+            transaction_details: Some(TransactionDetails::None),
+            rewards: Some(false),
+            max_supported_transaction_version: Some(0),
+            ..RpcBlockConfig::default()
+        })
+        .await
+        .map_err(ApiError::upstream)?;
+
+    let info = CachedSlotInfo {
+        slot,
+        leader: String::new(),
+        block_hash: block.blockhash,
+        timestamp: block.block_time.unwrap_or_default(),
+        confirmed: true,
+        finalized: false,
+        cached_at: chrono::Utc::now().timestamp(),
+    };
+    if let Some(cache) = &state.cache {
+        let _ = cache.cache_slot(info.clone()).await;
     }
+
+    Ok(Json(serde_json::to_value(info).map_err(ApiError::upstream)?))
+}
+
+async fn transaction(State(state): State<ApiState>, Path(signature): Path<String>) -> ApiResult {
+    let signature = Signature::from_str(&signature)
+        .map_err(|_| ApiError::bad_request(format!("invalid signature '{}'", signature)))?;
+
+    let transaction = state.client
+        .get_transaction_with_config(&signature, RpcTransactionConfig {
+            encoding: Some(UiTransactionEncoding::Json),
+            commitment: None,
+            max_supported_transaction_version: Some(0),
+        })
+        .await
+        .map_err(ApiError::upstream)?;
+
+    Ok(Json(serde_json::to_value(transaction).map_err(ApiError::upstream)?))
+}
+
+async fn account(State(state): State<ApiState>, Path(pubkey): Path<String>) -> ApiResult {
+    let address = Pubkey::from_str(&pubkey)
+        .map_err(|_| ApiError::bad_request(format!("invalid pubkey '{}'", pubkey)))?;
+
+    if let Some(cache) = &state.cache {
+        if let Some(account) = cache.get_account(&pubkey).await {
+            return Ok(Json(serde_json::to_value(account).map_err(ApiError::upstream)?));
+        }
+    }
+
+    let account = state.client.get_account(&address).await.map_err(ApiError::upstream)?;
+    let cached = CachedAccount {
+        pubkey,
+        lamports: account.lamports,
+        owner: account.owner.to_string(),
+        executable: account.executable,
+        rent_epoch: account.rent_epoch,
+        data_len: account.data.len(),
+        cached_at: chrono::Utc::now().timestamp(),
+    };
+    if let Some(cache) = &state.cache {
+        let _ = cache.cache_account(cached.clone()).await;
+    }
+
+    Ok(Json(serde_json::to_value(cached).map_err(ApiError::upstream)?))
+}
+
+async fn block(State(state): State<ApiState>, Path(slot): Path<u64>) -> ApiResult {
+    let block = state.client
+        .get_block_with_config(slot, RpcBlockConfig {
+            transaction_details: Some(TransactionDetails::Signatures),
+            rewards: Some(false),
+            max_supported_transaction_version: Some(0),
+            ..RpcBlockConfig::default()
+        })
+        .await
+        .map_err(ApiError::upstream)?;
+
+    Ok(Json(serde_json::to_value(block).map_err(ApiError::upstream)?))
+}
+
+async fn metrics(State(state): State<ApiState>) -> ApiResult {
+    let cache = match &state.cache {
+        Some(cache) => cache.get_performance_metrics().await,
+        None => serde_json::Value::Null,
+    };
+
+    Ok(Json(serde_json::json!({
+        "uptime_seconds": state.started_at.elapsed().as_secs(),
+        "cache": cache,
+    })))
 }
 
 /// Show API status
@@ -0,0 +1,427 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use colored::*;
+use futures::StreamExt;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcBlockConfig;
+use solana_commitment_config::CommitmentConfig;
+use solana_transaction_status::{TransactionDetails, UiTransactionEncoding};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+use tokio::time::{Interval, MissedTickBehavior};
+use tracing::{debug, info, warn};
+
+use crate::database::{Database, TrackedSlot};
+use crate::slot_tracker::{is_skipped_slot_error, tracked_transaction};
+
+/// Slots listed with `getBlocks` and checkpointed at a time
+const CHUNK_SLOTS: u64 = 1_000;
+
+/// Fetched blocks written per database transaction
+const WRITE_BATCH_SIZE: usize = 50;
+
+/// Attempts per block before it is counted as failed
+const MAX_ATTEMPTS: u32 = 3;
+
+const RETRY_DELAY: Duration = Duration::from_millis(500);
+
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(5);
+
+/// What `db backfill` fetches and how hard it may hit the RPC
+pub struct BackfillOptions {
+    pub from_slot: u64,
+    pub to_slot: u64,
+    pub workers: usize,
+    /// RPC requests per second across all workers
+    pub rate_limit: u32,
+    pub transactions: bool,
+}
+
+#[derive(Debug, Default)]
+pub struct BackfillSummary {
+    pub stored: u64,
+    pub already_indexed: u64,
+    pub skipped: u64,
+    pub failed: u64,
+}
+
+/// Spaces RPC requests so all workers together stay within the budget
+struct RateBudget {
+    ticks: Mutex<Interval>,
+}
+
+impl RateBudget {
+    fn new(requests_per_second: u32) -> Self {
+        let mut ticks = tokio::time::interval(Duration::from_secs(1) / requests_per_second.max(1));
+        ticks.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        Self { ticks: Mutex::new(ticks) }
+    }
+
+    async fn acquire(&self) {
+        self.ticks.lock().await.tick().await;
+    }
+}
+
+enum FetchOutcome {
+    Stored(TrackedSlot),
+    Skipped,
+    Failed,
+}
+
+/// Store every finalized block in `from_slot..=to_slot` that isn't indexed
+/// yet, fetching with `workers` concurrent requests. Progress is
+/// checkpointed per chunk, so an interrupted run resumes where it stopped.
+pub async fn run_backfill(database: &Database, rpc_url: &str, options: BackfillOptions) -> Result<BackfillSummary> {
+    if options.from_slot > options.to_slot {
+        return Err(anyhow::anyhow!("--from-slot {} is past --to-slot {}", options.from_slot, options.to_slot));
+    }
+
+    let client = RpcClient::new_with_commitment(rpc_url.to_string(), CommitmentConfig::finalized());
+    let budget = RateBudget::new(options.rate_limit);
+
+    budget.acquire().await;
+    let finalized_tip = client.get_slot().await?;
+    let to_slot = if options.to_slot > finalized_tip {
+        warn!("{} {} | Slot {} is not finalized yet, stopping at {}",
+            "⚠️".bright_yellow(),
+            "BACKFILL".bright_yellow(),
+            options.to_slot,
+            finalized_tip
+        );
+        finalized_tip
+    } else {
+        options.to_slot
+    };
+
+    let start_slot = match database.get_backfill_checkpoint(options.from_slot, options.to_slot).await? {
+        Some(next_slot) => {
+            info!("{} {} | Resuming {}..={} from checkpoint at slot {}",
+                "⏯️".bright_green(),
+                "BACKFILL".bright_green(),
+                options.from_slot,
+                options.to_slot,
+                next_slot.to_string().bright_yellow()
+            );
+            next_slot
+        }
+        None => options.from_slot,
+    };
+
+    info!("{} {} | Backfilling slots {}..={} with {} workers at {} req/s",
+        "🚀".bright_green(),
+        "BACKFILL".bright_green(),
+        start_slot,
+        to_slot,
+        options.workers,
+        options.rate_limit
+    );
+
+    let mut summary = BackfillSummary::default();
+    let total_slots = (to_slot + 1).saturating_sub(start_slot);
+    let started_at = Instant::now();
+    let mut last_progress = Instant::now();
+    // Chunks with failed blocks hold the checkpoint back so a rerun retries them
+    let mut checkpoint_held = false;
+
+    let mut chunk_start = start_slot;
+    while chunk_start <= to_slot {
+        let chunk_end = to_slot.min(chunk_start + CHUNK_SLOTS - 1);
+
+        budget.acquire().await;
+        let produced = client.get_blocks(chunk_start, Some(chunk_end)).await?;
+        let indexed = database.get_indexed_slots(chunk_start, chunk_end).await?;
+        let produced_count = produced.len() as u64;
+        summary.skipped += (chunk_end - chunk_start + 1) - produced_count;
+
+        let pending: Vec<u64> = produced.into_iter().filter(|slot| !indexed.contains(slot)).collect();
+        summary.already_indexed += produced_count - pending.len() as u64;
+
+        let leaders = if pending.is_empty() {
+            HashMap::new()
+        } else {
+            fetch_leaders(&client, &budget, chunk_start, chunk_end).await
+        };
+
+        let mut fetches = futures::stream::iter(pending)
+            .map(|slot| fetch_block(&client, &budget, slot, options.transactions, leaders.get(&slot).cloned()))
+            .buffer_unordered(options.workers.max(1));
+
+        let mut batch = Vec::with_capacity(WRITE_BATCH_SIZE);
+        let mut chunk_failed = false;
+        while let Some(outcome) = fetches.next().await {
+            match outcome {
+                FetchOutcome::Stored(tracked) => batch.push(tracked),
+                FetchOutcome::Skipped => summary.skipped += 1,
+                FetchOutcome::Failed => {
+                    summary.failed += 1;
+                    chunk_failed = true;
+                }
+            }
+
+            if batch.len() >= WRITE_BATCH_SIZE {
+                database.save_tracked_slots(&batch, &[]).await?;
+                summary.stored += batch.len() as u64;
+                batch.clear();
+            }
+
+            if last_progress.elapsed() >= PROGRESS_INTERVAL {
+                last_progress = Instant::now();
+                print_progress(&summary, chunk_start, total_slots, start_slot, started_at);
+            }
+        }
+
+        if !batch.is_empty() {
+            database.save_tracked_slots(&batch, &[]).await?;
+            summary.stored += batch.len() as u64;
+        }
+
+        checkpoint_held |= chunk_failed;
+        if !checkpoint_held {
+            database.save_backfill_checkpoint(options.from_slot, options.to_slot, chunk_end + 1).await?;
+        }
+
+        chunk_start = chunk_end + 1;
+    }
+
+    print_progress(&summary, to_slot + 1, total_slots, start_slot, started_at);
+
+    if summary.failed == 0 && to_slot == options.to_slot {
+        database.clear_backfill_checkpoint(options.from_slot, options.to_slot).await?;
+    }
+
+    Ok(summary)
+}
+
+/// Scheduled leaders of `from_slot..=to_slot`; empty when the RPC no longer
+/// has the schedule for that epoch
+async fn fetch_leaders(client: &RpcClient, budget: &RateBudget, from_slot: u64, to_slot: u64) -> HashMap<u64, String> {
+    budget.acquire().await;
+    match client.get_slot_leaders(from_slot, to_slot - from_slot + 1).await {
+        Ok(leaders) => leaders.into_iter()
+            .enumerate()
+            .map(|(offset, leader)| (from_slot + offset as u64, leader.to_string()))
+            .collect(),
+        Err(e) => {
+            debug!("No leader schedule for slots {}..={}: {}", from_slot, to_slot, e);
+            HashMap::new()
+        }
+    }
+}
+
+async fn fetch_block(client: &RpcClient, budget: &RateBudget, slot: u64, transactions: bool, leader: Option<String>) -> FetchOutcome {
+    let config = RpcBlockConfig {
+        encoding: Some(UiTransactionEncoding::Base64),
+        transaction_details: Some(if transactions { TransactionDetails::Full } else { TransactionDetails::None }),
+        rewards: Some(false),
+        commitment: Some(CommitmentConfig::finalized()),
+        max_supported_transaction_version: Some(0),
+    };
+
+    for attempt in 1..=MAX_ATTEMPTS {
+        budget.acquire().await;
+        match client.get_block_with_config(slot, config).await {
+            Ok(block) => {
+                return FetchOutcome::Stored(TrackedSlot {
+                    slot,
+                    blockhash: block.blockhash,
+                    parent_slot: block.parent_slot,
+                    finalized: true,
+                    timestamp: block.block_time.and_then(|time| DateTime::from_timestamp(time, 0)).unwrap_or_else(Utc::now),
+                    leader,
+                    transactions: block.transactions.unwrap_or_default().iter()
+                        .filter_map(tracked_transaction)
+                        .collect(),
+                });
+            }
+            Err(e) if is_skipped_slot_error(&e) => return FetchOutcome::Skipped,
+            Err(e) if attempt < MAX_ATTEMPTS => {
+                debug!("Attempt {} for block {} failed: {}", attempt, slot, e);
+                tokio::time::sleep(RETRY_DELAY * attempt).await;
+            }
+            Err(e) => {
+                warn!("{} {} | Giving up on block {}: {}",
+                    "⚠️".bright_yellow(),
+                    "BACKFILL".bright_yellow(),
+                    slot,
+                    e
+                );
+            }
+        }
+    }
+
+    FetchOutcome::Failed
+}
+
+fn print_progress(summary: &BackfillSummary, next_slot: u64, total_slots: u64, start_slot: u64, started_at: Instant) {
+    let done = next_slot.saturating_sub(start_slot).min(total_slots);
+    let elapsed = started_at.elapsed().as_secs_f64().max(0.001);
+    let percent = if total_slots == 0 { 100.0 } else { done as f64 / total_slots as f64 * 100.0 };
+
+    info!("{} {} | {}/{} slots ({:.1}%) | {} stored, {} indexed, {} skipped, {} failed | {:.1} blocks/s",
+        "📦".bright_cyan(),
+        "BACKFILL".bright_cyan(),
+        done,
+        total_slots,
+        percent,
+        summary.stored.to_string().bright_green(),
+        summary.already_indexed,
+        summary.skipped,
+        summary.failed.to_string().bright_red(),
+        summary.stored as f64 / elapsed
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::DatabaseConfig;
+    use axum::{extract::State, routing::post, Json, Router};
+    use serde_json::{json, Value};
+    use std::collections::HashSet;
+    use std::sync::{Arc, Mutex as StdMutex};
+
+    const LEADER: &str = "11111111111111111111111111111111";
+
+    /// Finalized chain served over JSON-RPC, where `getBlock` fails for the
+    /// `failing` slots
+    #[derive(Clone, Default)]
+    struct MockCluster {
+        tip: u64,
+        produced: Vec<u64>,
+        failing: HashSet<u64>,
+        /// Slots `getBlock` was called for
+        fetched: Arc<StdMutex<Vec<u64>>>,
+    }
+
+    impl MockCluster {
+        fn new(tip: u64, produced: impl IntoIterator<Item = u64>) -> Self {
+            Self { tip, produced: produced.into_iter().collect(), ..Default::default() }
+        }
+
+        fn failing(self, failing: impl IntoIterator<Item = u64>) -> Self {
+            Self { failing: failing.into_iter().collect(), ..self }
+        }
+
+        fn fetched(&self) -> Vec<u64> {
+            let mut fetched = self.fetched.lock().unwrap().clone();
+            fetched.sort_unstable();
+            fetched.dedup();
+            fetched
+        }
+
+        async fn serve(&self) -> String {
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let url = format!("http://{}", listener.local_addr().unwrap());
+            let app = Router::new().route("/", post(handle_rpc)).with_state(self.clone());
+            tokio::spawn(async move { axum::serve(listener, app).await });
+            url
+        }
+    }
+
+    async fn handle_rpc(State(cluster): State<MockCluster>, Json(request): Json<Value>) -> Json<Value> {
+        let param = |index: usize| request["params"][index].as_u64().unwrap_or_default();
+        let result = match request["method"].as_str().unwrap_or_default() {
+            "getVersion" => json!({ "solana-core": "3.0.0", "feature-set": 0 }),
+            "getSlot" => json!(cluster.tip),
+            "getBlocks" => json!(cluster.produced.iter().filter(|slot| (param(0)..=param(1)).contains(slot)).collect::<Vec<_>>()),
+            "getSlotLeaders" => json!(vec![LEADER; param(1) as usize]),
+            "getBlock" => {
+                let slot = param(0);
+                cluster.fetched.lock().unwrap().push(slot);
+                if cluster.failing.contains(&slot) {
+                    return Json(json!({ "jsonrpc": "2.0", "id": request["id"], "error": { "code": -32603, "message": "Internal error" } }));
+                }
+                json!({
+                    "blockhash": format!("hash-{}", slot),
+                    "previousBlockhash": format!("hash-{}", slot - 1),
+                    "parentSlot": slot - 1,
+                    "blockTime": null,
+                    "blockHeight": null,
+                })
+            }
+            _ => return Json(json!({ "jsonrpc": "2.0", "id": request["id"], "error": { "code": -32601, "message": "Method not found" } })),
+        };
+        Json(json!({ "jsonrpc": "2.0", "id": request["id"], "result": result }))
+    }
+
+    async fn memory_database() -> Database {
+        Database::new(&DatabaseConfig {
+            database_url: "sqlite::memory:".to_string(),
+            max_connections: 1,
+            ..DatabaseConfig::default()
+        }).await.unwrap()
+    }
+
+    fn options(from_slot: u64, to_slot: u64) -> BackfillOptions {
+        BackfillOptions { from_slot, to_slot, workers: 4, rate_limit: 10_000, transactions: false }
+    }
+
+    #[tokio::test]
+    async fn placeholder_rows_are_backfilled() {
+        let database = memory_database().await;
+        database.upsert_slot_commitment(101, true, true, Utc::now()).await.unwrap();
+        database.insert_slot(102, "hash-102", 101, true, Utc::now()).await.unwrap();
+
+        let cluster = MockCluster::new(200, 100..=104);
+        let summary = run_backfill(&database, &cluster.serve().await, options(100, 104)).await.unwrap();
+
+        assert_eq!(cluster.fetched(), vec![100, 101, 103, 104]);
+        assert_eq!((summary.stored, summary.already_indexed, summary.failed), (4, 1, 0));
+        assert_eq!(database.get_slot(101).await.unwrap().unwrap().blockhash, "hash-101");
+        assert_eq!(database.get_slot_leader(101).await.unwrap().unwrap().leader_pubkey, LEADER);
+    }
+
+    #[tokio::test]
+    async fn skipped_slots_are_counted_and_a_finished_run_clears_its_checkpoint() {
+        let database = memory_database().await;
+        let cluster = MockCluster::new(200, [100, 102]);
+        let summary = run_backfill(&database, &cluster.serve().await, options(100, 103)).await.unwrap();
+
+        assert_eq!((summary.stored, summary.skipped), (2, 2));
+        assert!(database.get_backfill_checkpoint(100, 103).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn resumes_from_the_checkpoint() {
+        let database = memory_database().await;
+        database.save_backfill_checkpoint(0, 1_999, CHUNK_SLOTS).await.unwrap();
+
+        let cluster = MockCluster::new(5_000, [5, 1_005]);
+        let summary = run_backfill(&database, &cluster.serve().await, options(0, 1_999)).await.unwrap();
+
+        assert_eq!(cluster.fetched(), vec![1_005]);
+        assert_eq!(summary.stored, 1);
+        assert!(database.get_slot(5).await.unwrap().is_none());
+        assert!(database.get_backfill_checkpoint(0, 1_999).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn failed_chunk_holds_the_checkpoint_back() {
+        let database = memory_database().await;
+        let cluster = MockCluster::new(5_000, [5, 1_005, 2_005]).failing([1_005]);
+        let summary = run_backfill(&database, &cluster.serve().await, options(0, 2_999)).await.unwrap();
+
+        assert_eq!((summary.stored, summary.failed), (2, 1));
+        assert_eq!(database.get_backfill_checkpoint(0, 2_999).await.unwrap(), Some(CHUNK_SLOTS));
+
+        // The rerun retries from the failed chunk and skips what it already stored
+        let cluster = MockCluster::new(5_000, [5, 1_005, 2_005]);
+        let summary = run_backfill(&database, &cluster.serve().await, options(0, 2_999)).await.unwrap();
+
+        assert_eq!(cluster.fetched(), vec![1_005]);
+        assert_eq!((summary.stored, summary.already_indexed), (1, 1));
+        assert!(database.get_backfill_checkpoint(0, 2_999).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn stops_at_the_finalized_tip_and_keeps_the_checkpoint() {
+        let database = memory_database().await;
+        let cluster = MockCluster::new(50, [10, 60]);
+        let summary = run_backfill(&database, &cluster.serve().await, options(0, 100)).await.unwrap();
+
+        assert_eq!(cluster.fetched(), vec![10]);
+        assert_eq!(summary.stored, 1);
+        assert_eq!(database.get_backfill_checkpoint(0, 100).await.unwrap(), Some(51));
+    }
+}
@@ -264,6 +264,15 @@ impl IndexerCache {
         debug!("{}", "✅ Cache maintenance completed".bright_green());
     }
 
+    /// Drop cached slot and block data for slots orphaned by a reorg
+    pub async fn invalidate_slots(&self, slots: &[u64]) {
+        for slot in slots {
+            self.hot_slots.invalidate(slot).await;
+            self.blocks.invalidate(slot).await;
+        }
+        debug!("{} {}", "🔀 Invalidated orphaned slots:".bright_yellow(), slots.len().to_string().yellow());
+    }
+
     /// Invalidate all caches (emergency reset)
     pub async fn invalidate_all(&self) {
         warn!("{}", "🔥 Invalidating ALL caches...".bright_red());
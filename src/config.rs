@@ -14,6 +14,10 @@ pub struct Config {
     pub helius_parsed_tx_url: String,
     pub helius_tx_history_url: String,
 
+    // Yellowstone Geyser gRPC (from environment - sensitive)
+    pub yellowstone_endpoint: String,
+    pub yellowstone_auth_token: String,
+
     // Webhook configuration (from environment - sensitive)
     pub webhook_base_url: String,
     pub webhook_secrets: WebhookSecrets,
@@ -141,6 +145,7 @@ pub struct PerformanceConfig {
     pub http_client_pool_size: u32,
     pub enable_rate_limiting: bool,
     pub max_requests_per_second: u32,
+    pub max_streams_per_client: u32,
     pub rate_limit_window_seconds: u64,
     pub optimize_for_latency: bool,
     pub flow_block_interval_ms: u64,
@@ -252,6 +257,7 @@ impl Default for PerformanceConfig {
             http_client_pool_size: 100,
             enable_rate_limiting: true,
             max_requests_per_second: 1000,
+            max_streams_per_client: 16,
             rate_limit_window_seconds: 60,
             optimize_for_latency: true,
             flow_block_interval_ms: 2000,
@@ -302,6 +308,12 @@ impl Config {
             helius_tx_history_url: env::var("HELIUS_TX_HISTORY_URL")
                 .unwrap_or_else(|_| "https://api.helius.xyz/v0/addresses/{address}/transactions/".to_string()),
 
+            // Yellowstone Geyser gRPC
+            yellowstone_endpoint: env::var("YELLOWSTONE_ENDPOINT")
+                .unwrap_or_else(|_| "your-yellowstone-endpoint".to_string()),
+            yellowstone_auth_token: env::var("YELLOWSTONE_AUTH_TOKEN")
+                .unwrap_or_else(|_| "your-yellowstone-auth-token".to_string()),
+
             // Webhook configuration
             webhook_base_url: env::var("WEBHOOK_BASE_URL")
                 .unwrap_or_else(|_| "https://your-domain.com/webhooks".to_string()),
@@ -365,7 +377,21 @@ impl Config {
             cache_config: CacheConfig::default(),
             monitoring_config: MonitoringConfig::default(),
             helius_config: HeliusConfig::default(),
-            performance_config: PerformanceConfig::default(),
+            performance_config: PerformanceConfig {
+                enable_rate_limiting: env::var("ENABLE_RATE_LIMITING")
+                    .unwrap_or_else(|_| "true".to_string())
+                    .parse()
+                    .unwrap_or(true),
+                max_requests_per_second: env::var("MAX_REQUESTS_PER_SECOND")
+                    .unwrap_or_else(|_| "1000".to_string())
+                    .parse()
+                    .unwrap_or(1000),
+                max_streams_per_client: env::var("MAX_STREAMS_PER_CLIENT")
+                    .unwrap_or_else(|_| "16".to_string())
+                    .parse()
+                    .unwrap_or(16),
+                ..PerformanceConfig::default()
+            },
             database_config: DatabaseConfig {
                 database_url: env::var("DATABASE_URL")
                     .unwrap_or_else(|_| "sqlite:./solana_indexer.db".to_string()),
@@ -394,4 +420,45 @@ impl Config {
             None
         }
     }
+
+    /// Get the base URL outbound webhook events are posted under, if configured
+    pub fn get_webhook_base_url(&self) -> Option<&str> {
+        if !self.webhook_base_url.is_empty() && self.webhook_base_url != "https://your-domain.com/webhooks" {
+            Some(&self.webhook_base_url)
+        } else {
+            None
+        }
+    }
+
+    /// Get the API key accepted by the servers, if configured
+    pub fn get_api_key(&self) -> Option<&str> {
+        if !self.api_key.is_empty() && self.api_key != "your-secure-api-key" {
+            Some(&self.api_key)
+        } else {
+            None
+        }
+    }
+
+    /// Get the secret used to verify client JWTs, if configured
+    pub fn get_jwt_secret(&self) -> Option<&str> {
+        if !self.jwt_secret.is_empty() && self.jwt_secret != "your-jwt-secret" {
+            Some(&self.jwt_secret)
+        } else {
+            None
+        }
+    }
+
+    /// Get the Yellowstone endpoint and auth token, if configured
+    pub fn get_yellowstone_credentials(&self) -> Option<(&str, &str)> {
+        if !self.yellowstone_endpoint.is_empty() && self.yellowstone_endpoint != "your-yellowstone-endpoint" {
+            let auth_token = if self.yellowstone_auth_token == "your-yellowstone-auth-token" {
+                ""
+            } else {
+                &self.yellowstone_auth_token
+            };
+            Some((&self.yellowstone_endpoint, auth_token))
+        } else {
+            None
+        }
+    }
 }
\ No newline at end of file
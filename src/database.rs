@@ -1,13 +1,31 @@
 use anyhow::Result;
-use sqlx::{Pool, Sqlite, Row};
+use sqlx::{Pool, Row, Sqlite, SqliteConnection};
+use std::collections::HashSet;
 use std::time::Duration;
 use crate::config::DatabaseConfig;
+use crate::pump_fun::TokenTrade;
+use crate::reorg::ReorgEvent;
+use crate::slot_lifecycle::{LatencyPercentiles, SlotTiming};
+use crate::transaction_decoder::{self, BalanceChange, DecodedInstruction, DecodedTransaction, TokenBalanceChange};
 use tracing::{info, error, debug, warn};
 use colored::*;
 use chrono::{DateTime, Utc};
 use solana_client::rpc_client::RpcClient;
 use solana_transaction_status::{UiTransactionEncoding, TransactionDetails};
 
+/// Blockhash of slot rows inserted only to satisfy foreign keys
+pub const PENDING_BLOCKHASH: &str = "pending_blockhash";
+/// Blockhash of the slot rows the wallet tracker seeds at startup
+pub const INITIAL_BLOCKHASH: &str = "initial_blockhash";
+/// Blockhash of slots stored after getBlock failed
+pub const UNKNOWN_BLOCKHASH: &str = "unknown_blockhash";
+
+/// Whether a stored blockhash is a placeholder rather than the block's own;
+/// such rows also carry a guessed parent slot
+pub fn is_placeholder_blockhash(blockhash: &str) -> bool {
+    blockhash.is_empty() || [PENDING_BLOCKHASH, INITIAL_BLOCKHASH, UNKNOWN_BLOCKHASH].contains(&blockhash)
+}
+
 pub struct Database {
     pool: Pool<Sqlite>,
 }
@@ -44,13 +62,81 @@ impl Database {
 
     pub async fn insert_slot(&self, slot: u64, blockhash: &str, parent_slot: u64, finalized: bool, timestamp: DateTime<Utc>) -> Result<()> {
         debug!("Inserting slot {} into database", slot);
+        Self::insert_slot_with(&self.pool, slot, blockhash, parent_slot, finalized, finalized, timestamp).await
+    }
+
+    async fn insert_slot_with<'e, E>(executor: E, slot: u64, blockhash: &str, parent_slot: u64, confirmed: bool, finalized: bool, timestamp: DateTime<Utc>) -> Result<()>
+    where
+        E: sqlx::Executor<'e, Database = Sqlite>,
+    {
+        // Never downgrade a commitment or overwrite a real block with a placeholder
+        let placeholders = [PENDING_BLOCKHASH, INITIAL_BLOCKHASH, UNKNOWN_BLOCKHASH]
+            .map(|blockhash| format!("'{}'", blockhash))
+            .join(", ");
+        let replaces_block = format!("excluded.blockhash NOT IN ('', {})", placeholders);
+        let sql = format!(
+            "INSERT INTO slots (slot, blockhash, parent_slot, confirmed, finalized, timestamp) VALUES (?, ?, ?, ?, ?, ?)
+             ON CONFLICT(slot) DO UPDATE SET
+                blockhash = CASE WHEN {0} THEN excluded.blockhash ELSE slots.blockhash END,
+                parent_slot = CASE WHEN {0} THEN excluded.parent_slot ELSE slots.parent_slot END,
+                timestamp = CASE WHEN {0} THEN excluded.timestamp ELSE slots.timestamp END,
+                confirmed = slots.confirmed OR excluded.confirmed,
+                finalized = slots.finalized OR excluded.finalized",
+            replaces_block
+        );
+
+        sqlx::query(&sql)
+        .bind(slot as i64)
+        .bind(blockhash)
+        .bind(parent_slot as i64)
+        .bind(confirmed || finalized)
+        .bind(finalized)
+        .bind(timestamp)
+        .execute(executor)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Record that a slot reached a commitment level, never downgrading one already stored.
+    /// New rows are placeholders with parent slot 0 until the block itself is stored.
+    pub async fn upsert_slot_commitment(&self, slot: u64, confirmed: bool, finalized: bool, timestamp: DateTime<Utc>) -> Result<()> {
+        debug!("Upserting commitment for slot {} into database", slot);
 
         sqlx::query(
-            "INSERT OR REPLACE INTO slots (slot, blockhash, parent_slot, finalized, timestamp) VALUES (?, ?, ?, ?, ?)"
+            "INSERT INTO slots (slot, blockhash, parent_slot, confirmed, finalized, timestamp) VALUES (?, ?, 0, ?, ?, ?)
+             ON CONFLICT(slot) DO UPDATE SET
+                confirmed = slots.confirmed OR excluded.confirmed,
+                finalized = slots.finalized OR excluded.finalized"
+        )
+        .bind(slot as i64)
+        .bind(PENDING_BLOCKHASH)
+        .bind(confirmed || finalized)
+        .bind(finalized)
+        .bind(timestamp)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Store a slot's block details, keeping any higher commitment already recorded
+    pub async fn upsert_slot_block(&self, slot: u64, blockhash: &str, parent_slot: u64, confirmed: bool, finalized: bool, timestamp: DateTime<Utc>) -> Result<()> {
+        debug!("Upserting block details for slot {} into database", slot);
+
+        sqlx::query(
+            "INSERT INTO slots (slot, blockhash, parent_slot, confirmed, finalized, timestamp) VALUES (?, ?, ?, ?, ?, ?)
+             ON CONFLICT(slot) DO UPDATE SET
+                blockhash = excluded.blockhash,
+                parent_slot = excluded.parent_slot,
+                timestamp = excluded.timestamp,
+                confirmed = slots.confirmed OR excluded.confirmed,
+                finalized = slots.finalized OR excluded.finalized"
         )
         .bind(slot as i64)
         .bind(blockhash)
         .bind(parent_slot as i64)
+        .bind(confirmed || finalized)
         .bind(finalized)
         .bind(timestamp)
         .execute(&self.pool)
@@ -63,7 +149,7 @@ impl Database {
         debug!("Fetching slot {} from database", slot);
 
         let row = sqlx::query(
-            "SELECT slot, blockhash, parent_slot, finalized, timestamp FROM slots WHERE slot = ?"
+            "SELECT slot, blockhash, parent_slot, confirmed, finalized, timestamp FROM slots WHERE slot = ?"
         )
         .bind(slot as i64)
         .fetch_optional(&self.pool)
@@ -74,6 +160,7 @@ impl Database {
                 slot: row.get::<i64, _>("slot") as u64,
                 blockhash: row.get("blockhash"),
                 parent_slot: row.get::<i64, _>("parent_slot") as u64,
+                confirmed: row.get("confirmed"),
                 finalized: row.get("finalized"),
                 timestamp: row.get("timestamp"),
             }))
@@ -86,7 +173,7 @@ impl Database {
         debug!("Fetching {} recent slots from database", limit);
 
         let rows = sqlx::query(
-            "SELECT slot, blockhash, parent_slot, finalized, timestamp FROM slots ORDER BY slot DESC LIMIT ?"
+            "SELECT slot, blockhash, parent_slot, confirmed, finalized, timestamp FROM slots ORDER BY slot DESC LIMIT ?"
         )
         .bind(limit as i64)
         .fetch_all(&self.pool)
@@ -96,6 +183,7 @@ impl Database {
             slot: row.get::<i64, _>("slot") as u64,
             blockhash: row.get("blockhash"),
             parent_slot: row.get::<i64, _>("parent_slot") as u64,
+            confirmed: row.get("confirmed"),
             finalized: row.get("finalized"),
             timestamp: row.get("timestamp"),
         }).collect();
@@ -107,8 +195,32 @@ impl Database {
         debug!("Fetching {} finalized slots from database", limit);
 
         let rows = sqlx::query(
-            "SELECT slot, blockhash, parent_slot, finalized, timestamp FROM slots WHERE finalized = 1 ORDER BY slot DESC LIMIT ?"
+            "SELECT slot, blockhash, parent_slot, confirmed, finalized, timestamp FROM slots WHERE finalized = 1 ORDER BY slot DESC LIMIT ?"
+        )
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let slots = rows.into_iter().map(|row| SlotData {
+            slot: row.get::<i64, _>("slot") as u64,
+            blockhash: row.get("blockhash"),
+            parent_slot: row.get::<i64, _>("parent_slot") as u64,
+            confirmed: row.get("confirmed"),
+            finalized: row.get("finalized"),
+            timestamp: row.get("timestamp"),
+        }).collect();
+
+        Ok(slots)
+    }
+
+    /// Slots at or after `from_slot` in ascending order, for stream replay
+    pub async fn get_slots_from(&self, from_slot: u64, limit: u64) -> Result<Vec<SlotData>> {
+        debug!("Fetching {} slots from slot {} from database", limit, from_slot);
+
+        let rows = sqlx::query(
+            "SELECT slot, blockhash, parent_slot, confirmed, finalized, timestamp FROM slots WHERE slot >= ? ORDER BY slot ASC LIMIT ?"
         )
+        .bind(from_slot as i64)
         .bind(limit as i64)
         .fetch_all(&self.pool)
         .await?;
@@ -117,6 +229,7 @@ impl Database {
             slot: row.get::<i64, _>("slot") as u64,
             blockhash: row.get("blockhash"),
             parent_slot: row.get::<i64, _>("parent_slot") as u64,
+            confirmed: row.get("confirmed"),
             finalized: row.get("finalized"),
             timestamp: row.get("timestamp"),
         }).collect();
@@ -127,11 +240,19 @@ impl Database {
     // Transaction operations
     pub async fn insert_transaction(&self, signature: &str, slot: u64, fee: u64, status: &str, program_ids: &[String], timestamp: DateTime<Utc>) -> Result<()> {
         debug!("Inserting transaction {} into database", signature);
+        Self::insert_transaction_with(&self.pool, signature, slot, fee, status, program_ids, timestamp).await
+    }
 
+    async fn insert_transaction_with<'e, E>(executor: E, signature: &str, slot: u64, fee: u64, status: &str, program_ids: &[String], timestamp: DateTime<Utc>) -> Result<()>
+    where
+        E: sqlx::Executor<'e, Database = Sqlite>,
+    {
         let program_ids_json = serde_json::to_string(program_ids)?;
 
         sqlx::query(
-            "INSERT OR REPLACE INTO transactions (signature, slot, fee, status, program_ids, timestamp) VALUES (?, ?, ?, ?, ?, ?)"
+            "INSERT INTO transactions (signature, slot, fee, status, program_ids, timestamp) VALUES (?, ?, ?, ?, ?, ?)
+             ON CONFLICT(signature) DO UPDATE SET slot = excluded.slot, fee = excluded.fee, status = excluded.status,
+             program_ids = excluded.program_ids, timestamp = excluded.timestamp"
         )
         .bind(signature)
         .bind(slot as i64)
@@ -139,7 +260,7 @@ impl Database {
         .bind(status)
         .bind(program_ids_json)
         .bind(timestamp)
-        .execute(&self.pool)
+        .execute(executor)
         .await?;
 
         Ok(())
@@ -149,7 +270,7 @@ impl Database {
         debug!("Fetching transaction {} from database", signature);
 
         let row = sqlx::query(
-            "SELECT signature, slot, fee, status, program_ids, timestamp FROM transactions WHERE signature = ?"
+            "SELECT signature, slot, fee, status, program_ids, timestamp, signer, compute_units, priority_fee, recent_blockhash, error FROM transactions WHERE signature = ?"
         )
         .bind(signature)
         .fetch_optional(&self.pool)
@@ -158,14 +279,7 @@ impl Database {
         if let Some(row) = row {
             let program_ids_str: String = row.get("program_ids");
             let program_ids: Vec<String> = serde_json::from_str(&program_ids_str)?;
-            Ok(Some(TransactionData {
-                signature: row.get("signature"),
-                slot: row.get::<i64, _>("slot") as u64,
-                fee: row.get::<i64, _>("fee") as u64,
-                status: row.get("status"),
-                program_ids,
-                timestamp: row.get("timestamp"),
-            }))
+            Ok(Some(TransactionData::from_row(&row, program_ids)))
         } else {
             Ok(None)
         }
@@ -175,7 +289,7 @@ impl Database {
         debug!("Fetching transactions for slot {} from database", slot);
 
         let rows = sqlx::query(
-            "SELECT signature, slot, fee, status, program_ids, timestamp FROM transactions WHERE slot = ?"
+            "SELECT signature, slot, fee, status, program_ids, timestamp, signer, compute_units, priority_fee, recent_blockhash, error FROM transactions WHERE slot = ?"
         )
         .bind(slot as i64)
         .fetch_all(&self.pool)
@@ -184,35 +298,370 @@ impl Database {
         let transactions = rows.into_iter().map(|row| {
             let program_ids_str: String = row.get("program_ids");
             let program_ids: Vec<String> = serde_json::from_str(&program_ids_str).unwrap_or_default();
-            TransactionData {
-                signature: row.get("signature"),
-                slot: row.get::<i64, _>("slot") as u64,
-                fee: row.get::<i64, _>("fee") as u64,
-                status: row.get("status"),
-                program_ids,
-                timestamp: row.get("timestamp"),
+            TransactionData::from_row(&row, program_ids)
+        }).collect();
+
+        Ok(transactions)
+    }
+
+    /// Transactions after `(slot, signature)` in that order, for stream replay,
+    /// each with its account keys: the stored static and lookup-table keys of
+    /// decoded transactions, otherwise its programs and instruction accounts
+    pub async fn get_transactions_after(&self, slot: u64, signature: &str, finalized_only: bool, limit: u64) -> Result<Vec<(TransactionData, Vec<String>)>> {
+        debug!("Fetching {} transactions after {}:{} from database", limit, slot, signature);
+
+        let rows = sqlx::query(
+            "SELECT t.signature, t.slot, t.fee, t.status, t.program_ids, t.timestamp, t.signer, t.compute_units,
+                t.priority_fee, t.recent_blockhash, t.error,
+                (SELECT json_group_array(address ORDER BY account_index) FROM transaction_accounts a
+                    WHERE a.signature = t.signature) AS account_keys,
+                (SELECT json_group_array(DISTINCT account.value) FROM instructions i, json_each(i.accounts) AS account
+                    WHERE i.signature = t.signature) AS instruction_accounts
+             FROM transactions t JOIN slots s ON s.slot = t.slot
+             WHERE (t.slot > ? OR (t.slot = ? AND t.signature > ?)) AND (? = 0 OR s.finalized)
+             ORDER BY t.slot, t.signature
+             LIMIT ?"
+        )
+        .bind(slot as i64)
+        .bind(slot as i64)
+        .bind(signature)
+        .bind(finalized_only)
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let transactions = rows.into_iter().map(|row| {
+            let json_list = |column: &str| serde_json::from_str::<Vec<String>>(&row.get::<String, _>(column)).unwrap_or_default();
+            let program_ids = json_list("program_ids");
+            let mut account_keys = json_list("account_keys");
+            if account_keys.is_empty() {
+                account_keys = program_ids.clone();
+                for account in json_list("instruction_accounts") {
+                    if !account_keys.contains(&account) {
+                        account_keys.push(account);
+                    }
+                }
             }
+            (TransactionData::from_row(&row, program_ids), account_keys)
         }).collect();
 
         Ok(transactions)
     }
 
+    /// Store a decoded transaction with its accounts, balance changes and
+    /// logs, replacing any earlier copy
+    pub async fn store_decoded_transaction(&self, decoded: &DecodedTransaction) -> Result<()> {
+        debug!("Storing decoded transaction {}", decoded.signature);
+
+        let mut tx = self.pool.begin().await?;
+        let timestamp = decoded.timestamp();
+
+        // Make sure the slot exists for the foreign key, without touching a stored block
+        sqlx::query(
+            "INSERT OR IGNORE INTO slots (slot, blockhash, parent_slot, finalized, timestamp) VALUES (?, ?, ?, ?, ?)"
+        )
+        .bind(decoded.slot as i64)
+        .bind(PENDING_BLOCKHASH)
+        .bind(decoded.slot.saturating_sub(1) as i64)
+        .bind(false)
+        .bind(timestamp)
+        .execute(&mut *tx)
+        .await?;
+
+        for statement in [
+            "DELETE FROM transaction_accounts WHERE signature = ?",
+            "DELETE FROM transaction_balance_changes WHERE signature = ?",
+            "DELETE FROM transaction_token_balances WHERE signature = ?",
+            "DELETE FROM transaction_logs WHERE signature = ?",
+        ] {
+            sqlx::query(statement).bind(&decoded.signature).execute(&mut *tx).await?;
+        }
+
+        sqlx::query(
+            "INSERT OR REPLACE INTO transactions (signature, slot, fee, status, program_ids, timestamp, signer, compute_units, priority_fee, recent_blockhash, error) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
+        )
+        .bind(&decoded.signature)
+        .bind(decoded.slot as i64)
+        .bind(decoded.fee as i64)
+        .bind(decoded.status())
+        .bind(serde_json::to_string(&decoded.program_ids())?)
+        .bind(timestamp)
+        .bind(&decoded.signer)
+        .bind(decoded.compute_units.map(|units| units as i64))
+        .bind(decoded.priority_fee as i64)
+        .bind(&decoded.recent_blockhash)
+        .bind(&decoded.error)
+        .execute(&mut *tx)
+        .await?;
+
+        for (index, account) in decoded.accounts.iter().enumerate() {
+            sqlx::query(
+                "INSERT INTO transaction_accounts (signature, account_index, address, signer, writable, from_lookup_table) VALUES (?, ?, ?, ?, ?, ?)"
+            )
+            .bind(&decoded.signature)
+            .bind(index as i64)
+            .bind(&account.address)
+            .bind(account.signer)
+            .bind(account.writable)
+            .bind(account.from_lookup_table)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        for change in &decoded.balance_changes {
+            sqlx::query(
+                "INSERT INTO transaction_balance_changes (signature, account_index, address, pre_lamports, post_lamports) VALUES (?, ?, ?, ?, ?)"
+            )
+            .bind(&decoded.signature)
+            .bind(change.account_index as i64)
+            .bind(&change.address)
+            .bind(change.pre_lamports as i64)
+            .bind(change.post_lamports as i64)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        for change in &decoded.token_balance_changes {
+            sqlx::query(
+                "INSERT INTO transaction_token_balances (signature, account_index, address, mint, owner, token_program, decimals, pre_amount, post_amount) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)"
+            )
+            .bind(&decoded.signature)
+            .bind(change.account_index as i64)
+            .bind(&change.address)
+            .bind(&change.mint)
+            .bind(&change.owner)
+            .bind(&change.token_program)
+            .bind(change.decimals as i64)
+            .bind(change.pre_amount.to_string())
+            .bind(change.post_amount.to_string())
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        for (index, message) in decoded.log_messages.iter().enumerate() {
+            sqlx::query("INSERT INTO transaction_logs (signature, log_index, message) VALUES (?, ?, ?)")
+                .bind(&decoded.signature)
+                .bind(index as i64)
+                .bind(message)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        Self::replace_instructions(&mut *tx, &decoded.signature, decoded.slot, &decoded.instructions).await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn replace_instructions(conn: &mut SqliteConnection, signature: &str, slot: u64, instructions: &[DecodedInstruction]) -> Result<()> {
+        sqlx::query("DELETE FROM instructions WHERE signature = ?")
+            .bind(signature)
+            .execute(&mut *conn)
+            .await?;
+
+        for instruction in instructions {
+            sqlx::query(
+                "INSERT INTO instructions (signature, slot, instruction_index, inner_index, program_id, parsed_type, accounts, data, parsed) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)"
+            )
+            .bind(signature)
+            .bind(slot as i64)
+            .bind(instruction.index as i64)
+            .bind(instruction.inner_index.map(|index| index as i64))
+            .bind(&instruction.program_id)
+            .bind(&instruction.parsed_type)
+            .bind(serde_json::to_string(&instruction.accounts)?)
+            .bind(&instruction.data)
+            .bind(instruction.parsed.as_ref().map(|parsed| parsed.to_string()))
+            .execute(&mut *conn)
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Stored instructions matching `query`, newest first
+    pub async fn get_instructions(&self, query: &InstructionQuery) -> Result<Vec<InstructionData>> {
+        debug!("Fetching instructions matching {:?}", query);
+
+        let rows = sqlx::query(
+            "SELECT signature, slot, instruction_index, inner_index, program_id, parsed_type, accounts, data, parsed
+             FROM instructions
+             WHERE (?1 IS NULL OR program_id = ?1)
+               AND (?2 IS NULL OR parsed_type = ?2)
+               AND (?3 IS NULL OR EXISTS (SELECT 1 FROM json_each(instructions.accounts) WHERE json_each.value = ?3))
+               AND (?4 IS NULL OR slot >= ?4)
+               AND (?5 IS NULL OR slot <= ?5)
+             ORDER BY slot DESC, signature, instruction_index, inner_index
+             LIMIT ?6"
+        )
+        .bind(&query.program_id)
+        .bind(&query.parsed_type)
+        .bind(&query.account)
+        .bind(query.from_slot.map(|slot| slot as i64))
+        .bind(query.to_slot.map(|slot| slot as i64))
+        .bind(query.limit as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(|row| InstructionData {
+            signature: row.get("signature"),
+            slot: row.get::<i64, _>("slot") as u64,
+            instruction_index: row.get::<i64, _>("instruction_index") as u32,
+            inner_index: row.get::<Option<i64>, _>("inner_index").map(|index| index as u32),
+            program_id: row.get("program_id"),
+            parsed_type: row.get("parsed_type"),
+            accounts: serde_json::from_str(&row.get::<String, _>("accounts")).unwrap_or_default(),
+            data: row.get("data"),
+            parsed: row.get("parsed"),
+        }).collect())
+    }
+
+    pub async fn get_balance_changes(&self, signature: &str) -> Result<Vec<BalanceChange>> {
+        let rows = sqlx::query(
+            "SELECT account_index, address, pre_lamports, post_lamports FROM transaction_balance_changes WHERE signature = ? ORDER BY account_index"
+        )
+        .bind(signature)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(|row| BalanceChange {
+            account_index: row.get::<i64, _>("account_index") as u32,
+            address: row.get("address"),
+            pre_lamports: row.get::<i64, _>("pre_lamports") as u64,
+            post_lamports: row.get::<i64, _>("post_lamports") as u64,
+        }).collect())
+    }
+
+    pub async fn get_token_balance_changes(&self, signature: &str) -> Result<Vec<TokenBalanceChange>> {
+        let rows = sqlx::query(
+            "SELECT account_index, address, mint, owner, token_program, decimals, pre_amount, post_amount FROM transaction_token_balances WHERE signature = ? ORDER BY account_index"
+        )
+        .bind(signature)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(|row| TokenBalanceChange {
+            account_index: row.get::<i64, _>("account_index") as u32,
+            address: row.get("address"),
+            mint: row.get("mint"),
+            owner: row.get("owner"),
+            token_program: row.get("token_program"),
+            decimals: row.get::<i64, _>("decimals") as u8,
+            pre_amount: row.get::<String, _>("pre_amount").parse().unwrap_or_default(),
+            post_amount: row.get::<String, _>("post_amount").parse().unwrap_or_default(),
+        }).collect())
+    }
+
+    pub async fn get_transaction_logs(&self, signature: &str) -> Result<Vec<String>> {
+        let rows = sqlx::query("SELECT message FROM transaction_logs WHERE signature = ? ORDER BY log_index")
+            .bind(signature)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(rows.iter().map(|row| row.get("message")).collect())
+    }
+
+    // Account operations
+    pub async fn get_account(&self, address: &str) -> Result<Option<AccountData>> {
+        debug!("Fetching account {} from database", address);
+
+        let row = sqlx::query(
+            "SELECT address, lamports, owner, executable, slot, data_size, updated_at FROM accounts WHERE address = ?"
+        )
+        .bind(address)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(|row| AccountData {
+            address: row.get("address"),
+            lamports: row.get::<i64, _>("lamports") as u64,
+            owner: row.get("owner"),
+            executable: row.get("executable"),
+            slot: row.get::<i64, _>("slot") as u64,
+            data_size: row.get::<Option<i64>, _>("data_size").unwrap_or(0) as u64,
+            updated_at: row.get("updated_at"),
+        }))
+    }
+
     // Leader operations
+    /// Store the leader of `slot`, adding a placeholder slot row in the same
+    /// transaction when the slot itself isn't stored (yet), e.g. skipped slots
     pub async fn insert_slot_leader(&self, slot: u64, leader_pubkey: &str, validator_name: Option<&str>) -> Result<()> {
         debug!("Inserting slot leader for slot {} into database", slot);
 
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query(
+            "INSERT OR IGNORE INTO slots (slot, blockhash, parent_slot, finalized, timestamp) VALUES (?, ?, 0, ?, ?)"
+        )
+        .bind(slot as i64)
+        .bind(PENDING_BLOCKHASH)
+        .bind(false)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        Self::insert_slot_leader_with(&mut *tx, slot, leader_pubkey, validator_name).await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn insert_slot_leader_with<'e, E>(executor: E, slot: u64, leader_pubkey: &str, validator_name: Option<&str>) -> Result<()>
+    where
+        E: sqlx::Executor<'e, Database = Sqlite>,
+    {
         sqlx::query(
             "INSERT OR REPLACE INTO slot_leaders (slot, leader_pubkey, validator_name) VALUES (?, ?, ?)"
         )
         .bind(slot as i64)
         .bind(leader_pubkey)
         .bind(validator_name)
-        .execute(&self.pool)
+        .execute(executor)
         .await?;
 
         Ok(())
     }
 
+    /// Store tracked slots with their leaders and transactions, and mark
+    /// `finalized_slots` as finalized, all in one transaction
+    pub async fn save_tracked_slots(&self, slots: &[TrackedSlot], finalized_slots: &[u64]) -> Result<()> {
+        debug!("Saving {} tracked slots, finalizing {}", slots.len(), finalized_slots.len());
+
+        let mut tx = self.pool.begin().await?;
+
+        for tracked in slots {
+            Self::insert_slot_with(&mut *tx, tracked.slot, &tracked.blockhash, tracked.parent_slot, true, tracked.finalized, tracked.timestamp).await?;
+
+            if let Some(leader) = &tracked.leader {
+                Self::insert_slot_leader_with(&mut *tx, tracked.slot, leader, None).await?;
+            }
+
+            for transaction in &tracked.transactions {
+                Self::insert_transaction_with(
+                    &mut *tx,
+                    &transaction.signature,
+                    tracked.slot,
+                    transaction.fee,
+                    transaction.status,
+                    &transaction.program_ids,
+                    tracked.timestamp,
+                ).await?;
+                Self::replace_instructions(&mut *tx, &transaction.signature, tracked.slot, &transaction.instructions).await?;
+            }
+        }
+
+        for slot in finalized_slots {
+            sqlx::query("UPDATE slots SET confirmed = 1, finalized = 1 WHERE slot = ?")
+                .bind(*slot as i64)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
     pub async fn get_slot_leader(&self, slot: u64) -> Result<Option<SlotLeaderData>> {
         debug!("Fetching leader for slot {} from database", slot);
 
@@ -234,6 +683,311 @@ impl Database {
         }
     }
 
+    /// Slot leaders at or after `from_slot` in ascending order, for stream replay
+    pub async fn get_slot_leaders_from(&self, from_slot: u64, limit: u64) -> Result<Vec<SlotLeaderData>> {
+        debug!("Fetching {} slot leaders from slot {} from database", limit, from_slot);
+
+        let rows = sqlx::query(
+            "SELECT slot, leader_pubkey, validator_name FROM slot_leaders WHERE slot >= ? ORDER BY slot ASC LIMIT ?"
+        )
+        .bind(from_slot as i64)
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let leaders = rows.into_iter().map(|row| SlotLeaderData {
+            slot: row.get::<i64, _>("slot") as u64,
+            leader_pubkey: row.get("leader_pubkey"),
+            validator_name: row.get("validator_name"),
+        }).collect();
+
+        Ok(leaders)
+    }
+
+    // Slot production operations
+    /// Record whether the scheduled leader produced a block for `slot`
+    pub async fn record_slot_production(&self, slot: u64, leader_pubkey: &str, skipped: bool) -> Result<()> {
+        debug!("Recording slot {} as {} by {}", slot, if skipped { "skipped" } else { "produced" }, leader_pubkey);
+
+        sqlx::query(
+            "INSERT OR REPLACE INTO slot_production (slot, leader_pubkey, skipped, recorded_at) VALUES (?, ?, ?, ?)"
+        )
+        .bind(slot as i64)
+        .bind(leader_pubkey)
+        .bind(skipped)
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Slots in `from_slot..=to_slot` whose block is already stored; placeholder
+    /// rows don't count, their blocks were never fetched
+    pub async fn get_indexed_slots(&self, from_slot: u64, to_slot: u64) -> Result<HashSet<u64>> {
+        let rows = sqlx::query("SELECT slot FROM slots WHERE slot BETWEEN ? AND ? AND blockhash NOT IN ('', ?, ?, ?)")
+            .bind(from_slot as i64)
+            .bind(to_slot as i64)
+            .bind(PENDING_BLOCKHASH)
+            .bind(INITIAL_BLOCKHASH)
+            .bind(UNKNOWN_BLOCKHASH)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(rows.iter().map(|row| row.get::<i64, _>("slot") as u64).collect())
+    }
+
+    /// First slot still to do for an interrupted backfill of `from_slot..=to_slot`
+    pub async fn get_backfill_checkpoint(&self, from_slot: u64, to_slot: u64) -> Result<Option<u64>> {
+        let row = sqlx::query("SELECT next_slot FROM backfill_checkpoints WHERE from_slot = ? AND to_slot = ?")
+            .bind(from_slot as i64)
+            .bind(to_slot as i64)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(row.map(|row| row.get::<i64, _>("next_slot") as u64))
+    }
+
+    pub async fn save_backfill_checkpoint(&self, from_slot: u64, to_slot: u64, next_slot: u64) -> Result<()> {
+        debug!("Backfill {}..={} checkpointed at slot {}", from_slot, to_slot, next_slot);
+
+        sqlx::query(
+            "INSERT OR REPLACE INTO backfill_checkpoints (from_slot, to_slot, next_slot, updated_at) VALUES (?, ?, ?, ?)"
+        )
+        .bind(from_slot as i64)
+        .bind(to_slot as i64)
+        .bind(next_slot as i64)
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn clear_backfill_checkpoint(&self, from_slot: u64, to_slot: u64) -> Result<()> {
+        sqlx::query("DELETE FROM backfill_checkpoints WHERE from_slot = ? AND to_slot = ?")
+            .bind(from_slot as i64)
+            .bind(to_slot as i64)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Record `wallet`'s balances as of `slot`, one row per mint
+    pub async fn save_wallet_balances(&self, wallet: &str, slot: u64, timestamp: DateTime<Utc>, balances: &[WalletBalance]) -> Result<()> {
+        if balances.is_empty() {
+            return Ok(());
+        }
+        debug!("Saving {} balances for {} at slot {}", balances.len(), wallet, slot);
+
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query(
+            "INSERT OR IGNORE INTO slots (slot, blockhash, parent_slot, finalized, timestamp) VALUES (?, ?, ?, ?, ?)"
+        )
+        .bind(slot as i64)
+        .bind(PENDING_BLOCKHASH)
+        .bind(slot.saturating_sub(1) as i64)
+        .bind(false)
+        .bind(timestamp)
+        .execute(&mut *tx)
+        .await?;
+
+        for balance in balances {
+            sqlx::query(
+                "INSERT INTO wallet_balances (wallet_address, token_mint, token_symbol, balance, timestamp, slot) VALUES (?, ?, ?, ?, ?, ?)"
+            )
+            .bind(wallet)
+            .bind(balance.token_mint.as_deref())
+            .bind(balance.token_symbol.as_deref())
+            .bind(balance.balance)
+            .bind(timestamp)
+            .bind(slot as i64)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Store pump.fun trades, skipping ones already recorded
+    pub async fn insert_token_trades(&self, trades: &[TokenTrade]) -> Result<()> {
+        if trades.is_empty() {
+            return Ok(());
+        }
+        debug!("Inserting {} token trades", trades.len());
+
+        let mut tx = self.pool.begin().await?;
+
+        for trade in trades {
+            sqlx::query(
+                "INSERT OR IGNORE INTO token_trades (signature, slot, trade_index, platform, action, mint, trader, bonding_curve, sol_amount, token_amount, virtual_sol_reserves, virtual_token_reserves, real_sol_reserves, real_token_reserves, name, symbol, uri, timestamp) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
+            )
+            .bind(&trade.signature)
+            .bind(trade.slot as i64)
+            .bind(trade.trade_index as i64)
+            .bind("pump.fun")
+            .bind(trade.action.as_str())
+            .bind(&trade.mint)
+            .bind(&trade.trader)
+            .bind(trade.bonding_curve.as_deref())
+            .bind(trade.sol_amount.map(|amount| amount as i64))
+            .bind(trade.token_amount.map(|amount| amount as i64))
+            .bind(trade.curve.map(|curve| curve.virtual_sol_reserves as i64))
+            .bind(trade.curve.map(|curve| curve.virtual_token_reserves as i64))
+            .bind(trade.curve.and_then(|curve| curve.real_sol_reserves).map(|reserves| reserves as i64))
+            .bind(trade.curve.and_then(|curve| curve.real_token_reserves).map(|reserves| reserves as i64))
+            .bind(trade.name.as_deref())
+            .bind(trade.symbol.as_deref())
+            .bind(trade.uri.as_deref())
+            .bind(trade.timestamp)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Per-validator skip rates over the last `window_slots` recorded slots, worst first
+    pub async fn get_validator_skip_rates(&self, window_slots: u64, limit: u64) -> Result<Vec<ValidatorSkipRate>> {
+        debug!("Fetching validator skip rates over the last {} slots", window_slots);
+
+        let rows = sqlx::query(
+            "SELECT leader_pubkey, COUNT(*) AS assigned, SUM(CASE WHEN skipped THEN 1 ELSE 0 END) AS skipped
+             FROM slot_production
+             WHERE slot > (SELECT COALESCE(MAX(slot), 0) FROM slot_production) - ?
+             GROUP BY leader_pubkey
+             ORDER BY CAST(skipped AS REAL) / COUNT(*) DESC, assigned DESC
+             LIMIT ?"
+        )
+        .bind(window_slots as i64)
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let rates = rows.into_iter().map(|row| {
+            ValidatorSkipRate::new(
+                row.get("leader_pubkey"),
+                row.get::<i64, _>("assigned") as u64,
+                row.get::<i64, _>("skipped") as u64,
+            )
+        }).collect();
+
+        Ok(rates)
+    }
+
+    // Slot lifecycle operations
+    /// Store a slot's commitment timings, keeping transitions already recorded
+    pub async fn upsert_slot_timing(&self, timing: &SlotTiming) -> Result<()> {
+        debug!("Upserting timing for slot {}", timing.slot);
+
+        sqlx::query(
+            "INSERT INTO slot_timings (slot, first_seen_at, confirmed_at, finalized_at, confirmation_ms, finalization_ms) VALUES (?, ?, ?, ?, ?, ?)
+             ON CONFLICT(slot) DO UPDATE SET
+                confirmed_at = COALESCE(slot_timings.confirmed_at, excluded.confirmed_at),
+                finalized_at = COALESCE(slot_timings.finalized_at, excluded.finalized_at),
+                confirmation_ms = COALESCE(slot_timings.confirmation_ms, excluded.confirmation_ms),
+                finalization_ms = COALESCE(slot_timings.finalization_ms, excluded.finalization_ms)"
+        )
+        .bind(timing.slot as i64)
+        .bind(timing.first_seen_at)
+        .bind(timing.confirmed_at)
+        .bind(timing.finalized_at)
+        .bind(timing.confirmation_ms().map(|ms| ms as i64))
+        .bind(timing.finalization_ms().map(|ms| ms as i64))
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Confirmation and finalization latency percentiles over the last `window_slots` timed slots
+    pub async fn get_commitment_latencies(&self, window_slots: u64) -> Result<(LatencyPercentiles, LatencyPercentiles)> {
+        debug!("Fetching commitment latencies over the last {} slots", window_slots);
+
+        let rows = sqlx::query(
+            "SELECT confirmation_ms, finalization_ms FROM slot_timings
+             WHERE slot > (SELECT COALESCE(MAX(slot), 0) FROM slot_timings) - ?"
+        )
+        .bind(window_slots as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut confirmation = Vec::with_capacity(rows.len());
+        let mut finalization = Vec::with_capacity(rows.len());
+        for row in rows {
+            if let Some(ms) = row.get::<Option<i64>, _>("confirmation_ms") {
+                confirmation.push(ms as u64);
+            }
+            if let Some(ms) = row.get::<Option<i64>, _>("finalization_ms") {
+                finalization.push(ms as u64);
+            }
+        }
+
+        Ok((LatencyPercentiles::from_samples(confirmation), LatencyPercentiles::from_samples(finalization)))
+    }
+
+    // Reorg operations
+    /// Delete every row indexed for the event's orphaned slots and record the
+    /// reorg, all in one transaction. Returns the number of rows removed.
+    pub async fn rollback_orphaned_slots(&self, event: &ReorgEvent) -> Result<u64> {
+        debug!("Rolling back {} orphaned slots: {:?}", event.depth, event.orphaned_slots);
+
+        let mut tx = self.pool.begin().await?;
+        let mut rows = 0;
+
+        for slot in &event.orphaned_slots {
+            for statement in [
+                "DELETE FROM account_activities WHERE block_slot = ?",
+                "DELETE FROM wallet_activities WHERE block_slot = ?",
+                "DELETE FROM wallet_balances WHERE slot = ?",
+                "DELETE FROM transaction_accounts WHERE signature IN (SELECT signature FROM transactions WHERE slot = ?)",
+                "DELETE FROM transaction_balance_changes WHERE signature IN (SELECT signature FROM transactions WHERE slot = ?)",
+                "DELETE FROM transaction_token_balances WHERE signature IN (SELECT signature FROM transactions WHERE slot = ?)",
+                "DELETE FROM transaction_logs WHERE signature IN (SELECT signature FROM transactions WHERE slot = ?)",
+                "DELETE FROM instructions WHERE slot = ?",
+                "DELETE FROM token_trades WHERE slot = ?",
+                "DELETE FROM transactions WHERE slot = ?",
+                // The schedule doesn't depend on the fork, but the row references the slot
+                "DELETE FROM slot_leaders WHERE slot = ?",
+                // Orphaned slots would otherwise skew skip rates and commitment latencies
+                "DELETE FROM slot_production WHERE slot = ?",
+                "DELETE FROM slot_timings WHERE slot = ?",
+                "DELETE FROM slots WHERE slot = ?",
+            ] {
+                rows += sqlx::query(statement)
+                    .bind(*slot as i64)
+                    .execute(&mut *tx)
+                    .await?
+                    .rows_affected();
+            }
+        }
+
+        sqlx::query(
+            "INSERT INTO reorgs (slot, fork_slot, depth, orphaned_slots, rows_rolled_back, detected_at) VALUES (?, ?, ?, ?, ?, ?)"
+        )
+        .bind(event.slot as i64)
+        .bind(event.fork_slot as i64)
+        .bind(event.depth as i64)
+        .bind(serde_json::to_string(&event.orphaned_slots)?)
+        .bind(rows as i64)
+        .bind(DateTime::from_timestamp(event.detected_at, 0).unwrap_or_else(Utc::now))
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(rows)
+    }
+
+    /// Check the connection without logging, for health and status probes
+    pub async fn ping(&self) -> Result<()> {
+        sqlx::query("SELECT 1").fetch_one(&self.pool).await?;
+        Ok(())
+    }
+
     // Test connection
     pub async fn test_connection(&self) -> Result<()> {
         info!("{}", "🧪 Testing database connection...".bright_cyan());
@@ -314,7 +1068,7 @@ impl Database {
                 // Store with minimal info
                 self.insert_slot(
                     current_slot,
-                    "unknown_blockhash",
+                    UNKNOWN_BLOCKHASH,
                     current_slot.saturating_sub(1),
                     false,
                     Utc::now(),
@@ -375,7 +1129,7 @@ impl Database {
                     // Store with minimal info
                     self.insert_slot(
                         slot_number,
-                        "unknown_blockhash",
+                        UNKNOWN_BLOCKHASH,
                         slot_number.saturating_sub(1),
                         false,
                         Utc::now(),
@@ -419,51 +1173,19 @@ impl Database {
     pub async fn fetch_and_store_transaction(&self, rpc_client: &RpcClient, signature: &str) -> Result<()> {
         info!("{} {}", "🔄 Fetching transaction:".bright_cyan(), signature.bright_blue());
 
-        match rpc_client.get_transaction(
-            &signature.parse()?,
-            UiTransactionEncoding::Json,
-        ) {
-            Ok(transaction) => {
-                let slot = transaction.slot;
-                let fee = transaction.transaction
-                    .meta
-                    .as_ref()
-                    .map(|m| m.fee)
-                    .unwrap_or(0);
-
-                let status = if transaction.transaction
-                    .meta
-                    .as_ref()
-                    .map(|m| m.err.is_none())
-                    .unwrap_or(false) {
-                    "success"
-                } else {
-                    "failed"
-                };
-
-                // Extract program IDs (simplified for now)
-                let program_ids: Vec<String> = vec!["system".to_string()];
-
-                let timestamp = if let Some(block_time) = transaction.block_time {
-                    DateTime::from_timestamp(block_time, 0).unwrap_or_else(|| Utc::now())
-                } else {
-                    Utc::now()
-                };
-
-                self.insert_transaction(
-                    signature,
-                    slot,
-                    fee,
-                    status,
-                    &program_ids,
-                    timestamp,
-                ).await?;
-
-                info!("{} {}", "✅ Stored transaction:".bright_green(), signature.bright_blue());
+        match transaction_decoder::fetch_decoded_transaction(rpc_client, &signature.parse()?) {
+            Ok(decoded) => {
+                self.store_decoded_transaction(&decoded).await?;
+                info!("{} {} ({} instructions, {} balance changes)",
+                    "✅ Stored transaction:".bright_green(),
+                    signature.bright_blue(),
+                    decoded.instructions.len(),
+                    decoded.balance_changes.len() + decoded.token_balance_changes.len()
+                );
             }
             Err(e) => {
                 error!("{} {} - {}", "❌ Error fetching transaction:".bright_red(), signature, e);
-                return Err(e.into());
+                return Err(e);
             }
         }
 
@@ -471,11 +1193,66 @@ impl Database {
     }
 }
 
+/// A slot observed by the tracker, as written by `Database::save_tracked_slots`
+#[derive(Debug, Clone)]
+pub struct TrackedSlot {
+    pub slot: u64,
+    pub blockhash: String,
+    pub parent_slot: u64,
+    pub finalized: bool,
+    pub timestamp: DateTime<Utc>,
+    pub leader: Option<String>,
+    pub transactions: Vec<TrackedTransaction>,
+}
+
+#[derive(Debug, Clone)]
+pub struct TrackedTransaction {
+    pub signature: String,
+    pub fee: u64,
+    pub status: &'static str,
+    pub program_ids: Vec<String>,
+    pub instructions: Vec<DecodedInstruction>,
+}
+
+/// One row of `wallet_balances`; `token_mint` is `None` for SOL
+#[derive(Debug, Clone)]
+pub struct WalletBalance {
+    pub token_mint: Option<String>,
+    pub token_symbol: Option<String>,
+    pub balance: f64,
+}
+
+/// Filters for `Database::get_instructions`; unset fields match everything
+#[derive(Debug, Clone, Default)]
+pub struct InstructionQuery {
+    pub program_id: Option<String>,
+    pub parsed_type: Option<String>,
+    /// Only instructions that reference this account
+    pub account: Option<String>,
+    pub from_slot: Option<u64>,
+    pub to_slot: Option<u64>,
+    pub limit: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct InstructionData {
+    pub signature: String,
+    pub slot: u64,
+    pub instruction_index: u32,
+    pub inner_index: Option<u32>,
+    pub program_id: String,
+    pub parsed_type: Option<String>,
+    pub accounts: Vec<String>,
+    pub data: Option<String>,
+    pub parsed: Option<String>,
+}
+
 #[derive(Debug, Clone)]
 pub struct SlotData {
     pub slot: u64,
     pub blockhash: String,
     pub parent_slot: u64,
+    pub confirmed: bool,
     pub finalized: bool,
     pub timestamp: DateTime<Utc>,
 }
@@ -488,6 +1265,30 @@ pub struct TransactionData {
     pub status: String,
     pub program_ids: Vec<String>,
     pub timestamp: DateTime<Utc>,
+    /// Decoded fields, unset for transactions stored without decoding
+    pub signer: Option<String>,
+    pub compute_units: Option<u64>,
+    pub priority_fee: Option<u64>,
+    pub recent_blockhash: Option<String>,
+    pub error: Option<String>,
+}
+
+impl TransactionData {
+    fn from_row(row: &sqlx::sqlite::SqliteRow, program_ids: Vec<String>) -> Self {
+        Self {
+            signature: row.get("signature"),
+            slot: row.get::<i64, _>("slot") as u64,
+            fee: row.get::<i64, _>("fee") as u64,
+            status: row.get("status"),
+            program_ids,
+            timestamp: row.get("timestamp"),
+            signer: row.get("signer"),
+            compute_units: row.get::<Option<i64>, _>("compute_units").map(|units| units as u64),
+            priority_fee: row.get::<Option<i64>, _>("priority_fee").map(|fee| fee as u64),
+            recent_blockhash: row.get("recent_blockhash"),
+            error: row.get("error"),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -495,4 +1296,37 @@ pub struct SlotLeaderData {
     pub slot: u64,
     pub leader_pubkey: String,
     pub validator_name: Option<String>,
-}
\ No newline at end of file
+}
+
+#[derive(Debug, Clone)]
+pub struct AccountData {
+    pub address: String,
+    pub lamports: u64,
+    pub owner: String,
+    pub executable: bool,
+    pub slot: u64,
+    pub data_size: u64,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ValidatorSkipRate {
+    pub leader_pubkey: String,
+    pub assigned_slots: u64,
+    pub skipped_slots: u64,
+}
+
+impl ValidatorSkipRate {
+    pub fn new(leader_pubkey: String, assigned_slots: u64, skipped_slots: u64) -> Self {
+        Self { leader_pubkey, assigned_slots, skipped_slots }
+    }
+
+    /// Fraction of assigned slots that were skipped
+    pub fn skip_rate(&self) -> f64 {
+        if self.assigned_slots == 0 {
+            0.0
+        } else {
+            self.skipped_slots as f64 / self.assigned_slots as f64
+        }
+    }
+}
@@ -0,0 +1,355 @@
+use colored::*;
+use jsonwebtoken::{decode, Algorithm, DecodingKey, Validation};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tonic::{service::Interceptor, Request, Status};
+use tracing::{debug, warn};
+
+use crate::config::Config;
+
+/// Header carrying the static API key
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// Header carrying a `Bearer <jwt>` token
+const AUTHORIZATION_HEADER: &str = "authorization";
+
+/// Idle clients are forgotten once the usage table grows past this size
+const MAX_TRACKED_CLIENTS: usize = 10_000;
+
+/// How a client proved its identity
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthMethod {
+    ApiKey,
+    Jwt,
+    Anonymous,
+}
+
+/// Identity attached to every authenticated request
+#[derive(Debug, Clone)]
+pub struct ClientIdentity {
+    pub client_id: String,
+    pub method: AuthMethod,
+}
+
+#[derive(Debug, Deserialize)]
+struct Claims {
+    sub: String,
+}
+
+/// Validates API keys / JWTs and enforces the per-client request rate
+#[derive(Clone)]
+pub struct AuthInterceptor {
+    api_key: Option<String>,
+    jwt_key: Option<DecodingKey>,
+    validation: Validation,
+    quotas: Arc<ClientQuotas>,
+}
+
+impl AuthInterceptor {
+    pub fn new(config: &Config, quotas: Arc<ClientQuotas>) -> Self {
+        let api_key = config.get_api_key().map(str::to_string);
+        let jwt_key = config.get_jwt_secret().map(|secret| DecodingKey::from_secret(secret.as_bytes()));
+
+        if api_key.is_none() && jwt_key.is_none() {
+            warn!("{} {} | API_KEY and JWT_SECRET are not set, accepting unauthenticated clients",
+                "⚠️".bright_yellow(),
+                "GRPC_AUTH".bright_yellow()
+            );
+        }
+
+        Self {
+            api_key,
+            jwt_key,
+            validation: Validation::new(Algorithm::HS256),
+            quotas,
+        }
+    }
+
+    fn authenticate(&self, request: &Request<()>) -> Result<ClientIdentity, Status> {
+        // Clients sharing a credential still get their own quota bucket per address
+        let peer = request.remote_addr()
+            .map(|addr| addr.ip().to_string())
+            .unwrap_or_else(|| "anonymous".to_string());
+
+        if self.api_key.is_none() && self.jwt_key.is_none() {
+            return Ok(ClientIdentity { client_id: peer, method: AuthMethod::Anonymous });
+        }
+
+        let metadata = request.metadata();
+
+        if let (Some(expected), Some(provided)) = (&self.api_key, metadata.get(API_KEY_HEADER)) {
+            let provided = provided.to_str().map_err(|_| Status::unauthenticated("malformed API key"))?;
+            if constant_time_eq(expected.as_bytes(), provided.as_bytes()) {
+                return Ok(ClientIdentity { client_id: format!("api-key@{}", peer), method: AuthMethod::ApiKey });
+            }
+            return Err(Status::unauthenticated("invalid API key"));
+        }
+
+        if let (Some(key), Some(header)) = (&self.jwt_key, metadata.get(AUTHORIZATION_HEADER)) {
+            let token = header.to_str().ok()
+                .and_then(|value| value.strip_prefix("Bearer "))
+                .ok_or_else(|| Status::unauthenticated("expected 'Bearer <token>' authorization"))?;
+            let claims = decode::<Claims>(token, key, &self.validation)
+                .map_err(|e| Status::unauthenticated(format!("invalid token: {}", e)))?
+                .claims;
+            return Ok(ClientIdentity { client_id: claims.sub, method: AuthMethod::Jwt });
+        }
+
+        Err(Status::unauthenticated("missing credentials"))
+    }
+}
+
+impl Interceptor for AuthInterceptor {
+    fn call(&mut self, mut request: Request<()>) -> Result<Request<()>, Status> {
+        let identity = self.authenticate(&request)?;
+        self.quotas.check_rate(&identity.client_id)?;
+
+        debug!("Authenticated gRPC client {} via {:?}", identity.client_id, identity.method);
+        request.extensions_mut().insert(identity);
+        Ok(request)
+    }
+}
+
+/// Client id attached by [`AuthInterceptor`], or "anonymous" when missing
+pub fn client_id<T>(request: &Request<T>) -> String {
+    request.extensions()
+        .get::<ClientIdentity>()
+        .map(|identity| identity.client_id.clone())
+        .unwrap_or_else(|| "anonymous".to_string())
+}
+
+struct ClientUsage {
+    tokens: f64,
+    last_refill: Instant,
+    active_streams: u32,
+}
+
+/// Per-client request-rate (token bucket) and concurrent-stream limits
+pub struct ClientQuotas {
+    rate_limiting: bool,
+    max_requests_per_second: u32,
+    max_streams_per_client: u32,
+    clients: Mutex<HashMap<String, ClientUsage>>,
+}
+
+impl ClientQuotas {
+    pub fn new(config: &Config) -> Self {
+        let performance = &config.performance_config;
+        Self {
+            rate_limiting: performance.enable_rate_limiting,
+            max_requests_per_second: performance.max_requests_per_second.max(1),
+            max_streams_per_client: performance.max_streams_per_client,
+            clients: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn new_usage(&self) -> ClientUsage {
+        ClientUsage {
+            tokens: self.max_requests_per_second as f64,
+            last_refill: Instant::now(),
+            active_streams: 0,
+        }
+    }
+
+    /// Idle clients have a full bucket and no streams, so forgetting them is lossless
+    fn is_idle(&self, usage: &ClientUsage) -> bool {
+        let rate = self.max_requests_per_second as f64;
+        usage.active_streams == 0
+            && (!self.rate_limiting || usage.tokens + usage.last_refill.elapsed().as_secs_f64() * rate >= rate)
+    }
+
+    /// Take one request token for `client_id`
+    pub fn check_rate(&self, client_id: &str) -> Result<(), Status> {
+        if !self.rate_limiting {
+            return Ok(());
+        }
+
+        let mut clients = self.clients.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if clients.len() >= MAX_TRACKED_CLIENTS && !clients.contains_key(client_id) {
+            clients.retain(|_, usage| !self.is_idle(usage));
+        }
+
+        let rate = self.max_requests_per_second as f64;
+        let usage = clients.entry(client_id.to_string()).or_insert_with(|| self.new_usage());
+
+        let now = Instant::now();
+        usage.tokens = (usage.tokens + now.duration_since(usage.last_refill).as_secs_f64() * rate).min(rate);
+        usage.last_refill = now;
+
+        if usage.tokens < 1.0 {
+            return Err(Status::resource_exhausted(format!(
+                "rate limit of {} requests/second exceeded", self.max_requests_per_second
+            )));
+        }
+        usage.tokens -= 1.0;
+        Ok(())
+    }
+
+    /// Reserve a concurrent stream slot for `client_id`, released when the permit drops
+    pub fn acquire_stream(self: &Arc<Self>, client_id: &str) -> Result<StreamPermit, Status> {
+        let mut clients = self.clients.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let usage = clients.entry(client_id.to_string()).or_insert_with(|| self.new_usage());
+
+        if usage.active_streams >= self.max_streams_per_client {
+            return Err(Status::resource_exhausted(format!(
+                "limit of {} concurrent streams per client reached", self.max_streams_per_client
+            )));
+        }
+        usage.active_streams += 1;
+
+        Ok(StreamPermit {
+            quotas: self.clone(),
+            client_id: client_id.to_string(),
+        })
+    }
+}
+
+/// Held by a streaming subscription for as long as it is open
+pub struct StreamPermit {
+    quotas: Arc<ClientQuotas>,
+    client_id: String,
+}
+
+impl Drop for StreamPermit {
+    fn drop(&mut self) {
+        let mut clients = self.quotas.clients.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let idle = match clients.get_mut(&self.client_id) {
+            Some(usage) => {
+                usage.active_streams = usage.active_streams.saturating_sub(1);
+                self.quotas.is_idle(usage)
+            }
+            None => false,
+        };
+
+        // Otherwise stream-only clients would pile up while rate limiting is off
+        if idle {
+            clients.remove(&self.client_id);
+        }
+    }
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonwebtoken::{encode, EncodingKey, Header};
+    use tonic::codegen::http::{HeaderMap, HeaderValue};
+    use tonic::metadata::MetadataMap;
+
+    fn config(api_key: &str, jwt_secret: &str) -> Config {
+        let mut config = Config::from_env().unwrap();
+        config.api_key = api_key.to_string();
+        config.jwt_secret = jwt_secret.to_string();
+        config.performance_config.enable_rate_limiting = true;
+        config.performance_config.max_requests_per_second = 2;
+        config.performance_config.max_streams_per_client = 2;
+        config
+    }
+
+    fn interceptor(api_key: &str, jwt_secret: &str) -> AuthInterceptor {
+        let config = config(api_key, jwt_secret);
+        AuthInterceptor::new(&config, Arc::new(ClientQuotas::new(&config)))
+    }
+
+    fn request_with(header: &'static str, value: HeaderValue) -> Request<()> {
+        let mut headers = HeaderMap::new();
+        headers.insert(header, value);
+        let mut request = Request::new(());
+        *request.metadata_mut() = MetadataMap::from_headers(headers);
+        request
+    }
+
+    fn token(secret: &str, expires_in: i64) -> String {
+        let claims = serde_json::json!({
+            "sub": "alice",
+            "exp": chrono::Utc::now().timestamp() + expires_in,
+        });
+        encode(&Header::default(), &claims, &EncodingKey::from_secret(secret.as_bytes())).unwrap()
+    }
+
+    fn bearer(token: &str) -> Request<()> {
+        request_with(AUTHORIZATION_HEADER, HeaderValue::from_str(&format!("Bearer {}", token)).unwrap())
+    }
+
+    #[test]
+    fn api_keys_are_checked() {
+        let auth = interceptor("secret", "");
+
+        let identity = auth.authenticate(&request_with(API_KEY_HEADER, HeaderValue::from_static("secret"))).unwrap();
+        assert_eq!(identity.method, AuthMethod::ApiKey);
+        assert_eq!(identity.client_id, "api-key@anonymous");
+
+        for value in [HeaderValue::from_static("wrong"), HeaderValue::from_bytes(b"secret\xff").unwrap()] {
+            let error = auth.authenticate(&request_with(API_KEY_HEADER, value)).unwrap_err();
+            assert_eq!(error.code(), tonic::Code::Unauthenticated);
+        }
+
+        let error = auth.authenticate(&Request::new(())).unwrap_err();
+        assert_eq!(error.code(), tonic::Code::Unauthenticated);
+    }
+
+    #[test]
+    fn jwts_are_checked() {
+        let auth = interceptor("", "jwt-secret");
+
+        let identity = auth.authenticate(&bearer(&token("jwt-secret", 3600))).unwrap();
+        assert_eq!(identity.method, AuthMethod::Jwt);
+        assert_eq!(identity.client_id, "alice");
+
+        let rejected = [
+            bearer(&token("jwt-secret", -3600)),
+            bearer(&token("other-secret", 3600)),
+            request_with(AUTHORIZATION_HEADER, HeaderValue::from_str(&token("jwt-secret", 3600)).unwrap()),
+        ];
+        for request in rejected {
+            let error = auth.authenticate(&request).unwrap_err();
+            assert_eq!(error.code(), tonic::Code::Unauthenticated);
+        }
+    }
+
+    #[test]
+    fn anonymous_mode_accepts_everyone() {
+        let identity = interceptor("", "").authenticate(&Request::new(())).unwrap();
+        assert_eq!(identity.method, AuthMethod::Anonymous);
+        assert_eq!(identity.client_id, "anonymous");
+    }
+
+    #[test]
+    fn request_tokens_refill_over_time() {
+        let quotas = ClientQuotas::new(&config("", ""));
+
+        assert!(quotas.check_rate("client").is_ok());
+        assert!(quotas.check_rate("client").is_ok());
+        let error = quotas.check_rate("client").unwrap_err();
+        assert_eq!(error.code(), tonic::Code::ResourceExhausted);
+        assert!(quotas.check_rate("other").is_ok());
+
+        std::thread::sleep(std::time::Duration::from_millis(600));
+        assert!(quotas.check_rate("client").is_ok());
+    }
+
+    #[test]
+    fn stream_permits_are_limited_and_released() {
+        let mut config = config("", "");
+        config.performance_config.enable_rate_limiting = false;
+        let quotas = Arc::new(ClientQuotas::new(&config));
+
+        let first = quotas.acquire_stream("client").unwrap();
+        let second = quotas.acquire_stream("client").unwrap();
+        let error = quotas.acquire_stream("client").err().unwrap();
+        assert_eq!(error.code(), tonic::Code::ResourceExhausted);
+
+        drop(first);
+        let third = quotas.acquire_stream("client").unwrap();
+
+        // Idle clients are forgotten once their last stream closes
+        drop(second);
+        drop(third);
+        assert!(quotas.clients.lock().unwrap().is_empty());
+    }
+}
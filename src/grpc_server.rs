@@ -1,134 +1,85 @@
 use anyhow::Result;
 use colored::*;
+use futures::{SinkExt, StreamExt};
+use solana_client::nonblocking::rpc_client::RpcClient as NonblockingRpcClient;
 use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcBlockConfig;
+use solana_commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use solana_transaction_status::TransactionDetails;
+use std::collections::{HashMap, HashSet};
+use std::net::SocketAddr;
+use std::str::FromStr;
 use std::sync::Arc;
-use tokio::sync::RwLock;
-use tonic::{Request, Response, Status};
-use tracing::{info, debug, error, warn};
+use std::time::Duration;
+use tokio::sync::{broadcast, mpsc, RwLock};
+use tokio::task::JoinHandle;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::{transport::{Channel, ClientTlsConfig, Server}, Request, Response, Status};
+use tonic_health::pb::{health_check_response::ServingStatus, health_client::HealthClient, HealthCheckRequest};
+use tonic_reflection::pb::v1::{
+    server_reflection_client::ServerReflectionClient, server_reflection_request::MessageRequest,
+    server_reflection_response::MessageResponse, ServerReflectionRequest,
+};
+use tracing::{info, debug, warn};
+use yellowstone_grpc_proto::prelude::{
+    subscribe_update::UpdateOneof, CommitmentLevel, SubscribeRequest,
+    SubscribeRequestFilterAccounts, SubscribeRequestFilterTransactions, SubscribeRequestPing,
+};
 
 use crate::{
-    cache::IndexerCache,
+    cache::{CachedAccount, CachedSlotInfo, IndexerCache},
     config::Config,
-    database::Database,
-
-    slot_tracker::SlotTracker,
+    database::{self, Database, InstructionQuery},
+    grpc_auth::{self, AuthInterceptor, ClientQuotas, StreamPermit, API_KEY_HEADER},
+    program_decoders::VOTE_PROGRAM_ID,
+    reorg::{self, ForkTracker},
+    slot_tracker::{is_rpc_response_error, is_skipped_slot_error, SlotTracker},
+    tls::ServerTls,
+    yellowstone_monitor,
 };
 
-// Manual type definitions for gRPC messages
-#[derive(Debug, Clone)]
-pub struct SlotUpdate {
-    pub slot: u64,
-    pub commitment: String,
-    pub timestamp: i64,
-    pub parent_slot: u64,
-    pub block_hash: String,
-    pub block_height: u64,
-}
-
-#[derive(Debug, Clone)]
-pub struct SlotInfo {
-    pub current_slot: u64,
-    pub finalized_slot: u64,
-    pub confirmed_slot: u64,
-    pub timestamp: i64,
-}
-
-#[derive(Debug, Clone)]
-pub struct SlotLeaderInfo {
-    pub slot: u64,
-    pub leader_pubkey: String,
-    pub timestamp: i64,
-}
-
-#[derive(Debug, Clone)]
-pub struct SlotLeaderUpdate {
-    pub slot: u64,
-    pub leader_pubkey: String,
-    pub previous_leader: String,
-    pub timestamp: i64,
-}
-
-#[derive(Debug, Clone)]
-pub struct GetSlotRequest {
-    pub slot: u64,
-}
-
-#[derive(Debug, Clone)]
-pub struct GetSlotLeaderRequest {
-    pub slot: u64,
-}
-
-#[derive(Debug, Clone)]
-pub struct GetTransactionsRequest {
-    pub signatures: Vec<String>,
-    pub limit: u32,
-}
+/// Types and service stubs generated from `proto/solana_indexer/indexer.proto`
+pub mod proto {
+    tonic::include_proto!("solana_indexer");
 
-#[derive(Debug, Clone)]
-pub struct GetTransactionsResponse {
-    pub transactions: Vec<TransactionInfo>,
-    pub total_count: u32,
-    pub cached_count: u32,
+    /// Encoded descriptors for gRPC server reflection
+    pub const FILE_DESCRIPTOR_SET: &[u8] = tonic::include_file_descriptor_set!("solana_indexer_descriptor");
 }
 
-#[derive(Debug, Clone)]
-pub struct TransactionInfo {
-    pub signature: String,
-    pub slot: u64,
-    pub from: String,
-    pub to: String,
-    pub amount: u64,
-    pub fee: u64,
-    pub status: String,
-    pub timestamp: i64,
-}
+use proto::{
+    solana_indexer_client::SolanaIndexerClient,
+    solana_indexer_server::{SolanaIndexer, SolanaIndexerServer},
+    AccountInfo, AccountSubscriptionRequest, AccountUpdate, GetAccountRequest, GetCurrentSlotRequest,
+    GetIndexerStatusRequest, GetInstructionsRequest, GetInstructionsResponse, GetSlotLeaderRequest,
+    GetSlotRequest, GetTransactionsRequest, GetTransactionsResponse, IndexerStatus, InstructionInfo,
+    ReorgSubscriptionRequest, ReorgUpdate, SlotInfo,
+    SlotLeaderInfo, SlotLeaderSubscriptionRequest, SlotLeaderUpdate, SlotSubscriptionRequest,
+    SlotUpdate, TransactionInfo, TransactionSubscriptionRequest, TransactionUpdate,
+};
 
-#[derive(Debug, Clone)]
-pub struct GetAccountRequest {
-    pub address: String,
-}
+/// Capacity of the slot update fan-out channel shared by all subscribers
+const SLOT_UPDATE_CHANNEL_CAPACITY: usize = 1024;
 
-#[derive(Debug, Clone)]
-pub struct AccountInfo {
-    pub address: String,
-    pub lamports: u64,
-    pub owner: String,
-    pub executable: bool,
-    pub rent_epoch: u64,
-    pub data_size: u64,
-    pub slot: u64,
-    pub timestamp: i64,
-}
+/// Capacity of the reorg fan-out channel; reorgs are rare so this stays small
+const REORG_CHANNEL_CAPACITY: usize = 64;
 
-#[derive(Debug, Clone)]
-pub struct SlotSubscriptionRequest {
-    pub include_finalized: bool,
-    pub include_confirmed: bool,
-    pub include_processed: bool,
-}
+/// Capacity of each subscriber's outbound stream buffer
+const SUBSCRIBER_BUFFER_SIZE: usize = 128;
 
-#[derive(Debug, Clone)]
-pub struct SlotLeaderSubscriptionRequest {
-    pub start_slot: u64,
-    pub end_slot: u64,
-}
+const COMMITMENT_PROCESSED: &str = "processed";
+const COMMITMENT_CONFIRMED: &str = "confirmed";
+const COMMITMENT_FINALIZED: &str = "finalized";
 
-#[derive(Debug, Clone)]
-pub struct GetCurrentSlotRequest {}
+/// Commitment levels in the order a slot reaches them
+const COMMITMENT_LEVELS: [&str; 3] = [COMMITMENT_PROCESSED, COMMITMENT_CONFIRMED, COMMITMENT_FINALIZED];
 
-// Mock gRPC server trait for now
-#[async_trait::async_trait]
-pub trait SolanaIndexer {
-    async fn get_current_slot(&self, request: tonic::Request<GetCurrentSlotRequest>) -> Result<tonic::Response<SlotInfo>, tonic::Status>;
-    async fn get_slot(&self, request: tonic::Request<GetSlotRequest>) -> Result<tonic::Response<SlotInfo>, tonic::Status>;
-    async fn get_slot_leader(&self, request: tonic::Request<GetSlotLeaderRequest>) -> Result<tonic::Response<SlotLeaderInfo>, tonic::Status>;
-    async fn get_transactions(&self, request: tonic::Request<GetTransactionsRequest>) -> Result<tonic::Response<GetTransactionsResponse>, tonic::Status>;
-    async fn get_account(&self, request: tonic::Request<GetAccountRequest>) -> Result<tonic::Response<AccountInfo>, tonic::Status>;
+/// Number of persisted rows loaded per replay query
+const REPLAY_PAGE_SIZE: u64 = 500;
 
-    // Streaming methods
-    async fn subscribe_slots(&self, request: tonic::Request<SlotSubscriptionRequest>) -> Result<tonic::Response<Vec<SlotUpdate>>, tonic::Status>;
-    async fn subscribe_slot_leaders(&self, request: tonic::Request<SlotLeaderSubscriptionRequest>) -> Result<tonic::Response<Vec<SlotLeaderUpdate>>, tonic::Status>;
-}
+/// Instructions returned by `GetInstructions` when no limit is given, and the most it returns
+const DEFAULT_INSTRUCTION_LIMIT: u64 = 100;
+const MAX_INSTRUCTION_LIMIT: u64 = 1_000;
 
 /// High-performance gRPC server for Solana indexer
 pub struct SolanaIndexerService {
@@ -136,6 +87,12 @@ pub struct SolanaIndexerService {
     database: Arc<Database>,
     config: Arc<Config>,
     slot_tracker: Arc<RwLock<SlotTracker>>,
+    slot_updates: broadcast::Sender<SlotUpdate>,
+    leader_updates: broadcast::Sender<SlotLeaderUpdate>,
+    reorg_updates: broadcast::Sender<ReorgUpdate>,
+    metrics: Arc<RwLock<GrpcMetrics>>,
+    quotas: Arc<ClientQuotas>,
+    rpc: Arc<NonblockingRpcClient>,
 }
 
 impl SolanaIndexerService {
@@ -145,46 +102,280 @@ impl SolanaIndexerService {
         config: Arc<Config>,
         slot_tracker: Arc<RwLock<SlotTracker>>,
     ) -> Self {
+        let (slot_updates, _) = broadcast::channel(SLOT_UPDATE_CHANNEL_CAPACITY);
+        let (leader_updates, _) = broadcast::channel(SLOT_UPDATE_CHANNEL_CAPACITY);
+        let (reorg_updates, _) = broadcast::channel(REORG_CHANNEL_CAPACITY);
+        let quotas = Arc::new(ClientQuotas::new(&config));
+        let rpc = Arc::new(NonblockingRpcClient::new(config.solana_rpc_url.clone()));
+
         Self {
             cache,
             database,
             config,
             slot_tracker,
+            slot_updates,
+            leader_updates,
+            reorg_updates,
+            metrics: Arc::new(RwLock::new(GrpcMetrics::new())),
+            quotas,
+            rpc,
         }
     }
 
-        /// Start the gRPC server
-    pub async fn start(&self) -> Result<()> {
-        info!("{} {} | Starting gRPC server on port {}",
+    /// Start the gRPC server and serve until Ctrl+C, over TLS when `tls` is set
+    pub async fn start(self, addr: SocketAddr, tls: Option<ServerTls>) -> Result<()> {
+        info!("{} {} | Starting gRPC server on {}",
             "🚀".bright_green(),
             "GRPC_SERVER".bright_green(),
-            self.config.grpc_server_port.to_string().bright_cyan()
+            addr.to_string().bright_cyan()
         );
 
-        // For now, just log that the server would start
-        // In a full implementation, this would start the actual gRPC server
-        info!("{} {} | gRPC server would start here (use 'grpc start' command)",
-            "ℹ️".bright_blue(),
-            "INFO".bright_blue()
-        );
+        let poller = self.spawn_slot_poller();
+        let interceptor = AuthInterceptor::new(&self.config, self.quotas.clone());
+
+        let (health_reporter, health_service) = tonic_health::server::health_reporter();
+        health_reporter.set_serving::<SolanaIndexerServer<SolanaIndexerService>>().await;
+
+        let reflection_service = tonic_reflection::server::Builder::configure()
+            .register_encoded_file_descriptor_set(proto::FILE_DESCRIPTOR_SET)
+            .register_encoded_file_descriptor_set(tonic_health::pb::FILE_DESCRIPTOR_SET)
+            .build_v1()?;
+
+        let mut builder = Server::builder();
+        if let Some(tls) = &tls {
+            builder = builder.tls_config(tls.tonic_config()?)?;
+        }
 
+        builder
+            .add_service(health_service)
+            .add_service(reflection_service)
+            .add_service(SolanaIndexerServer::with_interceptor(self, interceptor))
+            .serve_with_shutdown(addr, async move {
+                let _ = tokio::signal::ctrl_c().await;
+                info!("{} {} | Shutdown signal received",
+                    "🛑".bright_yellow(),
+                    "GRPC_SERVER".bright_yellow()
+                );
+                health_reporter.set_not_serving::<SolanaIndexerServer<SolanaIndexerService>>().await;
+            })
+            .await?;
+
+        poller.abort();
         Ok(())
     }
 
+    /// Poll the RPC for new slots at every commitment level, feeding the slot
+    /// tracker, cache and subscribers
+    fn spawn_slot_poller(&self) -> JoinHandle<()> {
+        let rpc_url = self.config.solana_rpc_url.clone();
+        let poll_interval = Duration::from_millis(self.config.update_interval_ms);
+        let slot_tracker = self.slot_tracker.clone();
+        let cache = self.cache.clone();
+        let database = self.database.clone();
+        let slot_updates = self.slot_updates.clone();
+        let leader_updates = self.leader_updates.clone();
+        let reorg_updates = self.reorg_updates.clone();
+        let webhook_base_url = self.config.get_webhook_base_url().map(str::to_string);
+
+        tokio::spawn(async move {
+            let client = NonblockingRpcClient::new(rpc_url.clone());
+            // ForkTracker polls with the blocking client, so its checks run off the runtime workers
+            let blocking_client = Arc::new(RpcClient::new(rpc_url));
+            let mut ticker = tokio::time::interval(poll_interval);
+            let mut last_slots = [0u64; 3];
+            let mut current_leader = String::new();
+            let mut fork_tracker = ForkTracker::new();
+
+            loop {
+                ticker.tick().await;
+
+                let checker = blocking_client.clone();
+                let checked = tokio::task::spawn_blocking(move || {
+                    let checked = fork_tracker.check(&checker);
+                    (fork_tracker, checked)
+                }).await;
+                let checked = match checked {
+                    Ok((tracker, checked)) => {
+                        fork_tracker = tracker;
+                        checked
+                    }
+                    Err(e) => {
+                        // The tracker was lost with the task, start over from the current tips
+                        fork_tracker = ForkTracker::new();
+                        Err(anyhow::anyhow!("fork check task failed: {}", e))
+                    }
+                };
+
+                match checked {
+                    Ok(Some(event)) => {
+                        reorg::apply_reorg(&event, Some(&database), webhook_base_url.as_deref()).await;
+                        cache.invalidate_slots(&event.orphaned_slots).await;
+                        let _ = reorg_updates.send(ReorgUpdate {
+                            slot: event.slot,
+                            fork_slot: event.fork_slot,
+                            depth: event.depth,
+                            orphaned_slots: event.orphaned_slots,
+                            detected_at: event.detected_at,
+                            dropped_updates: 0,
+                        });
+                    }
+                    Ok(None) => {}
+                    Err(e) => debug!("Fork check failed: {}", e),
+                }
+
+                let levels = [
+                    (CommitmentConfig::processed(), COMMITMENT_PROCESSED),
+                    (CommitmentConfig::confirmed(), COMMITMENT_CONFIRMED),
+                    (CommitmentConfig::finalized(), COMMITMENT_FINALIZED),
+                ];
+
+                for (index, (commitment, label)) in levels.into_iter().enumerate() {
+                    let slot = match client.get_slot_with_commitment(commitment).await {
+                        Ok(slot) => slot,
+                        Err(e) => {
+                            warn!("{} {} | Failed to poll {} slot: {}",
+                                "⚠️".bright_yellow(),
+                                "SLOT_POLLER".bright_yellow(),
+                                label,
+                                e
+                            );
+                            continue;
+                        }
+                    };
+
+                    if slot <= last_slots[index] {
+                        continue;
+                    }
+                    last_slots[index] = slot;
+
+                    let received_at = chrono::Utc::now();
+                    let now = received_at.timestamp();
+
+                    match label {
+                        COMMITMENT_PROCESSED => {
+                            slot_tracker.write().await.observe_slot(slot);
+                            fork_tracker.observe_processed(slot);
+                            let _ = cache.cache_slot(CachedSlotInfo {
+                                slot,
+                                leader: String::new(),
+                                block_hash: String::new(),
+                                timestamp: now,
+                                confirmed: false,
+                                finalized: false,
+                                cached_at: now,
+                            }).await;
+                        }
+                        COMMITMENT_CONFIRMED => {
+                            slot_tracker.write().await.observe_confirmed_slot(slot);
+                            if let Some(mut cached) = cache.get_slot(slot).await {
+                                cached.confirmed = true;
+                                let _ = cache.cache_slot(cached).await;
+                            }
+                        }
+                        _ => {
+                            slot_tracker.write().await.observe_finalized_slot(slot);
+                            if let Some(mut cached) = cache.get_slot(slot).await {
+                                cached.confirmed = true;
+                                cached.finalized = true;
+                                let _ = cache.cache_slot(cached).await;
+                            }
+                        }
+                    }
+
+                    // Persist before broadcasting so replaying subscribers can't miss an update
+                    if let Err(e) = database.upsert_slot_commitment(
+                        slot,
+                        label != COMMITMENT_PROCESSED,
+                        label == COMMITMENT_FINALIZED,
+                        received_at,
+                    ).await {
+                        warn!("{} {} | Failed to persist {} slot {}: {}",
+                            "⚠️".bright_yellow(),
+                            "SLOT_POLLER".bright_yellow(),
+                            label,
+                            slot,
+                            e
+                        );
+                    }
+
+                    // getSlot carries no block, so parent and hash stay unset.
+                    // No receivers simply means nobody is subscribed right now
+                    let _ = slot_updates.send(SlotUpdate {
+                        slot,
+                        commitment: label.to_string(),
+                        timestamp: now,
+                        parent_slot: 0,
+                        block_hash: String::new(),
+                        block_height: 0,
+                        dropped_updates: 0,
+                        cursor: String::new(),
+                    });
+
+                    if label != COMMITMENT_PROCESSED {
+                        continue;
+                    }
+
+                    let leader = match client.get_slot_leaders(slot, 1).await {
+                        Ok(leaders) => match leaders.into_iter().next() {
+                            Some(leader) => leader.to_string(),
+                            None => continue,
+                        },
+                        Err(e) => {
+                            debug!("Failed to resolve leader for slot {}: {}", slot, e);
+                            continue;
+                        }
+                    };
+
+                    if let Err(e) = database.insert_slot_leader(slot, &leader, None).await {
+                        warn!("{} {} | Failed to persist leader for slot {}: {}",
+                            "⚠️".bright_yellow(),
+                            "SLOT_POLLER".bright_yellow(),
+                            slot,
+                            e
+                        );
+                    }
+
+                    if leader != current_leader {
+                        let _ = leader_updates.send(SlotLeaderUpdate {
+                            slot,
+                            leader_pubkey: leader.clone(),
+                            previous_leader: std::mem::replace(&mut current_leader, leader),
+                            timestamp: now,
+                            dropped_updates: 0,
+                            cursor: String::new(),
+                        });
+                    }
+                }
+            }
+        })
+    }
+
     /// Get current slot information with sub-millisecond response
     async fn get_current_slot_internal(&self) -> Result<SlotInfo, Status> {
         let start_time = std::time::Instant::now();
 
+        let (current_slot, confirmed_slot, finalized_slot) = {
+            let tracker = self.slot_tracker.read().await;
+            let current_slot = tracker.get_current_slot().await;
+            (
+                current_slot,
+                tracker.get_confirmed_slot().unwrap_or(current_slot.saturating_sub(1)),
+                tracker.get_finalized_slot().unwrap_or(current_slot.saturating_sub(32)),
+            )
+        };
+
         // Try cache first (sub-millisecond)
-        if let Some(cached_slot) = self.cache.get_slot(0).await {
+        if let Some(cached_slot) = self.cache.get_slot(current_slot).await {
             let response = SlotInfo {
                 current_slot: cached_slot.slot,
-                finalized_slot: cached_slot.slot.saturating_sub(32),
-                confirmed_slot: cached_slot.slot.saturating_sub(1),
+                finalized_slot,
+                confirmed_slot,
                 timestamp: cached_slot.timestamp,
+                ..Default::default()
             };
 
             let duration = start_time.elapsed();
+            self.metrics.write().await.record_request(duration, true);
             debug!("{} {} | Cache HIT: {}μs",
                 "🎯".bright_green(),
                 "SLOT_CACHE".bright_green(),
@@ -194,21 +385,20 @@ impl SolanaIndexerService {
             return Ok(response);
         }
 
-        // Fallback to database (few milliseconds)
-        let slot_tracker = self.slot_tracker.read().await;
-        let current_slot = slot_tracker.get_current_slot().await;
-
+        // Fall back to the tracker's last observed slots
         let response = SlotInfo {
             current_slot,
-            finalized_slot: current_slot.saturating_sub(32),
-            confirmed_slot: current_slot.saturating_sub(1),
+            finalized_slot,
+            confirmed_slot,
             timestamp: std::time::SystemTime::now()
                 .duration_since(std::time::UNIX_EPOCH)
                 .unwrap()
                 .as_secs() as i64,
+            ..Default::default()
         };
 
         let duration = start_time.elapsed();
+        self.metrics.write().await.record_request(duration, false);
         debug!("{} {} | Database fallback: {}μs",
             "💾".bright_blue(),
             "SLOT_DB".bright_blue(),
@@ -218,8 +408,177 @@ impl SolanaIndexerService {
         Ok(response)
     }
 
-    /// Get transactions with smart caching
-    async fn get_transactions_internal(&self, request: &GetTransactionsRequest) -> Result<Vec<TransactionInfo>, Status> {
+    /// Resolve a slot's block through the cache, then the database, then the
+    /// RPC, writing RPC results back. Skipped slots are `NOT_FOUND`.
+    async fn get_slot_internal(&self, slot: u64) -> Result<SlotInfo, Status> {
+        let start_time = std::time::Instant::now();
+
+        let (confirmed_slot, finalized_slot) = {
+            let tracker = self.slot_tracker.read().await;
+            (tracker.get_confirmed_slot().unwrap_or(0), tracker.get_finalized_slot().unwrap_or(0))
+        };
+
+        // The poller caches and persists commitment-only rows, so only entries
+        // with a blockhash count as resolved
+        if let Some(cached) = self.cache.get_slot(slot).await.filter(|cached| !cached.block_hash.is_empty()) {
+            let leader_pubkey = if cached.leader.is_empty() {
+                self.resolve_slot_leader(slot).await.unwrap_or_default()
+            } else {
+                cached.leader
+            };
+            // The cache doesn't keep the parent, the write-back stored it in the database
+            let parent_slot = match self.database.get_slot(slot).await {
+                Ok(Some(stored)) => stored.parent_slot,
+                _ => 0,
+            };
+            self.metrics.write().await.record_request(start_time.elapsed(), true);
+
+            return Ok(SlotInfo {
+                current_slot: slot,
+                finalized_slot,
+                confirmed_slot,
+                timestamp: cached.timestamp,
+                parent_slot,
+                blockhash: cached.block_hash,
+                leader_pubkey,
+                confirmed: cached.confirmed || slot <= confirmed_slot,
+                finalized: cached.finalized || slot <= finalized_slot,
+            });
+        }
+
+        let stored = self.database.get_slot(slot).await
+            .map_err(|e| Status::internal(format!("database error: {}", e)))?
+            .filter(|stored| !database::is_placeholder_blockhash(&stored.blockhash));
+
+        let (blockhash, parent_slot, timestamp, confirmed, finalized) = match stored {
+            Some(stored) => (
+                stored.blockhash,
+                stored.parent_slot,
+                stored.timestamp.timestamp(),
+                stored.confirmed,
+                stored.finalized,
+            ),
+            None => {
+                let block = self.rpc
+                    .get_block_with_config(slot, RpcBlockConfig {
+                        transaction_details: Some(TransactionDetails::None),
+                        rewards: Some(false),
+                        commitment: Some(CommitmentConfig::confirmed()),
+                        max_supported_transaction_version: Some(0),
+                        ..RpcBlockConfig::default()
+                    })
+                    .await
+                    .map_err(|e| if is_skipped_slot_error(&e) {
+                        Status::not_found(format!("slot {} was skipped", slot))
+                    } else {
+                        Status::unavailable(format!("failed to fetch block for slot {}: {}", slot, e))
+                    })?;
+
+                let timestamp = block.block_time.unwrap_or_else(|| chrono::Utc::now().timestamp());
+                let finalized = slot <= finalized_slot;
+                let stored_at = chrono::DateTime::from_timestamp(timestamp, 0).unwrap_or_else(chrono::Utc::now);
+                if let Err(e) = self.database
+                    .upsert_slot_block(slot, &block.blockhash, block.parent_slot, true, finalized, stored_at)
+                    .await
+                {
+                    warn!("{} {} | Failed to persist slot {}: {}",
+                        "⚠️".bright_yellow(),
+                        "GET_SLOT".bright_yellow(),
+                        slot,
+                        e
+                    );
+                }
+
+                (block.blockhash, block.parent_slot, timestamp, true, finalized)
+            }
+        };
+
+        let leader_pubkey = self.resolve_slot_leader(slot).await.unwrap_or_default();
+        let confirmed = confirmed || slot <= confirmed_slot;
+        let finalized = finalized || slot <= finalized_slot;
+
+        let _ = self.cache.cache_slot(CachedSlotInfo {
+            slot,
+            leader: leader_pubkey.clone(),
+            block_hash: blockhash.clone(),
+            timestamp,
+            confirmed,
+            finalized,
+            cached_at: chrono::Utc::now().timestamp(),
+        }).await;
+        self.metrics.write().await.record_request(start_time.elapsed(), false);
+
+        Ok(SlotInfo {
+            current_slot: slot,
+            finalized_slot,
+            confirmed_slot,
+            timestamp,
+            parent_slot,
+            blockhash,
+            leader_pubkey,
+            confirmed,
+            finalized,
+        })
+    }
+
+    /// Resolve the scheduled leader of a slot through the cache, then the
+    /// database, then the RPC leader schedule, writing misses back to the
+    /// cache and RPC results to the database
+    async fn resolve_slot_leader(&self, slot: u64) -> Result<String, Status> {
+        let cached = self.cache.get_slot(slot).await;
+        if let Some(cached) = cached.as_ref().filter(|cached| !cached.leader.is_empty()) {
+            return Ok(cached.leader.clone());
+        }
+
+        let stored = self.database.get_slot_leader(slot).await
+            .map_err(|e| Status::internal(format!("database error: {}", e)))?;
+        let leader = match stored {
+            Some(stored) => stored.leader_pubkey,
+            None => {
+                let leader = self.rpc.get_slot_leaders(slot, 1)
+                    .await
+                    .map_err(|e| if is_rpc_response_error(&e) {
+                        Status::not_found(format!("no leader schedule for slot {}: {}", slot, e))
+                    } else {
+                        Status::unavailable(format!("failed to fetch leader schedule for slot {}: {}", slot, e))
+                    })?
+                    .into_iter()
+                    .next()
+                    .ok_or_else(|| Status::not_found(format!("no leader schedule for slot {}", slot)))?
+                    .to_string();
+
+                if let Err(e) = self.database.insert_slot_leader(slot, &leader, None).await {
+                    warn!("{} {} | Failed to persist leader for slot {}: {}",
+                        "⚠️".bright_yellow(),
+                        "GET_LEADER".bright_yellow(),
+                        slot,
+                        e
+                    );
+                }
+                leader
+            }
+        };
+
+        // Entries without a blockhash only carry commitment, like the poller's
+        let now = chrono::Utc::now().timestamp();
+        let _ = self.cache.cache_slot(match cached {
+            Some(cached) => CachedSlotInfo { leader: leader.clone(), ..cached },
+            None => CachedSlotInfo {
+                slot,
+                leader: leader.clone(),
+                block_hash: String::new(),
+                timestamp: now,
+                confirmed: false,
+                finalized: false,
+                cached_at: now,
+            },
+        }).await;
+
+        Ok(leader)
+    }
+
+    /// Get transactions with smart caching, returning them with how many came from cache
+    async fn get_transactions_internal(&self, request: &GetTransactionsRequest) -> Result<(Vec<TransactionInfo>, usize), Status> {
         let start_time = std::time::Instant::now();
         let mut transactions = Vec::new();
 
@@ -239,6 +598,8 @@ impl SolanaIndexerService {
             }
         }
 
+        let cached_count = transactions.len();
+
         // Fill remaining from database
         if transactions.len() < request.signatures.len() {
             let missing_signatures: Vec<_> = request.signatures
@@ -264,6 +625,7 @@ impl SolanaIndexerService {
         }
 
         let duration = start_time.elapsed();
+        self.metrics.write().await.record_request(duration, cached_count == request.signatures.len());
         debug!("{} {} | Transactions fetched: {} in {}μs",
             "📊".bright_blue(),
             "TRANSACTIONS".bright_blue(),
@@ -271,12 +633,14 @@ impl SolanaIndexerService {
             duration.as_micros()
         );
 
-        Ok(transactions)
+        Ok((transactions, cached_count))
     }
 
     /// Get account information with caching
     async fn get_account_internal(&self, request: &GetAccountRequest) -> Result<AccountInfo, Status> {
         let start_time = std::time::Instant::now();
+        let pubkey = Pubkey::from_str(&request.address)
+            .map_err(|_| Status::invalid_argument(format!("invalid account address '{}'", request.address)))?;
 
         // Try cache first
         if let Some(cached_account) = self.cache.get_account(&request.address).await {
@@ -292,6 +656,7 @@ impl SolanaIndexerService {
             };
 
             let duration = start_time.elapsed();
+            self.metrics.write().await.record_request(duration, true);
             debug!("{} {} | Account cache HIT: {}μs",
                 "🎯".bright_green(),
                 "ACCOUNT_CACHE".bright_green(),
@@ -301,31 +666,172 @@ impl SolanaIndexerService {
             return Ok(response);
         }
 
-        // Fallback to database (placeholder for now)
-        // In a full implementation, this would query the database
-        let response = AccountInfo {
-            address: request.address.clone(),
-            lamports: 0, // Would be from database
-            owner: "11111111111111111111111111111112".to_string(), // System program
-            executable: false,
-            rent_epoch: 0,
-            data_size: 0,
-            slot: 0,
-            timestamp: std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap()
-                .as_secs() as i64,
+        // Then the database, then the chain itself
+        let stored = self.database.get_account(&request.address).await
+            .map_err(|e| Status::internal(format!("database error: {}", e)))?;
+        let (response, source) = match stored {
+            Some(stored) => (AccountInfo {
+                address: stored.address,
+                lamports: stored.lamports,
+                owner: stored.owner,
+                executable: stored.executable,
+                rent_epoch: 0, // Not stored
+                data_size: stored.data_size,
+                slot: stored.slot,
+                timestamp: stored.updated_at.timestamp(),
+            }, "database"),
+            None => {
+                let fetched = self.rpc.get_account_with_commitment(&pubkey, CommitmentConfig::confirmed())
+                    .await
+                    .map_err(|e| Status::unavailable(format!("failed to fetch account {}: {}", request.address, e)))?;
+                let account = fetched.value
+                    .ok_or_else(|| Status::not_found(format!("account {} not found", request.address)))?;
+
+                (AccountInfo {
+                    address: request.address.clone(),
+                    lamports: account.lamports,
+                    owner: account.owner.to_string(),
+                    executable: account.executable,
+                    rent_epoch: account.rent_epoch,
+                    data_size: account.data.len() as u64,
+                    slot: fetched.context.slot,
+                    timestamp: chrono::Utc::now().timestamp(),
+                }, "RPC")
+            }
         };
 
+        let _ = self.cache.cache_account(CachedAccount {
+            pubkey: response.address.clone(),
+            lamports: response.lamports,
+            owner: response.owner.clone(),
+            executable: response.executable,
+            rent_epoch: response.rent_epoch,
+            data_len: response.data_size as usize,
+            cached_at: response.timestamp,
+        }).await;
+
         let duration = start_time.elapsed();
-        debug!("{} {} | Account database: {}μs",
+        self.metrics.write().await.record_request(duration, false);
+        debug!("{} {} | Account from {}: {}μs",
             "💾".bright_blue(),
             "ACCOUNT_DB".bright_blue(),
+            source,
             duration.as_micros()
         );
 
         Ok(response)
     }
+
+    /// Collect chain tip, indexing progress, cache and database health
+    async fn get_indexer_status_internal(&self) -> IndexerStatus {
+        let tracked_slot = self.slot_tracker.read().await.get_current_slot().await;
+        let tip_slot = match self.rpc.get_slot().await {
+            Ok(slot) => slot,
+            Err(e) => {
+                warn!("{} {} | Failed to fetch tip slot, using last tracked slot: {}",
+                    "⚠️".bright_yellow(),
+                    "INDEXER_STATUS".bright_yellow(),
+                    e
+                );
+                tracked_slot
+            }
+        };
+
+        let (database_connected, database_error) = match self.database.ping().await {
+            Ok(()) => (true, String::new()),
+            Err(e) => (false, e.to_string()),
+        };
+        let indexed_slot = match self.database.get_recent_slots(1).await {
+            Ok(slots) => slots.first().map_or(0, |slot| slot.slot),
+            Err(_) => 0,
+        };
+
+        let metrics = self.metrics.read().await;
+
+        IndexerStatus {
+            tip_slot,
+            indexed_slot,
+            lag_slots: tip_slot.saturating_sub(indexed_slot),
+            cache_hit_ratio: metrics.get_cache_hit_ratio(),
+            total_requests: metrics.total_requests,
+            average_response_time_ms: metrics.average_response_time.as_secs_f64() * 1000.0,
+            requests_per_second: metrics.requests_per_second,
+            database_connected,
+            database_error,
+            uptime_seconds: metrics.started_at.elapsed().as_secs(),
+            timestamp: chrono::Utc::now().timestamp(),
+        }
+    }
+
+    /// Open a dedicated Yellowstone subscription and forward the converted
+    /// updates to a single subscriber until either side goes away
+    async fn spawn_geyser_forwarder<T, F>(
+        &self,
+        request: SubscribeRequest,
+        tx: mpsc::Sender<Result<T, Status>>,
+        permit: StreamPermit,
+        tag: &'static str,
+        convert: F,
+    ) -> Result<(), Status>
+    where
+        T: Send + 'static,
+        F: Fn(UpdateOneof) -> Option<T> + Send + 'static,
+    {
+        let (endpoint, auth_token) = self.config.get_yellowstone_credentials()
+            .ok_or_else(|| Status::failed_precondition("YELLOWSTONE_ENDPOINT is not configured on this indexer"))?;
+
+        let mut client = yellowstone_monitor::connect_client(endpoint, auth_token).await
+            .map_err(|e| Status::unavailable(format!("Failed to connect to Yellowstone: {}", e)))?;
+        let (mut upstream_tx, mut upstream) = client.subscribe_with_request(Some(request)).await
+            .map_err(|e| Status::unavailable(format!("Failed to subscribe to Yellowstone: {}", e)))?;
+
+        tokio::spawn(async move {
+            let _client = client;
+            let _permit = permit;
+            let mut ping_id = 0;
+
+            while let Some(message) = upstream.next().await {
+                let update = match message {
+                    Ok(msg) => msg.update_oneof,
+                    Err(status) => {
+                        warn!("{} {} | Yellowstone stream error: {}",
+                            "⚠️".bright_yellow(),
+                            tag.bright_yellow(),
+                            status
+                        );
+                        let _ = tx.send(Err(status)).await;
+                        break;
+                    }
+                };
+
+                match update {
+                    Some(UpdateOneof::Ping(_)) => {
+                        // Answer pings so load balancers keep the upstream open
+                        ping_id += 1;
+                        let pong = SubscribeRequest {
+                            ping: Some(SubscribeRequestPing { id: ping_id }),
+                            ..Default::default()
+                        };
+                        if upstream_tx.send(pong).await.is_err() {
+                            break;
+                        }
+                    }
+                    Some(update) => {
+                        if let Some(converted) = convert(update) {
+                            if tx.send(Ok(converted)).await.is_err() {
+                                break; // Client went away
+                            }
+                        }
+                    }
+                    None => {}
+                }
+            }
+
+            debug!("{} {} | Subscriber stream closed", "📡".bright_blue(), tag.bright_blue());
+        });
+
+        Ok(())
+    }
 }
 
 #[tonic::async_trait]
@@ -358,13 +864,7 @@ impl SolanaIndexer for SolanaIndexerService {
     ) -> Result<Response<SlotInfo>, Status> {
         let start_time = std::time::Instant::now();
 
-        // Implementation would get specific slot data
-        let slot_info = SlotInfo {
-            current_slot: 0, // Would be from request
-            finalized_slot: 0,
-            confirmed_slot: 0,
-            timestamp: 0,
-        };
+        let slot_info = self.get_slot_internal(request.get_ref().slot).await?;
 
         let duration = start_time.elapsed();
         debug!("{} {} | Get slot: {}μs",
@@ -382,18 +882,17 @@ impl SolanaIndexer for SolanaIndexerService {
         request: Request<GetSlotLeaderRequest>,
     ) -> Result<Response<SlotLeaderInfo>, Status> {
         let start_time = std::time::Instant::now();
+        let slot = request.get_ref().slot;
 
-        // Implementation would get slot leader data
+        // Skipped slots still have a scheduled leader, the validator that missed them
         let leader_info = SlotLeaderInfo {
-            slot: request.get_ref().slot,
-            leader_pubkey: "leader_pubkey".to_string(),
-            timestamp: std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap()
-                .as_secs() as i64,
+            slot,
+            leader_pubkey: self.resolve_slot_leader(slot).await?,
+            timestamp: chrono::Utc::now().timestamp(),
         };
 
         let duration = start_time.elapsed();
+        self.metrics.write().await.record_request(duration, false);
         debug!("{} {} | Get slot leader: {}μs",
             "👑".bright_blue(),
             "GET_LEADER".bright_blue(),
@@ -410,13 +909,12 @@ impl SolanaIndexer for SolanaIndexerService {
     ) -> Result<Response<GetTransactionsResponse>, Status> {
         let start_time = std::time::Instant::now();
 
-        let transactions = self.get_transactions_internal(request.get_ref()).await?;
+        let (transactions, cached_count) = self.get_transactions_internal(request.get_ref()).await?;
 
-        let total_count = transactions.len() as u32;
         let response = GetTransactionsResponse {
+            total_count: transactions.len() as u32,
+            cached_count: cached_count as u32,
             transactions,
-            total_count,
-            cached_count: total_count, // Would calculate actual cached count
         };
 
         let duration = start_time.elapsed();
@@ -429,6 +927,55 @@ impl SolanaIndexer for SolanaIndexerService {
         Ok(Response::new(response))
     }
 
+    /// Query indexed instructions by program, type, account and slot range
+    async fn get_instructions(
+        &self,
+        request: Request<GetInstructionsRequest>,
+    ) -> Result<Response<GetInstructionsResponse>, Status> {
+        let start_time = std::time::Instant::now();
+        let request = request.into_inner();
+        let non_empty = |value: String| (!value.is_empty()).then_some(value);
+
+        let query = InstructionQuery {
+            program_id: non_empty(request.program_id),
+            parsed_type: non_empty(request.instruction_type),
+            account: non_empty(request.account),
+            from_slot: (request.from_slot > 0).then_some(request.from_slot),
+            to_slot: (request.to_slot > 0).then_some(request.to_slot),
+            limit: match request.limit {
+                0 => DEFAULT_INSTRUCTION_LIMIT,
+                limit => (limit as u64).min(MAX_INSTRUCTION_LIMIT),
+            },
+        };
+
+        let instructions = self.database.get_instructions(&query).await
+            .map_err(|e| Status::internal(format!("database error: {}", e)))?
+            .into_iter()
+            .map(|instruction| InstructionInfo {
+                signature: instruction.signature,
+                slot: instruction.slot,
+                instruction_index: instruction.instruction_index,
+                inner_index: instruction.inner_index,
+                program_id: instruction.program_id,
+                instruction_type: instruction.parsed_type.unwrap_or_default(),
+                accounts: instruction.accounts,
+                data: instruction.data.unwrap_or_default(),
+                parsed_json: instruction.parsed.unwrap_or_default(),
+            })
+            .collect::<Vec<_>>();
+
+        let duration = start_time.elapsed();
+        self.metrics.write().await.record_request(duration, false);
+        debug!("{} {} | Get instructions: {} in {}μs",
+            "🧩".bright_blue(),
+            "GET_INSTRUCTIONS".bright_blue(),
+            instructions.len().to_string().bright_cyan(),
+            duration.as_micros()
+        );
+
+        Ok(Response::new(GetInstructionsResponse { instructions }))
+    }
+
     /// Get account information
     async fn get_account(
         &self,
@@ -448,106 +995,873 @@ impl SolanaIndexer for SolanaIndexerService {
         Ok(Response::new(account_info))
     }
 
+    /// Get indexer health and progress
+    async fn get_indexer_status(
+        &self,
+        _request: Request<GetIndexerStatusRequest>,
+    ) -> Result<Response<IndexerStatus>, Status> {
+        Ok(Response::new(self.get_indexer_status_internal().await))
+    }
+
+    type SubscribeSlotsStream = ReceiverStream<Result<SlotUpdate, Status>>;
+
     /// Subscribe to real-time slot updates (streaming)
     async fn subscribe_slots(
         &self,
-        _request: tonic::Request<SlotSubscriptionRequest>,
-    ) -> Result<tonic::Response<Vec<SlotUpdate>>, tonic::Status> {
-        let start_time = std::time::Instant::now();
+        request: Request<SlotSubscriptionRequest>,
+    ) -> Result<Response<Self::SubscribeSlotsStream>, Status> {
+        let permit = self.quotas.acquire_stream(&grpc_auth::client_id(&request))?;
+        let request = request.into_inner();
+        let position = SlotPosition::from_request(&request)?;
+        let replay = position.is_resuming().then(|| self.database.clone());
+        let (tx, rx) = mpsc::channel(SUBSCRIBER_BUFFER_SIZE);
+
+        tokio::spawn(forward_updates(
+            self.slot_updates.subscribe(),
+            tx,
+            replay,
+            position,
+            permit,
+            "SUBSCRIBE_SLOTS",
+            |_: &SlotUpdate| StreamFilter::Send,
+        ));
+
+        debug!("{} {} | New slot subscriber",
+            "📡".bright_blue(),
+            "SUBSCRIBE_SLOTS".bright_blue()
+        );
 
-        // Fetch real slot updates from Solana RPC
-        let mut updates = Vec::new();
-        let slot_tracker = self.slot_tracker.read().await;
-        let current_slot = slot_tracker.get_current_slot().await;
-
-        // Get real slot information - only current and recent slots
-        let slots_to_fetch = vec![current_slot];
-
-        for slot in slots_to_fetch {
-            // Try to get real block data
-            let block_data = slot_tracker.fetch_block_data(slot).await.unwrap_or_else(|_| {
-                // Fallback to basic slot info if RPC fails
-                crate::slot_tracker::BlockData {
-                    slot,
-                    blockhash: format!("slot_{}", slot),
-                    transaction_count: 0,
-                    block_size_mb: 0.0,
-                    parent_slot: slot.saturating_sub(1),
-                    // Enhanced fields for better monitoring
-                    timestamp: std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs() as i64,
-                    leader_pubkey: "".to_string(),
-                    confirmation_time_ms: 0,
-                    finalization_time_ms: 0,
-                    total_fees: 0,
-                    total_volume: 0,
-                    vote_count: 0,
-                    missed_slots: 0,
-                    reorg_depth: None,
-                    block_version: 0,
-                    commitment_level: "".to_string(),
+        Ok(Response::new(ReceiverStream::new(rx)))
+    }
+
+    type SubscribeSlotLeadersStream = ReceiverStream<Result<SlotLeaderUpdate, Status>>;
+
+    /// Subscribe to slot leader changes (streaming)
+    async fn subscribe_slot_leaders(
+        &self,
+        request: Request<SlotLeaderSubscriptionRequest>,
+    ) -> Result<Response<Self::SubscribeSlotLeadersStream>, Status> {
+        let permit = self.quotas.acquire_stream(&grpc_auth::client_id(&request))?;
+        let range = request.into_inner();
+
+        if range.end_slot != 0 && range.end_slot < range.start_slot {
+            return Err(Status::invalid_argument("end_slot must not be lower than start_slot"));
+        }
+
+        let position = LeaderPosition::from_request(&range)?;
+        let replay = position.is_resuming().then(|| self.database.clone());
+        let (tx, rx) = mpsc::channel(SUBSCRIBER_BUFFER_SIZE);
+
+        tokio::spawn(forward_updates(
+            self.leader_updates.subscribe(),
+            tx,
+            replay,
+            position,
+            permit,
+            "SUBSCRIBE_LEADERS",
+            move |update: &SlotLeaderUpdate| {
+                if range.end_slot != 0 && update.slot > range.end_slot {
+                    StreamFilter::Finish
+                } else if update.slot < range.start_slot {
+                    StreamFilter::Skip
+                } else {
+                    StreamFilter::Send
                 }
-            });
+            },
+        ));
 
-            let update = SlotUpdate {
-                slot,
-                commitment: "confirmed".to_string(),
-                timestamp: std::time::SystemTime::now()
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .unwrap()
-                    .as_secs() as i64,
-                parent_slot: block_data.parent_slot,
-                block_hash: block_data.blockhash,
-                block_height: slot,
-            };
-            updates.push(update);
+        debug!("{} {} | New leader subscriber",
+            "👑".bright_blue(),
+            "SUBSCRIBE_LEADERS".bright_blue()
+        );
+
+        Ok(Response::new(ReceiverStream::new(rx)))
+    }
+
+    type SubscribeReorgsStream = ReceiverStream<Result<ReorgUpdate, Status>>;
+
+    /// Subscribe to fork switches that orphaned indexed slots (streaming)
+    async fn subscribe_reorgs(
+        &self,
+        request: Request<ReorgSubscriptionRequest>,
+    ) -> Result<Response<Self::SubscribeReorgsStream>, Status> {
+        let permit = self.quotas.acquire_stream(&grpc_auth::client_id(&request))?;
+        let mut updates = self.reorg_updates.subscribe();
+        let (tx, rx) = mpsc::channel(SUBSCRIBER_BUFFER_SIZE);
+
+        tokio::spawn(async move {
+            let _permit = permit;
+            let mut dropped = 0u64;
+
+            loop {
+                let mut update = match updates.recv().await {
+                    Ok(update) => update,
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        dropped += skipped;
+                        continue;
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                };
+
+                update.dropped_updates = std::mem::take(&mut dropped);
+                if tx.send(Ok(update)).await.is_err() {
+                    break; // Client went away
+                }
+            }
+        });
+
+        debug!("{} {} | New reorg subscriber",
+            "🔀".bright_blue(),
+            "SUBSCRIBE_REORGS".bright_blue()
+        );
+
+        Ok(Response::new(ReceiverStream::new(rx)))
+    }
+
+    type SubscribeAccountsStream = ReceiverStream<Result<AccountUpdate, Status>>;
+
+    /// Subscribe to account updates by address or owner program (streaming)
+    async fn subscribe_accounts(
+        &self,
+        request: Request<AccountSubscriptionRequest>,
+    ) -> Result<Response<Self::SubscribeAccountsStream>, Status> {
+        let permit = self.quotas.acquire_stream(&grpc_auth::client_id(&request))?;
+        let filter = request.into_inner();
+
+        if filter.addresses.is_empty() && filter.owners.is_empty() {
+            return Err(Status::invalid_argument("at least one address or owner is required"));
         }
+        validate_pubkeys(&filter.addresses)?;
+        validate_pubkeys(&filter.owners)?;
+
+        let mut accounts = HashMap::new();
+        accounts.insert(
+            "indexer_accounts".to_string(),
+            SubscribeRequestFilterAccounts {
+                account: filter.addresses,
+                owner: filter.owners,
+                ..Default::default()
+            },
+        );
 
-        let duration = start_time.elapsed();
-        debug!("{} {} | Subscribe slots: {}μs",
-            "📡".bright_blue(),
-            "SUBSCRIBE_SLOTS".bright_blue(),
-            duration.as_micros()
+        let subscribe_request = SubscribeRequest {
+            accounts,
+            commitment: Some(parse_commitment(&filter.commitment)? as i32),
+            ..Default::default()
+        };
+
+        let (tx, rx) = mpsc::channel(SUBSCRIBER_BUFFER_SIZE);
+        self.spawn_geyser_forwarder(subscribe_request, tx, permit, "SUBSCRIBE_ACCOUNTS", |update| match update {
+            UpdateOneof::Account(account_update) => {
+                let account = account_update.account?;
+                Some(AccountUpdate {
+                    address: bs58::encode(&account.pubkey).into_string(),
+                    lamports: account.lamports,
+                    owner: bs58::encode(&account.owner).into_string(),
+                    executable: account.executable,
+                    rent_epoch: account.rent_epoch,
+                    data: account.data,
+                    slot: account_update.slot,
+                    write_version: account.write_version,
+                    txn_signature: account.txn_signature
+                        .map(|signature| bs58::encode(signature).into_string())
+                        .unwrap_or_default(),
+                    timestamp: chrono::Utc::now().timestamp(),
+                })
+            }
+            _ => None,
+        }).await?;
+
+        debug!("{} {} | New account subscriber",
+            "👤".bright_blue(),
+            "SUBSCRIBE_ACCOUNTS".bright_blue()
         );
 
-        Ok(tonic::Response::new(updates))
+        Ok(Response::new(ReceiverStream::new(rx)))
     }
 
-    /// Subscribe to slot leader changes (streaming)
-    async fn subscribe_slot_leaders(
+    type SubscribeTransactionsStream = ReceiverStream<Result<TransactionUpdate, Status>>;
+
+    /// Subscribe to transactions matching account filters (streaming)
+    async fn subscribe_transactions(
         &self,
-        _request: tonic::Request<SlotLeaderSubscriptionRequest>,
-    ) -> Result<tonic::Response<Vec<SlotLeaderUpdate>>, tonic::Status> {
-        let start_time = std::time::Instant::now();
+        request: Request<TransactionSubscriptionRequest>,
+    ) -> Result<Response<Self::SubscribeTransactionsStream>, Status> {
+        let permit = self.quotas.acquire_stream(&grpc_auth::client_id(&request))?;
+        let filter = request.into_inner();
+
+        // Without these the upstream subscription would be the whole chain's transactions
+        if filter.account_include.is_empty() && filter.account_required.is_empty() {
+            return Err(Status::invalid_argument("at least one included or required account is required"));
+        }
+        validate_pubkeys(&filter.account_include)?;
+        validate_pubkeys(&filter.account_exclude)?;
+        validate_pubkeys(&filter.account_required)?;
+
+        let commitment = parse_commitment(&filter.commitment)?;
+        let position = TransactionPosition::from_request(&filter)?;
+        let replay = position.is_resuming().then(|| self.database.clone());
+
+        let mut transactions = HashMap::new();
+        transactions.insert(
+            "indexer_transactions".to_string(),
+            SubscribeRequestFilterTransactions {
+                account_include: filter.account_include,
+                account_exclude: filter.account_exclude,
+                account_required: filter.account_required,
+                vote: filter.vote,
+                failed: filter.failed,
+                signature: None,
+            },
+        );
+
+        let subscribe_request = SubscribeRequest {
+            transactions,
+            commitment: Some(commitment as i32),
+            ..Default::default()
+        };
+
+        // Subscribe upstream before replaying so nothing falls between history and live updates
+        let (live_tx, live_rx) = mpsc::channel(SUBSCRIBER_BUFFER_SIZE);
+        self.spawn_geyser_forwarder(subscribe_request, live_tx, permit, "SUBSCRIBE_TXS", |update| match update {
+            UpdateOneof::Transaction(transaction_update) => {
+                let info = transaction_update.transaction?;
+                let meta = info.meta.as_ref();
+
+                let mut account_keys: Vec<String> = info.transaction.as_ref()
+                    .and_then(|transaction| transaction.message.as_ref())
+                    .map(|message| message.account_keys.iter().map(|key| bs58::encode(key).into_string()).collect())
+                    .unwrap_or_default();
+                if let Some(meta) = meta {
+                    // Addresses loaded through lookup tables come after the static keys
+                    account_keys.extend(meta.loaded_writable_addresses.iter().map(|key| bs58::encode(key).into_string()));
+                    account_keys.extend(meta.loaded_readonly_addresses.iter().map(|key| bs58::encode(key).into_string()));
+                }
+
+                Some(TransactionUpdate {
+                    signature: bs58::encode(&info.signature).into_string(),
+                    slot: transaction_update.slot,
+                    index: info.index,
+                    is_vote: info.is_vote,
+                    failed: meta.is_some_and(|meta| meta.err.is_some()),
+                    fee: meta.map_or(0, |meta| meta.fee),
+                    account_keys,
+                    timestamp: chrono::Utc::now().timestamp(),
+                    cursor: String::new(),
+                })
+            }
+            _ => None,
+        }).await?;
+
+        let (tx, rx) = mpsc::channel(SUBSCRIBER_BUFFER_SIZE);
+        tokio::spawn(forward_transactions(live_rx, tx, replay, position));
+
+        debug!("{} {} | New transaction subscriber",
+            "💸".bright_blue(),
+            "SUBSCRIBE_TXS".bright_blue()
+        );
+
+        Ok(Response::new(ReceiverStream::new(rx)))
+    }
+}
+
+/// Decision taken by a subscription filter for each update
+enum StreamFilter {
+    Send,
+    Skip,
+    Finish,
+}
+
+/// Updates that can be fanned out to streaming subscribers
+trait StreamUpdate: Clone + Send + 'static {
+    fn set_dropped_updates(&mut self, dropped: u64);
+    fn set_cursor(&mut self, cursor: String);
+}
+
+impl StreamUpdate for SlotUpdate {
+    fn set_dropped_updates(&mut self, dropped: u64) {
+        self.dropped_updates = dropped;
+    }
+
+    fn set_cursor(&mut self, cursor: String) {
+        self.cursor = cursor;
+    }
+}
+
+impl StreamUpdate for SlotLeaderUpdate {
+    fn set_dropped_updates(&mut self, dropped: u64) {
+        self.dropped_updates = dropped;
+    }
+
+    fn set_cursor(&mut self, cursor: String) {
+        self.cursor = cursor;
+    }
+}
+
+impl StreamUpdate for TransactionUpdate {
+    /// Transactions are forwarded with backpressure, so none are ever dropped
+    fn set_dropped_updates(&mut self, _dropped: u64) {}
+
+    fn set_cursor(&mut self, cursor: String) {
+        self.cursor = cursor;
+    }
+}
+
+/// Where a subscriber is in a stream. Updates at or behind the position were
+/// already delivered, which lets history be replayed from the database and
+/// handed over to live updates without gaps or duplicates.
+#[tonic::async_trait]
+trait StreamPosition: Send + 'static {
+    type Update: StreamUpdate;
+
+    /// Whether the subscriber asked to resume from history
+    fn is_resuming(&self) -> bool;
+
+    /// Restart replay right after the last delivered update
+    fn rewind(&mut self);
+
+    /// Load the next page of persisted updates, `None` once caught up
+    async fn replay_page(&mut self, database: &Database) -> Result<Option<Vec<Self::Update>>>;
+
+    /// Whether `update` hasn't been delivered yet
+    fn is_new(&self, update: &Self::Update) -> bool;
+
+    /// Move past `update` as it is delivered
+    fn advance(&mut self, update: &mut Self::Update);
+
+    /// Opaque cursor resuming right after the last delivered update
+    fn cursor(&self) -> String;
+}
+
+/// Last slot delivered for each commitment level of a slot subscription
+struct SlotPosition {
+    wanted: [bool; 3],
+    delivered: [u64; 3],
+    resuming: bool,
+    replay_from: u64,
+}
+
+impl SlotPosition {
+    fn from_request(request: &SlotSubscriptionRequest) -> Result<Self, Status> {
+        let all_levels = !request.include_processed && !request.include_confirmed && !request.include_finalized;
+        let wanted = [
+            all_levels || request.include_processed,
+            all_levels || request.include_confirmed,
+            all_levels || request.include_finalized,
+        ];
+
+        let (delivered, resuming) = if !request.cursor.is_empty() {
+            (parse_slot_cursor(&request.cursor)?, true)
+        } else if request.from_slot > 0 {
+            ([request.from_slot - 1; 3], true)
+        } else {
+            ([0; 3], false)
+        };
+
+        Ok(Self { wanted, delivered, resuming, replay_from: 0 })
+    }
+}
+
+#[tonic::async_trait]
+impl StreamPosition for SlotPosition {
+    type Update = SlotUpdate;
+
+    fn is_resuming(&self) -> bool {
+        self.resuming
+    }
+
+    fn rewind(&mut self) {
+        self.replay_from = (0..3)
+            .filter(|&index| self.wanted[index])
+            .map(|index| self.delivered[index] + 1)
+            .min()
+            .unwrap_or(0);
+    }
+
+    async fn replay_page(&mut self, database: &Database) -> Result<Option<Vec<SlotUpdate>>> {
+        let rows = database.get_slots_from(self.replay_from, REPLAY_PAGE_SIZE).await?;
+        let Some(last) = rows.last() else {
+            return Ok(None);
+        };
+        self.replay_from = last.slot + 1;
+
+        let mut updates = Vec::new();
+        for row in rows {
+            let reached = [true, row.confirmed, row.finalized];
+            // Placeholder rows know neither their parent nor their hash
+            let (parent_slot, block_hash) = if database::is_placeholder_blockhash(&row.blockhash) {
+                (0, String::new())
+            } else {
+                (row.parent_slot, row.blockhash.clone())
+            };
+            for (index, commitment) in COMMITMENT_LEVELS.iter().enumerate() {
+                if reached[index] {
+                    updates.push(SlotUpdate {
+                        slot: row.slot,
+                        commitment: commitment.to_string(),
+                        timestamp: row.timestamp.timestamp(),
+                        parent_slot,
+                        block_hash: block_hash.clone(),
+                        block_height: 0,
+                        dropped_updates: 0,
+                        cursor: String::new(),
+                    });
+                }
+            }
+        }
+
+        Ok(Some(updates))
+    }
+
+    fn is_new(&self, update: &SlotUpdate) -> bool {
+        COMMITMENT_LEVELS.iter()
+            .position(|&level| level == update.commitment)
+            .is_some_and(|index| self.wanted[index] && update.slot > self.delivered[index])
+    }
+
+    fn advance(&mut self, update: &mut SlotUpdate) {
+        if let Some(index) = COMMITMENT_LEVELS.iter().position(|&level| level == update.commitment) {
+            self.delivered[index] = update.slot;
+        }
+    }
+
+    fn cursor(&self) -> String {
+        format!("slots:{}:{}:{}", self.delivered[0], self.delivered[1], self.delivered[2])
+    }
+}
+
+/// Last leader change delivered to a leader subscription
+struct LeaderPosition {
+    delivered: u64,
+    leader: String,
+    resuming: bool,
+    replay_from: u64,
+}
+
+impl LeaderPosition {
+    fn from_request(request: &SlotLeaderSubscriptionRequest) -> Result<Self, Status> {
+        let (delivered, leader, resuming) = if !request.cursor.is_empty() {
+            let (slot, leader) = parse_leader_cursor(&request.cursor)?;
+            (slot, leader, true)
+        } else if request.start_slot > 0 {
+            (request.start_slot - 1, String::new(), true)
+        } else {
+            (0, String::new(), false)
+        };
+
+        Ok(Self { delivered, leader, resuming, replay_from: 0 })
+    }
+}
+
+#[tonic::async_trait]
+impl StreamPosition for LeaderPosition {
+    type Update = SlotLeaderUpdate;
+
+    fn is_resuming(&self) -> bool {
+        self.resuming
+    }
+
+    fn rewind(&mut self) {
+        self.replay_from = self.delivered + 1;
+    }
+
+    async fn replay_page(&mut self, database: &Database) -> Result<Option<Vec<SlotLeaderUpdate>>> {
+        let rows = database.get_slot_leaders_from(self.replay_from, REPLAY_PAGE_SIZE).await?;
+        let Some(last) = rows.last() else {
+            return Ok(None);
+        };
+        self.replay_from = last.slot + 1;
 
-        // Fetch real leader updates from Solana RPC
+        // Only changes are streamed, so collapse consecutive slots of the same leader
+        let mut previous = self.leader.clone();
         let mut updates = Vec::new();
-        let slot_tracker = self.slot_tracker.read().await;
-        let current_slot = slot_tracker.get_current_slot().await;
-
-        // Get real leader information for current slot
-        if let Ok(leaders) = slot_tracker.get_slot_leaders(current_slot, 1).await {
-            if let Some(leader) = leaders.first() {
-                let update = SlotLeaderUpdate {
-                    slot: current_slot,
-                    leader_pubkey: leader.clone(),
-                    previous_leader: "".to_string(), // We don't have previous leader info easily
-                    timestamp: std::time::SystemTime::now()
-                        .duration_since(std::time::UNIX_EPOCH)
-                        .unwrap()
-                        .as_secs() as i64,
+        for row in rows {
+            if row.leader_pubkey != previous {
+                updates.push(SlotLeaderUpdate {
+                    slot: row.slot,
+                    leader_pubkey: row.leader_pubkey.clone(),
+                    previous_leader: std::mem::replace(&mut previous, row.leader_pubkey),
+                    timestamp: chrono::Utc::now().timestamp(),
+                    dropped_updates: 0,
+                    cursor: String::new(),
+                });
+            }
+        }
+
+        Ok(Some(updates))
+    }
+
+    fn is_new(&self, update: &SlotLeaderUpdate) -> bool {
+        update.slot > self.delivered && update.leader_pubkey != self.leader
+    }
+
+    fn advance(&mut self, update: &mut SlotLeaderUpdate) {
+        // Report the change relative to what this subscriber last saw
+        if !self.leader.is_empty() {
+            update.previous_leader = self.leader.clone();
+        }
+        self.delivered = update.slot;
+        self.leader = update.leader_pubkey.clone();
+    }
+
+    fn cursor(&self) -> String {
+        format!("leaders:{}:{}", self.delivered, self.leader)
+    }
+}
+
+/// Last transaction delivered to a transaction subscription. Replay walks
+/// the `transactions` table in `(slot, signature)` order while live updates
+/// arrive in block order, so the current slot's deliveries are also tracked
+/// by signature.
+struct TransactionPosition {
+    filter: TransactionSubscriptionRequest,
+    finalized_only: bool,
+    /// Slot of the last delivered transaction
+    slot: u64,
+    /// Transactions of `slot` up to this signature were delivered before resuming
+    resumed_through: String,
+    /// Transactions of `slot` up to this signature were delivered
+    delivered_through: String,
+    /// Signatures of `slot` delivered by this subscription
+    delivered: HashSet<String>,
+    replaying: bool,
+    resuming: bool,
+    replay_from: (u64, String),
+}
+
+impl TransactionPosition {
+    fn from_request(request: &TransactionSubscriptionRequest) -> Result<Self, Status> {
+        let (slot, signature, resuming) = if !request.cursor.is_empty() {
+            let (slot, signature) = parse_transaction_cursor(&request.cursor)?;
+            (slot, signature, true)
+        } else if request.from_slot > 0 {
+            (request.from_slot, String::new(), true)
+        } else {
+            (0, String::new(), false)
+        };
+
+        Ok(Self {
+            filter: request.clone(),
+            finalized_only: request.commitment == COMMITMENT_FINALIZED,
+            slot,
+            resumed_through: signature.clone(),
+            delivered_through: signature,
+            delivered: HashSet::new(),
+            replaying: false,
+            resuming,
+            replay_from: (0, String::new()),
+        })
+    }
+
+    /// Apply the subscription's filter to a replayed transaction, as Yellowstone does live
+    fn matches(&self, update: &TransactionUpdate) -> bool {
+        let filter = &self.filter;
+        let touches = |account: &String| update.account_keys.contains(account);
+
+        (filter.account_include.is_empty() || filter.account_include.iter().any(touches))
+            && !filter.account_exclude.iter().any(touches)
+            && filter.account_required.iter().all(touches)
+            && filter.vote.is_none_or(|vote| vote == update.is_vote)
+            && filter.failed.is_none_or(|failed| failed == update.failed)
+    }
+}
+
+#[tonic::async_trait]
+impl StreamPosition for TransactionPosition {
+    type Update = TransactionUpdate;
+
+    fn is_resuming(&self) -> bool {
+        self.resuming
+    }
+
+    fn rewind(&mut self) {
+        self.replay_from = (self.slot, self.delivered_through.clone());
+        self.replaying = true;
+    }
+
+    async fn replay_page(&mut self, database: &Database) -> Result<Option<Vec<TransactionUpdate>>> {
+        let (from_slot, after_signature) = &self.replay_from;
+        let rows = database.get_transactions_after(*from_slot, after_signature, self.finalized_only, REPLAY_PAGE_SIZE).await?;
+        let Some((last, _)) = rows.last() else {
+            self.replaying = false;
+            return Ok(None);
+        };
+        self.replay_from = (last.slot, last.signature.clone());
+
+        let updates = rows.into_iter()
+            .map(|(transaction, account_keys)| TransactionUpdate {
+                is_vote: transaction.program_ids.iter().any(|program| program == VOTE_PROGRAM_ID),
+                failed: transaction.status != "success",
+                signature: transaction.signature,
+                slot: transaction.slot,
+                // Block positions aren't stored
+                index: 0,
+                fee: transaction.fee,
+                account_keys,
+                timestamp: transaction.timestamp.timestamp(),
+                cursor: String::new(),
+            })
+            .filter(|update| self.matches(update))
+            .collect();
+
+        Ok(Some(updates))
+    }
+
+    fn is_new(&self, update: &TransactionUpdate) -> bool {
+        update.slot > self.slot
+            || (update.slot == self.slot
+                && update.signature > self.resumed_through
+                && !self.delivered.contains(&update.signature))
+    }
+
+    fn advance(&mut self, update: &mut TransactionUpdate) {
+        if update.slot != self.slot {
+            self.slot = update.slot;
+            self.resumed_through.clear();
+            self.delivered_through.clear();
+            self.delivered.clear();
+        }
+        self.delivered.insert(update.signature.clone());
+
+        // Only replay goes through a slot in signature order, after a live
+        // update the cursor falls back to what is known to be delivered
+        self.delivered_through = if self.replaying {
+            update.signature.clone()
+        } else {
+            self.resumed_through.clone()
+        };
+    }
+
+    fn cursor(&self) -> String {
+        format!("transactions:{}:{}", self.slot, self.delivered_through)
+    }
+}
+
+fn parse_slot_cursor(cursor: &str) -> Result<[u64; 3], Status> {
+    let malformed = || Status::invalid_argument(format!("malformed slot cursor '{}'", cursor));
+
+    let mut parts = cursor.split(':');
+    if parts.next() != Some("slots") {
+        return Err(malformed());
+    }
+
+    let mut delivered = [0u64; 3];
+    for slot in delivered.iter_mut() {
+        *slot = parts.next().and_then(|part| part.parse().ok()).ok_or_else(malformed)?;
+    }
+    if parts.next().is_some() {
+        return Err(malformed());
+    }
+
+    Ok(delivered)
+}
+
+fn parse_transaction_cursor(cursor: &str) -> Result<(u64, String), Status> {
+    let malformed = || Status::invalid_argument(format!("malformed transaction cursor '{}'", cursor));
+
+    let mut parts = cursor.splitn(3, ':');
+    if parts.next() != Some("transactions") {
+        return Err(malformed());
+    }
+    let slot = parts.next().and_then(|part| part.parse().ok()).ok_or_else(malformed)?;
+    let signature = parts.next().ok_or_else(malformed)?.to_string();
+
+    Ok((slot, signature))
+}
+
+fn parse_leader_cursor(cursor: &str) -> Result<(u64, String), Status> {
+    let malformed = || Status::invalid_argument(format!("malformed leader cursor '{}'", cursor));
+
+    let mut parts = cursor.splitn(3, ':');
+    if parts.next() != Some("leaders") {
+        return Err(malformed());
+    }
+    let slot = parts.next().and_then(|part| part.parse().ok()).ok_or_else(malformed)?;
+    let leader = parts.next().ok_or_else(malformed)?.to_string();
+
+    Ok((slot, leader))
+}
+
+/// Forward broadcast updates to a single subscriber without ever blocking the
+/// fan-out. Live-only subscribers that fall behind lose updates and get the
+/// count on the next delivered message. Resuming subscribers instead catch up
+/// from the database, first from their requested position and again whenever
+/// they fall behind, so their stream stays gap-free.
+async fn forward_updates<P, F>(
+    mut updates: broadcast::Receiver<P::Update>,
+    tx: mpsc::Sender<Result<P::Update, Status>>,
+    replay: Option<Arc<Database>>,
+    mut position: P,
+    _permit: StreamPermit,
+    tag: &'static str,
+    mut filter: F,
+) where
+    P: StreamPosition,
+    F: FnMut(&P::Update) -> StreamFilter + Send + 'static,
+{
+    let mut dropped = 0u64;
+    let mut catching_up = replay.is_some();
+
+    'stream: loop {
+        if let (true, Some(database)) = (catching_up, replay.as_ref()) {
+            position.rewind();
+
+            loop {
+                let page = match position.replay_page(database).await {
+                    Ok(Some(page)) => page,
+                    Ok(None) => break,
+                    Err(e) => {
+                        let _ = tx.send(Err(Status::internal(format!("Replay failed: {}", e)))).await;
+                        break 'stream;
+                    }
                 };
-                updates.push(update);
+
+                for mut update in page {
+                    match filter(&update) {
+                        StreamFilter::Send => {}
+                        StreamFilter::Skip => continue,
+                        StreamFilter::Finish => break 'stream,
+                    }
+                    if !position.is_new(&update) {
+                        continue;
+                    }
+
+                    position.advance(&mut update);
+                    update.set_cursor(position.cursor());
+                    if tx.send(Ok(update)).await.is_err() {
+                        break 'stream; // Client went away
+                    }
+                }
             }
+
+            catching_up = false;
         }
 
-        let duration = start_time.elapsed();
-        debug!("{} {} | Subscribe leaders: {}μs",
-            "👑".bright_blue(),
-            "SUBSCRIBE_LEADERS".bright_blue(),
-            duration.as_micros()
-        );
+        let mut update = match updates.recv().await {
+            Ok(update) => update,
+            Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                if replay.is_some() {
+                    catching_up = true;
+                } else {
+                    dropped += skipped;
+                }
+                continue;
+            }
+            Err(broadcast::error::RecvError::Closed) => break,
+        };
+
+        match filter(&update) {
+            StreamFilter::Send => {}
+            StreamFilter::Skip => continue,
+            StreamFilter::Finish => break,
+        }
+        if !position.is_new(&update) {
+            continue;
+        }
+
+        let permit = match tx.try_reserve() {
+            Ok(permit) => permit,
+            Err(mpsc::error::TrySendError::Full(())) => {
+                if replay.is_some() {
+                    catching_up = true;
+                } else {
+                    dropped += 1;
+                }
+                continue;
+            }
+            Err(mpsc::error::TrySendError::Closed(())) => break, // Client went away
+        };
+
+        if dropped > 0 {
+            warn!("{} {} | Slow subscriber dropped {} updates",
+                "⚠️".bright_yellow(),
+                tag.bright_yellow(),
+                dropped
+            );
+        }
+
+        position.advance(&mut update);
+        update.set_cursor(position.cursor());
+        update.set_dropped_updates(std::mem::take(&mut dropped));
+        permit.send(Ok(update));
+    }
+
+    debug!("{} {} | Subscriber stream closed", "📡".bright_blue(), tag.bright_blue());
+}
+
+/// Replay indexed transactions from `position` when resuming, then forward
+/// the live Yellowstone updates that weren't replayed. The upstream is
+/// subscribed first and held back by backpressure meanwhile, so nothing is
+/// missed at the switch-over.
+async fn forward_transactions(
+    mut live: mpsc::Receiver<Result<TransactionUpdate, Status>>,
+    tx: mpsc::Sender<Result<TransactionUpdate, Status>>,
+    replay: Option<Arc<Database>>,
+    mut position: TransactionPosition,
+) {
+    if let Some(database) = replay {
+        position.rewind();
+
+        loop {
+            let page = match position.replay_page(&database).await {
+                Ok(Some(page)) => page,
+                Ok(None) => break,
+                Err(e) => {
+                    let _ = tx.send(Err(Status::internal(format!("Replay failed: {}", e)))).await;
+                    return;
+                }
+            };
+
+            for mut update in page {
+                if !position.is_new(&update) {
+                    continue;
+                }
+                position.advance(&mut update);
+                update.set_cursor(position.cursor());
+                if tx.send(Ok(update)).await.is_err() {
+                    return; // Client went away
+                }
+            }
+        }
+    }
 
-        Ok(tonic::Response::new(updates))
+    while let Some(update) = live.recv().await {
+        let mut update = match update {
+            Ok(update) => update,
+            Err(status) => {
+                let _ = tx.send(Err(status)).await;
+                break;
+            }
+        };
+        if !position.is_new(&update) {
+            continue;
+        }
+
+        position.advance(&mut update);
+        update.set_cursor(position.cursor());
+        if tx.send(Ok(update)).await.is_err() {
+            break; // Client went away
+        }
+    }
+}
+
+/// Reject filters containing anything that isn't a valid base58 pubkey
+fn validate_pubkeys(addresses: &[String]) -> Result<(), Status> {
+    for address in addresses {
+        if Pubkey::from_str(address).is_err() {
+            return Err(Status::invalid_argument(format!("invalid pubkey '{}'", address)));
+        }
+    }
+    Ok(())
+}
+
+/// Map a commitment name from a subscription request to a Yellowstone level
+fn parse_commitment(commitment: &str) -> Result<CommitmentLevel, Status> {
+    match commitment {
+        "" | COMMITMENT_PROCESSED => Ok(CommitmentLevel::Processed),
+        COMMITMENT_CONFIRMED => Ok(CommitmentLevel::Confirmed),
+        COMMITMENT_FINALIZED => Ok(CommitmentLevel::Finalized),
+        other => Err(Status::invalid_argument(format!("unknown commitment '{}'", other))),
     }
 }
 
@@ -558,75 +1872,261 @@ impl Clone for SolanaIndexerService {
             database: self.database.clone(),
             config: self.config.clone(),
             slot_tracker: self.slot_tracker.clone(),
+            slot_updates: self.slot_updates.clone(),
+            leader_updates: self.leader_updates.clone(),
+            reorg_updates: self.reorg_updates.clone(),
+            metrics: self.metrics.clone(),
+            quotas: self.quotas.clone(),
+            rpc: self.rpc.clone(),
         }
     }
 }
 
 /// Start the gRPC server (for CLI compatibility)
-pub async fn start_server(bind_addr: String, port: u16, _client: RpcClient) -> Result<()> {
-    info!("{} {} | Starting gRPC server on {}:{}",
-        "🚀".bright_green(),
-        "GRPC_SERVER".bright_green(),
-        bind_addr.bright_cyan(),
-        port.to_string().bright_cyan()
-    );
-
-    // For now, just log that the server would start
-    // In a full implementation, this would start the actual gRPC server
-    info!("{} {} | gRPC server would start here (use 'grpc start' command)",
-        "ℹ️".bright_blue(),
-        "INFO".bright_blue()
-    );
-
-    Ok(())
+pub async fn start_server(bind_addr: String, port: u16, client: RpcClient, config: &Config) -> Result<()> {
+    start_enhanced_server(bind_addr, port, client, config, true, false, None).await
 }
 
 /// Start enhanced gRPC server (for CLI compatibility)
 pub async fn start_enhanced_server(
     bind_addr: String,
     port: u16,
-    _client: RpcClient,
-    _config: &crate::config::Config,
+    client: RpcClient,
+    config: &Config,
     _enable_solana: bool,
-    _enable_flow: bool,
+    enable_flow: bool,
+    tls: Option<ServerTls>,
 ) -> Result<()> {
-    info!("{} {} | Enhanced gRPC server would start on {}:{}",
-        "🚀".bright_green(),
-        "ENHANCED_GRPC".bright_green(),
-        bind_addr.bright_cyan(),
-        port.to_string().bright_cyan()
-    );
+    let addr: SocketAddr = format!("{}:{}", bind_addr, port).parse()?;
 
-    Ok(())
+    if enable_flow {
+        warn!("{} {} | Flow streaming is not served over gRPC yet, ignoring --enable-flow",
+            "⚠️".bright_yellow(),
+            "ENHANCED_GRPC".bright_yellow()
+        );
+    }
+
+    let config = Arc::new(config.clone());
+    let cache = Arc::new(IndexerCache::new((*config).clone()));
+    let database = Arc::new(Database::new(&config.database_config).await?);
+    let slot_tracker = Arc::new(RwLock::new(SlotTracker::new(
+        client,
+        config.monitoring_config.track_slot_leaders,
+        config.monitoring_config.track_finalized_only,
+        config.update_interval_ms,
+    )));
+
+    SolanaIndexerService::new(cache, database, config, slot_tracker)
+        .start(addr, tls)
+        .await
+}
+
+/// Connect to a running indexer gRPC server, over TLS when `tls` is set
+async fn connect_channel(address: &str, tls: Option<ClientTlsConfig>) -> Result<Channel> {
+    let mut endpoint = Channel::from_shared(address.to_string())?
+        .connect_timeout(Duration::from_secs(5));
+    if let Some(tls) = tls {
+        endpoint = endpoint.tls_config(tls)?;
+    }
+    Ok(endpoint.connect().await?)
+}
+
+/// Wrap a request with the configured API key, if any
+fn with_credentials<T>(message: T, config: &Config) -> Result<Request<T>> {
+    let mut request = Request::new(message);
+    if let Some(api_key) = config.get_api_key() {
+        request.metadata_mut().insert(API_KEY_HEADER, api_key.parse()?);
+    }
+    Ok(request)
+}
+
+/// Query the standard health service for `service` ("" means the whole server)
+async fn check_health(channel: Channel, service: &str) -> Result<ServingStatus> {
+    let response = HealthClient::new(channel)
+        .check(HealthCheckRequest { service: service.to_string() })
+        .await?;
+    Ok(response.into_inner().status())
+}
+
+/// List the services advertised through gRPC reflection
+async fn list_reflected_services(channel: Channel) -> Result<Vec<String>> {
+    let request = ServerReflectionRequest {
+        host: String::new(),
+        message_request: Some(MessageRequest::ListServices(String::new())),
+    };
+
+    let mut responses = ServerReflectionClient::new(channel)
+        .server_reflection_info(tokio_stream::iter(vec![request]))
+        .await?
+        .into_inner();
+
+    match responses.message().await?.and_then(|response| response.message_response) {
+        Some(MessageResponse::ListServicesResponse(list)) => {
+            Ok(list.service.into_iter().map(|service| service.name).collect())
+        }
+        Some(MessageResponse::ErrorResponse(error)) => {
+            Err(anyhow::anyhow!("Reflection error {}: {}", error.error_code, error.error_message))
+        }
+        _ => Err(anyhow::anyhow!("Unexpected reflection response")),
+    }
+}
+
+fn print_indexer_status(status: &IndexerStatus) {
+    println!("   {} {}", "Tip Slot:".bright_white(), status.tip_slot.to_string().bright_cyan());
+    println!("   {} {}", "Indexed Slot:".bright_white(), status.indexed_slot.to_string().bright_cyan());
+    let lag = format!("{} slots", status.lag_slots);
+    println!("   {} {}", "Lag:".bright_white(), if status.lag_slots > 150 { lag.bright_red() } else { lag.bright_green() });
+    println!("   {} {}", "Cache Hit Ratio:".bright_white(), format!("{:.1}%", status.cache_hit_ratio * 100.0).bright_yellow());
+    println!("   {} {}", "Requests:".bright_white(), status.total_requests.to_string().bright_cyan());
+    println!("   {} {}", "Avg Response:".bright_white(), format!("{:.3}ms", status.average_response_time_ms).bright_cyan());
+    println!("   {} {}", "Requests/sec:".bright_white(), format!("{:.2}", status.requests_per_second).bright_cyan());
+    if status.database_connected {
+        println!("   {} {}", "Database:".bright_white(), "Connected".bright_green());
+    } else {
+        println!("   {} {} ({})", "Database:".bright_white(), "Unreachable".bright_red(), status.database_error.bright_red());
+    }
+    println!("   {} {}s", "Uptime:".bright_white(), status.uptime_seconds.to_string().bright_cyan());
 }
 
-/// Show gRPC server status (for CLI compatibility)
-pub async fn show_status() -> Result<()> {
+/// Show the status of a running gRPC server
+pub async fn show_status(address: &str, config: &Config, tls: Option<ClientTlsConfig>) -> Result<()> {
     println!("{} {} | gRPC Server Status",
         "📊".bright_cyan().bold(),
         "GRPC_STATUS".bright_cyan()
     );
     println!();
-    println!("{} {} | Server is ready for gRPC operations",
-        "✅".bright_green(),
-        "STATUS".bright_green()
-    );
-    println!("{} {} | Use 'grpc start' to start the server",
-        "💡".bright_yellow(),
-        "TIP".bright_yellow()
-    );
+
+    let channel = match connect_channel(address, tls).await {
+        Ok(channel) => channel,
+        Err(e) => {
+            println!("{} {} | Server at {} is not reachable: {}",
+                "❌".bright_red(),
+                "STATUS".bright_red(),
+                address.bright_cyan(),
+                e
+            );
+            println!("{} {} | Use 'grpc start' to start the server",
+                "💡".bright_yellow(),
+                "TIP".bright_yellow()
+            );
+            return Ok(());
+        }
+    };
+
+    match check_health(channel.clone(), "").await {
+        Ok(ServingStatus::Serving) => println!("{} {} | Server at {} is serving",
+            "✅".bright_green(),
+            "STATUS".bright_green(),
+            address.bright_cyan()
+        ),
+        Ok(status) => println!("{} {} | Server at {} reports {}",
+            "⚠️".bright_yellow(),
+            "STATUS".bright_yellow(),
+            address.bright_cyan(),
+            status.as_str_name()
+        ),
+        Err(e) => println!("{} {} | Health check failed: {}",
+            "❌".bright_red(),
+            "STATUS".bright_red(),
+            e
+        ),
+    }
+
+    let status = SolanaIndexerClient::new(channel)
+        .get_indexer_status(with_credentials(GetIndexerStatusRequest {}, config)?)
+        .await?
+        .into_inner();
+
+    println!();
+    print_indexer_status(&status);
 
     Ok(())
 }
 
-/// Test gRPC client (for CLI compatibility)
-pub async fn test_grpc_client(_address: &str) -> Result<()> {
+/// Exercise health, reflection and indexer RPCs against a running server
+pub async fn test_grpc_client(address: &str, config: &Config, tls: Option<ClientTlsConfig>) -> Result<()> {
     println!("{} {} | gRPC Client Test",
         "🔧".bright_cyan().bold(),
         "GRPC_TEST".bright_cyan()
     );
     println!();
-    println!("{} {} | gRPC client test completed",
+
+    let channel = connect_channel(address, tls).await?;
+    let mut failures = 0;
+
+    let start_time = std::time::Instant::now();
+    match check_health(channel.clone(), "solana_indexer.SolanaIndexer").await {
+        Ok(ServingStatus::Serving) => println!("   {} {} {}",
+            "✅ Health:".bright_green(),
+            "SERVING".bright_white(),
+            format!("({}ms)", start_time.elapsed().as_millis()).bright_cyan()
+        ),
+        Ok(status) => {
+            failures += 1;
+            println!("   {} {}", "❌ Health:".bright_red(), status.as_str_name().bright_white());
+        }
+        Err(e) => {
+            failures += 1;
+            println!("   {} {}", "❌ Health:".bright_red(), e.to_string().bright_white());
+        }
+    }
+
+    let start_time = std::time::Instant::now();
+    match list_reflected_services(channel.clone()).await {
+        Ok(services) => println!("   {} {} {}",
+            "✅ Reflection:".bright_green(),
+            services.join(", ").bright_white(),
+            format!("({}ms)", start_time.elapsed().as_millis()).bright_cyan()
+        ),
+        Err(e) => {
+            failures += 1;
+            println!("   {} {}", "❌ Reflection:".bright_red(), e.to_string().bright_white());
+        }
+    }
+
+    let mut client = SolanaIndexerClient::new(channel);
+
+    let start_time = std::time::Instant::now();
+    match client.get_current_slot(with_credentials(GetCurrentSlotRequest {}, config)?).await {
+        Ok(response) => {
+            let slot_info = response.into_inner();
+            println!("   {} {} {}",
+                "✅ GetCurrentSlot:".bright_green(),
+                format!("slot {} (confirmed {}, finalized {})",
+                    slot_info.current_slot,
+                    slot_info.confirmed_slot,
+                    slot_info.finalized_slot
+                ).bright_white(),
+                format!("({}ms)", start_time.elapsed().as_millis()).bright_cyan()
+            );
+        }
+        Err(e) => {
+            failures += 1;
+            println!("   {} {}", "❌ GetCurrentSlot:".bright_red(), e.message().bright_white());
+        }
+    }
+
+    let start_time = std::time::Instant::now();
+    match client.get_indexer_status(with_credentials(GetIndexerStatusRequest {}, config)?).await {
+        Ok(response) => {
+            println!("   {} {}",
+                "✅ GetIndexerStatus:".bright_green(),
+                format!("({}ms)", start_time.elapsed().as_millis()).bright_cyan()
+            );
+            print_indexer_status(&response.into_inner());
+        }
+        Err(e) => {
+            failures += 1;
+            println!("   {} {}", "❌ GetIndexerStatus:".bright_red(), e.message().bright_white());
+        }
+    }
+
+    println!();
+    if failures > 0 {
+        return Err(anyhow::anyhow!("{} gRPC check(s) failed against {}", failures, address));
+    }
+
+    println!("{} {} | All gRPC checks passed",
         "✅".bright_green(),
         "TEST_COMPLETE".bright_green()
     );
@@ -641,6 +2141,7 @@ pub struct GrpcMetrics {
     pub cache_misses: u64,
     pub average_response_time: std::time::Duration,
     pub requests_per_second: f64,
+    pub started_at: std::time::Instant,
 }
 
 impl GrpcMetrics {
@@ -651,6 +2152,7 @@ impl GrpcMetrics {
             cache_misses: 0,
             average_response_time: std::time::Duration::ZERO,
             requests_per_second: 0.0,
+            started_at: std::time::Instant::now(),
         }
     }
 
@@ -666,6 +2168,11 @@ impl GrpcMetrics {
         // Update average response time
         let total_time = self.average_response_time * (self.total_requests - 1) as u32 + response_time;
         self.average_response_time = total_time / self.total_requests as u32;
+
+        let uptime = self.started_at.elapsed().as_secs_f64();
+        if uptime > 0.0 {
+            self.requests_per_second = self.total_requests as f64 / uptime;
+        }
     }
 
     pub fn get_cache_hit_ratio(&self) -> f64 {
@@ -676,3 +2183,145 @@ impl GrpcMetrics {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn slot_update(slot: u64, commitment: &str) -> SlotUpdate {
+        SlotUpdate { slot, commitment: commitment.to_string(), ..Default::default() }
+    }
+
+    fn leader_update(slot: u64, leader: &str) -> SlotLeaderUpdate {
+        SlotLeaderUpdate { slot, leader_pubkey: leader.to_string(), ..Default::default() }
+    }
+
+    #[test]
+    fn slot_cursor_round_trips() {
+        let mut position = SlotPosition::from_request(&SlotSubscriptionRequest::default()).unwrap();
+        assert!(!position.is_resuming());
+
+        position.advance(&mut slot_update(120, COMMITMENT_PROCESSED));
+        position.advance(&mut slot_update(110, COMMITMENT_CONFIRMED));
+        position.advance(&mut slot_update(90, COMMITMENT_FINALIZED));
+        assert_eq!(position.cursor(), "slots:120:110:90");
+
+        let resumed = SlotPosition::from_request(&SlotSubscriptionRequest {
+            cursor: position.cursor(),
+            from_slot: 5,
+            ..Default::default()
+        }).unwrap();
+        assert!(resumed.is_resuming());
+        assert_eq!(resumed.delivered, [120, 110, 90]);
+    }
+
+    #[test]
+    fn malformed_slot_cursors_are_rejected() {
+        for cursor in ["slots:1:2", "slots:1:2:3:4", "leaders:1:2:3", "slots:a:2:3"] {
+            let request = SlotSubscriptionRequest { cursor: cursor.to_string(), ..Default::default() };
+            let error = SlotPosition::from_request(&request).err().unwrap();
+            assert_eq!(error.code(), tonic::Code::InvalidArgument, "{}", cursor);
+        }
+    }
+
+    #[test]
+    fn slot_position_skips_delivered_and_unwanted_updates() {
+        let mut position = SlotPosition::from_request(&SlotSubscriptionRequest {
+            include_confirmed: true,
+            include_finalized: true,
+            from_slot: 100,
+            ..Default::default()
+        }).unwrap();
+
+        assert!(!position.is_new(&slot_update(150, COMMITMENT_PROCESSED)));
+        assert!(!position.is_new(&slot_update(99, COMMITMENT_CONFIRMED)));
+        assert!(position.is_new(&slot_update(100, COMMITMENT_CONFIRMED)));
+
+        position.advance(&mut slot_update(105, COMMITMENT_CONFIRMED));
+        assert!(!position.is_new(&slot_update(105, COMMITMENT_CONFIRMED)));
+        assert!(position.is_new(&slot_update(100, COMMITMENT_FINALIZED)));
+
+        // Replay restarts from the lowest wanted level
+        position.rewind();
+        assert_eq!(position.replay_from, 100);
+    }
+
+    #[test]
+    fn leader_cursor_keeps_colons_in_the_leader() {
+        let request = SlotLeaderSubscriptionRequest { cursor: "leaders:42:a:b".to_string(), ..Default::default() };
+        let position = LeaderPosition::from_request(&request).unwrap();
+        assert_eq!((position.delivered, position.leader.as_str()), (42, "a:b"));
+        assert_eq!(position.cursor(), "leaders:42:a:b");
+
+        let request = SlotLeaderSubscriptionRequest { cursor: "leaders:42".to_string(), ..Default::default() };
+        assert!(LeaderPosition::from_request(&request).is_err());
+    }
+
+    #[test]
+    fn leader_position_reports_changes_relative_to_the_subscriber() {
+        let mut position = LeaderPosition::from_request(&SlotLeaderSubscriptionRequest {
+            start_slot: 10,
+            ..Default::default()
+        }).unwrap();
+        assert!(position.is_resuming());
+
+        let mut first = leader_update(10, "A");
+        assert!(position.is_new(&first));
+        position.advance(&mut first);
+
+        assert!(!position.is_new(&leader_update(11, "A")));
+        assert!(!position.is_new(&leader_update(10, "B")));
+
+        let mut second = SlotLeaderUpdate { previous_leader: "C".to_string(), ..leader_update(14, "B") };
+        assert!(position.is_new(&second));
+        position.advance(&mut second);
+        assert_eq!(second.previous_leader, "A");
+        assert_eq!(position.cursor(), "leaders:14:B");
+    }
+
+    fn transaction_update(slot: u64, signature: &str) -> TransactionUpdate {
+        TransactionUpdate { slot, signature: signature.to_string(), ..Default::default() }
+    }
+
+    #[test]
+    fn transaction_cursor_skips_what_was_delivered_before_resuming() {
+        let request = TransactionSubscriptionRequest { cursor: "transactions:42:m".to_string(), from_slot: 5, ..Default::default() };
+        let mut position = TransactionPosition::from_request(&request).unwrap();
+        assert!(position.is_resuming());
+        assert_eq!(position.cursor(), "transactions:42:m");
+
+        assert!(!position.is_new(&transaction_update(41, "z")));
+        assert!(!position.is_new(&transaction_update(42, "a")));
+        assert!(position.is_new(&transaction_update(42, "n")));
+
+        // Live updates arrive in block order, so later cursors keep the resumed floor
+        let mut live = transaction_update(42, "x");
+        position.advance(&mut live);
+        assert!(!position.is_new(&transaction_update(42, "x")));
+        assert!(position.is_new(&transaction_update(42, "p")));
+        assert_eq!(position.cursor(), "transactions:42:m");
+
+        let request = TransactionSubscriptionRequest { cursor: "transactions:x:y".to_string(), ..Default::default() };
+        assert!(TransactionPosition::from_request(&request).is_err());
+    }
+
+    #[test]
+    fn transaction_replay_filter_matches_yellowstone_semantics() {
+        let position = TransactionPosition::from_request(&TransactionSubscriptionRequest {
+            account_include: vec!["A".to_string(), "B".to_string()],
+            account_exclude: vec!["X".to_string()],
+            failed: Some(false),
+            ..Default::default()
+        }).unwrap();
+        assert!(!position.is_resuming());
+
+        let with_accounts = |accounts: &[&str]| TransactionUpdate {
+            account_keys: accounts.iter().map(|account| account.to_string()).collect(),
+            ..transaction_update(1, "s")
+        };
+        assert!(position.matches(&with_accounts(&["B", "C"])));
+        assert!(!position.matches(&with_accounts(&["C"])));
+        assert!(!position.matches(&with_accounts(&["A", "X"])));
+        assert!(!position.matches(&TransactionUpdate { failed: true, ..with_accounts(&["A"]) }));
+    }
+}
@@ -0,0 +1,190 @@
+use anyhow::Result;
+use colored::*;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_response::RpcLeaderSchedule;
+use solana_sdk::epoch_schedule::EpochSchedule;
+use std::collections::BTreeMap;
+use tracing::{debug, info};
+
+/// Marks a slot the schedule has no leader for
+const NO_LEADER: u32 = u32::MAX;
+
+/// Epoch schedules kept at once; backfills walk epochs in order, so the
+/// oldest are dropped first
+const MAX_CACHED_EPOCHS: usize = 4;
+
+/// One epoch's schedule, indexed by slot
+struct EpochLeaders {
+    first_slot: u64,
+    validators: Vec<String>,
+    /// Index into `validators` for every slot of the epoch
+    slot_leaders: Vec<u32>,
+}
+
+impl EpochLeaders {
+    /// Index a `getLeaderSchedule` result, whose slot indices are relative to
+    /// the epoch's first slot
+    fn from_schedule(first_slot: u64, slots_in_epoch: u64, schedule: RpcLeaderSchedule) -> Self {
+        let mut validators = Vec::with_capacity(schedule.len());
+        let mut slot_leaders = vec![NO_LEADER; slots_in_epoch as usize];
+        for (validator, indices) in schedule {
+            let id = validators.len() as u32;
+            validators.push(validator);
+            for index in indices {
+                if let Some(leader) = slot_leaders.get_mut(index) {
+                    *leader = id;
+                }
+            }
+        }
+        Self { first_slot, validators, slot_leaders }
+    }
+
+    fn leader(&self, slot: u64) -> Option<&str> {
+        let index = *self.slot_leaders.get(slot.checked_sub(self.first_slot)? as usize)?;
+        self.validators.get(index as usize).map(String::as_str)
+    }
+}
+
+/// Leader schedules keyed by epoch, each fetched once with
+/// `getLeaderSchedule`. Slots are mapped to epochs with the cluster's
+/// `EpochSchedule`, itself fetched once.
+pub struct LeaderScheduleCache {
+    epoch_schedule: Option<EpochSchedule>,
+    /// `None` for epochs the RPC has no schedule for
+    epochs: BTreeMap<u64, Option<EpochLeaders>>,
+    /// Epoch of the latest slot looked up
+    latest_epoch: Option<u64>,
+}
+
+impl LeaderScheduleCache {
+    pub fn new() -> Self {
+        Self {
+            epoch_schedule: None,
+            epochs: BTreeMap::new(),
+            latest_epoch: None,
+        }
+    }
+
+    /// Epoch of the latest slot looked up
+    pub fn epoch(&self) -> Option<u64> {
+        self.latest_epoch
+    }
+
+    /// Leader of `slot` from the loaded schedules, without touching the RPC
+    pub fn leader(&self, slot: u64) -> Option<&str> {
+        let epoch = self.epoch_schedule.as_ref()?.get_epoch(slot);
+        self.epochs.get(&epoch)?.as_ref()?.leader(slot)
+    }
+
+    /// Leader of `slot`, loading its epoch's schedule the first time the epoch
+    /// is seen
+    pub async fn leader_for(&mut self, client: &RpcClient, slot: u64) -> Result<Option<String>> {
+        if self.epoch_schedule.is_none() {
+            self.epoch_schedule = Some(client.get_epoch_schedule().await?);
+        }
+        let epoch = self.epoch_schedule.as_ref().map_or(0, |schedule| schedule.get_epoch(slot));
+        if self.latest_epoch.is_none_or(|latest| epoch > latest) {
+            self.latest_epoch = Some(epoch);
+        }
+
+        if !self.epochs.contains_key(&epoch) {
+            let leaders = self.load(client, epoch).await?;
+            self.insert(epoch, leaders);
+        }
+        Ok(self.leader(slot).map(str::to_string))
+    }
+
+    fn insert(&mut self, epoch: u64, leaders: Option<EpochLeaders>) {
+        self.epochs.insert(epoch, leaders);
+        while self.epochs.len() > MAX_CACHED_EPOCHS {
+            self.epochs.pop_first();
+        }
+    }
+
+    async fn load(&self, client: &RpcClient, epoch: u64) -> Result<Option<EpochLeaders>> {
+        let Some(epoch_schedule) = &self.epoch_schedule else {
+            return Ok(None);
+        };
+        let first_slot = epoch_schedule.get_first_slot_in_epoch(epoch);
+        let slots_in_epoch = epoch_schedule.get_slots_in_epoch(epoch);
+
+        let Some(schedule) = client.get_leader_schedule(Some(first_slot)).await? else {
+            debug!("No leader schedule available for epoch {}", epoch);
+            return Ok(None);
+        };
+
+        let leaders = EpochLeaders::from_schedule(first_slot, slots_in_epoch, schedule);
+        info!("{} {} | Loaded leader schedule for epoch {} ({} validators, slots {}..{})",
+            "👑".bright_green(),
+            "LEADER_SCHEDULE".bright_green(),
+            epoch,
+            leaders.validators.len(),
+            first_slot,
+            first_slot + slots_in_epoch
+        );
+
+        Ok(Some(leaders))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 256-slot epochs after a warmup of 32, 64 and 128 slots, so epoch 3
+    /// starts at slot 224
+    fn cache_with_warmup() -> LeaderScheduleCache {
+        LeaderScheduleCache {
+            epoch_schedule: Some(EpochSchedule::custom(256, 256, true)),
+            ..LeaderScheduleCache::new()
+        }
+    }
+
+    fn schedule(entries: &[(&str, &[usize])]) -> RpcLeaderSchedule {
+        entries.iter().map(|(validator, indices)| (validator.to_string(), indices.to_vec())).collect()
+    }
+
+    #[test]
+    fn slots_map_to_their_epoch_and_index() {
+        let epoch_schedule = EpochSchedule::custom(256, 256, true);
+        assert_eq!((epoch_schedule.get_epoch(223), epoch_schedule.get_epoch(224)), (2, 3));
+        assert_eq!(epoch_schedule.get_first_slot_in_epoch(3), 224);
+
+        let mut cache = cache_with_warmup();
+        cache.insert(2, Some(EpochLeaders::from_schedule(96, 128, schedule(&[("late", &[127])]))));
+        cache.insert(3, Some(EpochLeaders::from_schedule(224, 256, schedule(&[("first", &[0, 1]), ("last", &[255])]))));
+
+        // Either side of the epoch boundary resolves in its own epoch
+        assert_eq!(cache.leader(223), Some("late"));
+        assert_eq!(cache.leader(224), Some("first"));
+        assert_eq!(cache.leader(225), Some("first"));
+        assert_eq!(cache.leader(479), Some("last"));
+        assert_eq!(cache.leader(480), None);
+        assert_eq!(cache.leader(226), None);
+    }
+
+    #[test]
+    fn out_of_range_indices_are_ignored() {
+        let leaders = EpochLeaders::from_schedule(32, 64, schedule(&[("validator", &[63, 64])]));
+        assert_eq!(leaders.leader(95), Some("validator"));
+        assert_eq!(leaders.leader(96), None);
+        assert_eq!(leaders.leader(31), None);
+    }
+
+    #[test]
+    fn oldest_epochs_are_evicted() {
+        let mut cache = LeaderScheduleCache {
+            epoch_schedule: Some(EpochSchedule::custom(32, 32, false)),
+            ..LeaderScheduleCache::new()
+        };
+        for epoch in 0..=MAX_CACHED_EPOCHS as u64 {
+            let first_slot = epoch * 32;
+            cache.insert(epoch, Some(EpochLeaders::from_schedule(first_slot, 32, schedule(&[("validator", &[0])]))));
+        }
+
+        assert_eq!(cache.epochs.len(), MAX_CACHED_EPOCHS);
+        assert!(!cache.epochs.contains_key(&0));
+        assert_eq!(cache.leader(0), None);
+        assert_eq!(cache.leader(32), Some("validator"));
+    }
+}
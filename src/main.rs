@@ -1,10 +1,10 @@
 use anyhow::Result;
-use clap::{Parser, Subcommand, ValueHint, ValueEnum, CommandFactory, builder::styling::{AnsiColor, Effects, Styles}};
+use clap::{Args, Parser, Subcommand, ValueHint, ValueEnum, CommandFactory, builder::styling::{AnsiColor, Effects, Styles}};
 use clap_complete::{generate, Generator, Shell};
 use colored::*;
 use dotenv::dotenv;
 use solana_client::rpc_client::RpcClient;
-use std::{env, io};
+use std::{env, io, path::PathBuf};
 use tracing::{info, error};
 use tracing_subscriber::{EnvFilter, fmt::format::FmtSpan};
 
@@ -14,25 +14,36 @@ const PUMP_FUN_FEE_ACCOUNT: &str = "CebN5WGQ4jvEPvsVU4EoHEpgzq1VV7AbicfhtW4xC9iM
 const PUMP_FUN_PROGRAM: &str = "6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P";
 
 mod account_watcher;
+mod activity_classifier;
 mod animations;
 mod api;
+mod backfill;
 mod cache;
 mod config;
 mod database;
 mod enhanced_logger;
 
 mod flow_monitor;
+mod grpc_auth;
 mod grpc_server;
 mod ipfs;
 mod ipfs_storage;
+mod leader_schedule;
 mod logger;
 mod metrics;
 mod performance_benchmark;
+mod program_decoders;
+mod pump_fun;
+mod reorg;
+mod slot_lifecycle;
 mod slot_tracker;
+mod tls;
+mod transaction_decoder;
 
 mod wallet_tracker;
 mod webhooks;
 mod yellowstone_monitor;
+mod yellowstone_subscription;
 
 fn get_styles() -> Styles {
     Styles::styled()
@@ -86,7 +97,16 @@ struct Cli {
     )]
     port: u16,
 
-
+    ///  Anchor IDL JSON file or directory of IDLs for decoding program instructions
+    #[arg(
+        long,
+        value_name = "PATH",
+        env = "ANCHOR_IDL_PATH",
+        value_delimiter = ',',
+        value_hint = ValueHint::AnyPath,
+        help_heading = "Decoding Options"
+    )]
+    idl: Vec<std::path::PathBuf>,
 
 
 
@@ -249,6 +269,9 @@ enum Commands {
         ///  List currently monitored accounts
         #[arg(long)]
         list_accounts: bool,
+
+        #[command(flatten)]
+        subscription: SubscriptionArgs,
     },
 
     ///  Performance metrics & monitoring
@@ -300,10 +323,14 @@ enum TrackTarget {
         #[arg(short, long, help_heading = "Tracking Options")]
         finalized_only: bool,
 
-        ///  Update interval in milliseconds (default: 400ms)
+        ///  Update interval in milliseconds (default: 400ms), also used while the websocket is down
         #[arg(short, long, default_value = "400", value_hint = ValueHint::Other, help_heading = "Performance")]
         interval: u64,
 
+        ///  Where new slots come from: interval polling, websocket push (slotSubscribe) or Yellowstone Geyser
+        #[arg(long, value_enum, default_value = "poll", help_heading = "Performance")]
+        source: slot_tracker::SlotSource,
+
         ///  Enable detailed transaction information
         #[arg(short, long, help_heading = "Data Options")]
         transactions: bool,
@@ -652,19 +679,206 @@ enum GrpcAction {
         /// Enable Flow blockchain streaming
         #[arg(long)]
         enable_flow: bool,
+
+        #[command(flatten)]
+        tls: ServerTlsArgs,
     },
 
     ///  Show gRPC server status
-    Status,
+    Status {
+        /// gRPC server address to query (use https:// for TLS)
+        #[arg(short, long, default_value = "http://127.0.0.1:50051")]
+        address: String,
+
+        #[command(flatten)]
+        tls: ClientTlsArgs,
+    },
 
     ///  Test gRPC endpoints
     Test {
-        /// gRPC server address to test
+        /// gRPC server address to test (use https:// for TLS)
         #[arg(short, long, default_value = "http://127.0.0.1:50051")]
         address: String,
+
+        #[command(flatten)]
+        tls: ClientTlsArgs,
+    },
+
+    ///  Generate a local CA with server and client certificates
+    GenerateCerts {
+        /// Directory to write the PEM files to
+        #[arg(short, long, default_value = "certs", value_hint = ValueHint::DirPath)]
+        out_dir: PathBuf,
+
+        /// Hostnames / IPs the server certificate is valid for
+        #[arg(long, value_delimiter = ',', default_value = "localhost,127.0.0.1")]
+        hosts: Vec<String>,
     },
 }
 
+#[derive(Args)]
+struct ServerTlsArgs {
+    /// Server certificate chain (PEM); enables TLS together with --tls-key
+    #[arg(long, env = "SSL_CERT_PATH", value_hint = ValueHint::FilePath)]
+    tls_cert: Option<PathBuf>,
+
+    /// Server private key (PEM)
+    #[arg(long, env = "SSL_KEY_PATH", value_hint = ValueHint::FilePath)]
+    tls_key: Option<PathBuf>,
+
+    /// CA (PEM) that client certificates must chain to; enables mutual TLS
+    #[arg(long, env = "SSL_CLIENT_CA_PATH", value_hint = ValueHint::FilePath)]
+    tls_client_ca: Option<PathBuf>,
+}
+
+impl ServerTlsArgs {
+    fn into_config(self) -> Result<Option<tls::ServerTls>> {
+        tls::ServerTls::from_paths(self.tls_cert, self.tls_key, self.tls_client_ca)
+    }
+}
+
+#[derive(Args)]
+struct ClientTlsArgs {
+    /// Extra CA (PEM) to trust when connecting over https://
+    #[arg(long, value_hint = ValueHint::FilePath)]
+    tls_ca: Option<PathBuf>,
+
+    /// Client certificate (PEM) for servers that require mutual TLS
+    #[arg(long, value_hint = ValueHint::FilePath)]
+    tls_cert: Option<PathBuf>,
+
+    /// Client private key (PEM)
+    #[arg(long, value_hint = ValueHint::FilePath)]
+    tls_key: Option<PathBuf>,
+}
+
+impl ClientTlsArgs {
+    fn into_config(self, address: &str) -> Result<Option<tonic::transport::ClientTlsConfig>> {
+        if !address.starts_with("https://") {
+            return Ok(None);
+        }
+        tls::client_tls_config(self.tls_ca.as_deref(), self.tls_cert.as_deref(), self.tls_key.as_deref()).map(Some)
+    }
+}
+
+#[derive(Args)]
+struct SubscriptionArgs {
+    /// YAML or JSON file describing the SubscribeRequest filters
+    #[arg(long, env = "YELLOWSTONE_SUBSCRIPTION", value_hint = ValueHint::FilePath, help_heading = "Subscription Options")]
+    subscription: Option<PathBuf>,
+
+    /// Commitment to stream at, overriding the file
+    #[arg(long, value_enum, help_heading = "Subscription Options")]
+    commitment: Option<yellowstone_subscription::SubscriptionCommitment>,
+
+    /// Transactions mentioning any of these accounts
+    #[arg(long, value_delimiter = ',', help_heading = "Subscription Options")]
+    account_include: Vec<String>,
+
+    /// Transactions mentioning none of these accounts
+    #[arg(long, value_delimiter = ',', help_heading = "Subscription Options")]
+    account_exclude: Vec<String>,
+
+    /// Transactions mentioning all of these accounts
+    #[arg(long, value_delimiter = ',', help_heading = "Subscription Options")]
+    account_required: Vec<String>,
+
+    /// Include vote transactions
+    #[arg(long, help_heading = "Subscription Options")]
+    include_votes: bool,
+
+    /// Include failed transactions
+    #[arg(long, help_heading = "Subscription Options")]
+    include_failed: bool,
+
+    /// Account updates for these pubkeys
+    #[arg(long, value_delimiter = ',', help_heading = "Subscription Options")]
+    accounts: Vec<String>,
+
+    /// Account updates for accounts owned by these programs
+    #[arg(long, value_delimiter = ',', help_heading = "Subscription Options")]
+    owner: Vec<String>,
+
+    /// Only accounts whose data matches OFFSET:BASE58
+    #[arg(long, value_name = "OFFSET:BASE58", help_heading = "Subscription Options")]
+    memcmp: Vec<String>,
+
+    /// Only accounts with exactly this many bytes of data
+    #[arg(long, help_heading = "Subscription Options")]
+    datasize: Option<u64>,
+
+    /// Stream slot status updates
+    #[arg(long, help_heading = "Subscription Options")]
+    slots: bool,
+
+    /// Stream full blocks
+    #[arg(long, help_heading = "Subscription Options")]
+    blocks: bool,
+
+    /// Stream block metadata
+    #[arg(long, help_heading = "Subscription Options")]
+    blocks_meta: bool,
+
+    /// Stream entries
+    #[arg(long, help_heading = "Subscription Options")]
+    entries: bool,
+}
+
+impl SubscriptionArgs {
+    /// The subscription file, if any, plus a `cli` filter per stream the flags ask for
+    fn into_config(self) -> Result<yellowstone_subscription::SubscriptionConfig> {
+        use yellowstone_subscription::*;
+
+        let mut config = match &self.subscription {
+            Some(path) => SubscriptionConfig::load(path)
+                .map_err(|e| anyhow::anyhow!("Failed to load subscription {}: {}", path.display(), e))?,
+            None => SubscriptionConfig::default(),
+        };
+        let name = CLI_FILTER_NAME.to_string();
+
+        if self.commitment.is_some() {
+            config.commitment = self.commitment;
+        }
+
+        let transaction_flags = !self.account_include.is_empty() || !self.account_exclude.is_empty() || !self.account_required.is_empty();
+        if transaction_flags || self.include_votes || self.include_failed {
+            config.transactions.insert(name.clone(), TransactionsFilter {
+                vote: Some(self.include_votes),
+                failed: Some(self.include_failed),
+                account_include: self.account_include,
+                account_exclude: self.account_exclude,
+                account_required: self.account_required,
+                ..Default::default()
+            });
+        }
+
+        if !self.accounts.is_empty() || !self.owner.is_empty() || !self.memcmp.is_empty() || self.datasize.is_some() {
+            config.accounts.insert(name.clone(), AccountsFilter {
+                account: self.accounts,
+                owner: self.owner,
+                memcmp: self.memcmp.iter().map(|value| MemcmpFilter::parse(value)).collect::<Result<_>>()?,
+                datasize: self.datasize,
+                ..Default::default()
+            });
+        }
+
+        if self.slots {
+            config.slots.insert(name.clone(), SlotsFilter::default());
+        }
+        if self.blocks {
+            config.blocks.insert(name.clone(), BlocksFilter::default());
+        }
+        if self.blocks_meta {
+            config.blocks_meta.insert(name.clone(), EmptyFilter {});
+        }
+        if self.entries {
+            config.entries.insert(name, EmptyFilter {});
+        }
+
+        Ok(config)
+    }
+}
+
 #[derive(Subcommand)]
 enum CacheAction {
     ///  Start cache system
@@ -834,6 +1048,9 @@ enum ApiAction {
         /// Enable caching
         #[arg(long, default_value = "true")]
         cache: bool,
+
+        #[command(flatten)]
+        tls: ServerTlsArgs,
     },
 
     ///  Show API status
@@ -883,12 +1100,51 @@ enum DatabaseAction {
         slot: u64,
     },
 
+    ///  List indexed instructions by program, instruction type, account and slot range
+    ListInstructions {
+        /// Program id
+        #[arg(short, long)]
+        program: Option<String>,
+        /// Parsed instruction type, e.g. transfer or transferChecked
+        #[arg(short = 't', long = "type")]
+        instruction_type: Option<String>,
+        /// Only instructions referencing this account
+        #[arg(short, long)]
+        account: Option<String>,
+        /// First slot of the range
+        #[arg(long)]
+        from_slot: Option<u64>,
+        /// Last slot of the range
+        #[arg(long)]
+        to_slot: Option<u64>,
+        /// Limit number of results
+        #[arg(short, long, default_value = "50")]
+        limit: u64,
+    },
+
     ///  Get slot leader
     GetLeader {
         /// Slot number
         slot: u64,
     },
 
+    ///  Show per-validator skip rates recorded by the slot tracker
+    SkipRates {
+        /// Number of most recent slots to include
+        #[arg(short, long, default_value = "10000")]
+        window: u64,
+        /// Limit number of results
+        #[arg(short, long, default_value = "20")]
+        limit: u64,
+    },
+
+    ///  Show processed → confirmed → finalized latency percentiles recorded by the slot tracker
+    SlotTimings {
+        /// Number of most recent slots to include
+        #[arg(short, long, default_value = "10000")]
+        window: u64,
+    },
+
     ///  Get slot info
     GetSlot {
         /// Slot number
@@ -913,6 +1169,25 @@ enum DatabaseAction {
         count: u64,
     },
 
+    ///  Backfill a historical slot range from Solana RPC, resuming from the last checkpoint
+    Backfill {
+        /// First slot of the range
+        #[arg(long)]
+        from_slot: u64,
+        /// Last slot of the range
+        #[arg(long)]
+        to_slot: u64,
+        /// Concurrent block fetches
+        #[arg(short, long, default_value = "8")]
+        workers: usize,
+        /// RPC requests per second across all workers
+        #[arg(short, long, default_value = "20")]
+        rate_limit: u32,
+        /// Also store each block's transactions
+        #[arg(short, long)]
+        transactions: bool,
+    },
+
     ///  Fetch and store slot leaders from Solana RPC
     FetchSlotLeaders {
         /// Starting slot number
@@ -965,6 +1240,14 @@ async fn main() -> Result<()> {
 
     let config = config::Config::from_env()?;
 
+    let mut decoders = program_decoders::DecoderRegistry::with_builtin();
+    for path in &cli.idl {
+        let loaded = decoders.load_anchor_idls(path)
+            .map_err(|e| anyhow::anyhow!("Failed to load Anchor IDL {}: {}", path.display(), e))?;
+        logger.info(&format!("{} Loaded {} Anchor IDL(s) from {}", icons::INFO, loaded, path.display()), "main");
+    }
+    program_decoders::install(decoders);
+
     let solana_url = config.solana_rpc_url.clone();
     animations::CliAnimations::show_connection_animation(&solana_url);
 
@@ -993,9 +1276,9 @@ async fn main() -> Result<()> {
 
         Commands::Track { target } => {
             match target {
-                TrackTarget::Slots { leaders, finalized_only, interval: update_interval, transactions, save } => {
+                TrackTarget::Slots { leaders, finalized_only, interval: update_interval, source, transactions, save } => {
                     logger.info(&format!("{} Starting real-time Solana slot tracking...", icons::TRACKING), "main");
-                    slot_tracker::start_tracking(client, leaders, finalized_only, update_interval).await?;
+                    slot_tracker::start_tracking(client, &config, leaders, finalized_only, update_interval, source, save, transactions).await?;
                 }
                 TrackTarget::Validators { identity, voting, stake } => {
                     logger.info(&format!("{} Starting validator performance tracking...", icons::TRACKING), "main");
@@ -1071,7 +1354,7 @@ async fn main() -> Result<()> {
 
         Commands::GrpcServe { bind } => {
             logger.info(&format!("{} Starting gRPC server on {}:{}", icons::SERVER, bind, cli.port), "main");
-            grpc_server::start_server(bind, cli.port, client).await?;
+            grpc_server::start_server(bind, cli.port, client, &config).await?;
         }
 
         Commands::BlockchainInfo { blockchain } => {
@@ -1103,17 +1386,23 @@ async fn main() -> Result<()> {
 
         Commands::GrpcServer { action } => {
             match action {
-                GrpcAction::Start { bind, port, enable_solana, enable_flow } => {
+                GrpcAction::Start { bind, port, enable_solana, enable_flow, tls } => {
                     info!("{} {}:{}", "📡 Starting gRPC server on".bright_cyan(), bind.yellow(), port.to_string().yellow());
-                    grpc_server::start_enhanced_server(bind, port, client, &config, enable_solana, enable_flow).await?;
+                    let tls = tls.into_config()?;
+                    grpc_server::start_enhanced_server(bind, port, client, &config, enable_solana, enable_flow, tls).await?;
                 }
-                GrpcAction::Status => {
-                    info!("{}", "📊 Checking gRPC server status...".bright_cyan());
-                    grpc_server::show_status().await?;
+                GrpcAction::Status { address, tls } => {
+                    info!("{} {}", "📊 Checking gRPC server status at".bright_cyan(), address.yellow());
+                    let tls = tls.into_config(&address)?;
+                    grpc_server::show_status(&address, &config, tls).await?;
                 }
-                GrpcAction::Test { address } => {
+                GrpcAction::Test { address, tls } => {
                     info!("{} {}", "🔧 Testing gRPC server at".bright_cyan(), address.yellow());
-                    grpc_server::test_grpc_client(&address).await?;
+                    let tls = tls.into_config(&address)?;
+                    grpc_server::test_grpc_client(&address, &config, tls).await?;
+                }
+                GrpcAction::GenerateCerts { out_dir, hosts } => {
+                    tls::generate_self_signed(&out_dir, hosts)?;
                 }
             }
         }
@@ -1176,7 +1465,7 @@ async fn main() -> Result<()> {
             }
         }
 
-        Commands::Yellowstone { endpoint, auth_token, add_account, remove_account, list_accounts } => {
+        Commands::Yellowstone { endpoint, auth_token, add_account, remove_account, list_accounts, subscription } => {
             use crate::yellowstone_monitor::start_yellowstone_monitoring;
 
             if list_accounts {
@@ -1196,8 +1485,17 @@ async fn main() -> Result<()> {
                 // In a full implementation, you'd remove this from a config file
             }
 
+            let subscription = subscription.into_config()?;
+
             println!("{} {}", "🚀 Starting Yellowstone gRPC monitoring for endpoint:".bright_yellow(), endpoint.bright_cyan());
-            start_yellowstone_monitoring(endpoint, auth_token, logger).await?;
+            let database = match database::Database::new(&config.database_config).await {
+                Ok(database) => Some(database),
+                Err(e) => {
+                    logger.warn(&format!("Database unavailable, token trades will not be persisted: {}", e), "main");
+                    None
+                }
+            };
+            start_yellowstone_monitoring(endpoint, auth_token, logger, database, subscription).await?;
         }
 
         Commands::Metrics { action } => {
@@ -1219,8 +1517,9 @@ async fn main() -> Result<()> {
 
         Commands::RestApi { action } => {
             match action {
-                ApiAction::Start { port, rate_limit, max_rps, cache } => {
-                    api::start_high_performance_api(&port, &rate_limit, &max_rps, &cache, &config, client).await?;
+                ApiAction::Start { port, rate_limit, max_rps, cache, tls } => {
+                    let tls = tls.into_config()?;
+                    api::start_high_performance_api(&port, &rate_limit, &max_rps, &cache, &config, client, tls).await?;
                 }
                 ApiAction::Status => {
                     api::show_api_status().await?;
@@ -1283,6 +1582,33 @@ async fn main() -> Result<()> {
                         );
                     }
                 }
+                DatabaseAction::ListInstructions { program, instruction_type, account, from_slot, to_slot, limit } => {
+                    info!("{}", "📋 Fetching indexed instructions...".bright_cyan());
+                    let instructions = db.get_instructions(&database::InstructionQuery {
+                        program_id: program,
+                        parsed_type: instruction_type,
+                        account,
+                        from_slot,
+                        to_slot,
+                        limit,
+                    }).await?;
+                    for instruction in instructions {
+                        let position = match instruction.inner_index {
+                            Some(inner_index) => format!("#{}.{}", instruction.instruction_index, inner_index),
+                            None => format!("#{}", instruction.instruction_index),
+                        };
+                        println!("🧩 Slot {} {} {}: {} {}",
+                            instruction.slot.to_string().bright_yellow(),
+                            instruction.signature.bright_blue(),
+                            position.bright_white(),
+                            instruction.program_id.bright_green(),
+                            instruction.parsed_type.as_deref().unwrap_or("unparsed").bright_cyan()
+                        );
+                        if !instruction.accounts.is_empty() {
+                            println!("   Accounts: {}", instruction.accounts.join(", ").bright_white());
+                        }
+                    }
+                }
                 DatabaseAction::GetLeader { slot } => {
                     info!("{} {}", "👑 Fetching leader for slot:".bright_cyan(), slot.to_string().yellow());
                     if let Some(leader) = db.get_slot_leader(slot).await? {
@@ -1297,6 +1623,37 @@ async fn main() -> Result<()> {
                         println!("{}", "❌ No leader found for this slot".bright_red());
                     }
                 }
+                DatabaseAction::SkipRates { window, limit } => {
+                    info!("{} {}", "📉 Fetching validator skip rates over slots:".bright_cyan(), window.to_string().yellow());
+                    let rates = db.get_validator_skip_rates(window, limit).await?;
+                    if rates.is_empty() {
+                        println!("{}", "❌ No slot production recorded yet, run 'track slots --leaders'".bright_red());
+                    }
+                    for rate in rates {
+                        println!("👑 {}: {}/{} skipped ({:.1}%)",
+                            rate.leader_pubkey.bright_green(),
+                            rate.skipped_slots.to_string().bright_red(),
+                            rate.assigned_slots.to_string().bright_white(),
+                            rate.skip_rate() * 100.0
+                        );
+                    }
+                }
+                DatabaseAction::SlotTimings { window } => {
+                    info!("{} {}", "⏱️ Fetching commitment latencies over slots:".bright_cyan(), window.to_string().yellow());
+                    let (confirmation, finalization) = db.get_commitment_latencies(window).await?;
+                    if confirmation.samples == 0 && finalization.samples == 0 {
+                        println!("{}", "❌ No slot timings recorded yet, run 'track slots'".bright_red());
+                    }
+                    for (label, latency) in [("Confirmation", confirmation), ("Finalization", finalization)] {
+                        println!("⏱️ {}: p50 {}ms | p95 {}ms | p99 {}ms ({} slots)",
+                            label.bright_green(),
+                            latency.p50.to_string().bright_white(),
+                            latency.p95.to_string().bright_yellow(),
+                            latency.p99.to_string().bright_red(),
+                            latency.samples
+                        );
+                    }
+                }
                 DatabaseAction::GetSlot { slot } => {
                     info!("{} {}", "🔍 Fetching slot:".bright_cyan(), slot.to_string().yellow());
                     if let Some(slot_data) = db.get_slot(slot).await? {
@@ -1320,6 +1677,32 @@ async fn main() -> Result<()> {
                         println!("   Status: {}", tx.status.bright_green());
                         println!("   Programs: {}", tx.program_ids.join(", ").bright_white());
                         println!("   Timestamp: {}", tx.timestamp.format("%Y-%m-%d %H:%M:%S UTC").to_string().bright_cyan());
+                        if let Some(signer) = &tx.signer {
+                            println!("   Signer: {}", signer.bright_white());
+                        }
+                        if let Some(compute_units) = tx.compute_units {
+                            println!("   Compute Units: {}", compute_units.to_string().bright_yellow());
+                        }
+                        if let Some(priority_fee) = tx.priority_fee {
+                            println!("   Priority Fee: {} lamports", priority_fee.to_string().bright_yellow());
+                        }
+                        if let Some(error) = &tx.error {
+                            println!("   Error: {}", error.bright_red());
+                        }
+                        for change in db.get_balance_changes(&signature).await? {
+                            println!("   SOL {}: {:+.9}", change.address.bright_white(), change.delta() as f64 / 1_000_000_000.0);
+                        }
+                        for change in db.get_token_balance_changes(&signature).await? {
+                            println!("   Token {} ({}): {:+}",
+                                change.owner.as_deref().unwrap_or(&change.address).bright_white(),
+                                change.mint.bright_cyan(),
+                                change.ui_delta()
+                            );
+                        }
+                        let logs = db.get_transaction_logs(&signature).await?;
+                        if !logs.is_empty() {
+                            println!("   Logs: {} lines", logs.len().to_string().bright_white());
+                        }
                     } else {
                         println!("{}", "❌ Transaction not found".bright_red());
                     }
@@ -1337,6 +1720,26 @@ async fn main() -> Result<()> {
                     let slots = db.fetch_and_store_recent_slots(&rpc_client, count).await?;
                     println!("{} {} {}", "✅ Successfully fetched and stored".bright_green(), slots.len().to_string().bright_yellow(), "slots with their transactions".bright_green());
                 }
+                DatabaseAction::Backfill { from_slot, to_slot, workers, rate_limit, transactions } => {
+                    info!("{} {} {} {}", "🌐 Backfilling slots".bright_cyan(), from_slot.to_string().bright_yellow(), "to".bright_cyan(), to_slot.to_string().bright_yellow());
+                    let summary = backfill::run_backfill(&db, &config.solana_rpc_url, backfill::BackfillOptions {
+                        from_slot,
+                        to_slot,
+                        workers,
+                        rate_limit,
+                        transactions,
+                    }).await?;
+                    println!("{} {} {} {} {} {} {} {}",
+                        "✅ Backfill stored".bright_green(),
+                        summary.stored.to_string().bright_yellow(),
+                        "blocks, skipped".bright_green(),
+                        (summary.already_indexed + summary.skipped).to_string().bright_yellow(),
+                        "indexed or empty slots,".bright_green(),
+                        summary.failed.to_string().bright_red(),
+                        "failed".bright_green(),
+                        if summary.failed > 0 { "(rerun to retry)" } else { "" }
+                    );
+                }
                 DatabaseAction::FetchSlotLeaders { slot, count } => {
                     let rpc_client = RpcClient::new(config.solana_rpc_url.clone());
                     let start_slot = if let Some(s) = slot {
@@ -0,0 +1,824 @@
+use anyhow::Result;
+use serde_json::{json, Map, Value};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use crate::activity_classifier::{STAKE_PROGRAM_ID, SYSTEM_PROGRAM_ID};
+use crate::pump_fun::PumpFunDecoder;
+use crate::transaction_decoder::COMPUTE_BUDGET_PROGRAM_ID;
+use crate::wallet_tracker::{TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID};
+
+pub const ASSOCIATED_TOKEN_PROGRAM_ID: &str = "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL";
+pub const VOTE_PROGRAM_ID: &str = "Vote111111111111111111111111111111111111111";
+pub const MEMO_PROGRAM_ID: &str = "MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr";
+pub const MEMO_V1_PROGRAM_ID: &str = "Memo1UhkJRfHyvLMcVucJwxXeuD728EqVDDwQDxFMNo";
+
+/// Instructions SPL Token shares with Token-2022; the rest are extensions
+const SPL_TOKEN_INSTRUCTION_COUNT: usize = 25;
+
+static REGISTRY: OnceLock<DecoderRegistry> = OnceLock::new();
+
+/// An instruction a decoder recognized
+#[derive(Debug, Clone)]
+pub struct NamedInstruction {
+    pub program: String,
+    pub name: String,
+    /// Named accounts followed by the decoded arguments
+    pub info: Map<String, Value>,
+}
+
+impl NamedInstruction {
+    /// Shaped like the RPC's `jsonParsed` output, so raw and parsed
+    /// instructions read the same downstream
+    pub fn parsed(&self) -> Value {
+        json!({ "type": self.name, "info": self.info })
+    }
+}
+
+/// Turns one program's raw instruction data into a named instruction
+pub trait ProgramDecoder: Send + Sync {
+    fn program_id(&self) -> &str;
+    fn program_name(&self) -> &str;
+    /// `None` when the data isn't an instruction this decoder knows
+    fn decode(&self, data: &[u8], accounts: &[String]) -> Option<NamedInstruction>;
+}
+
+/// Decoders keyed by program id
+pub struct DecoderRegistry {
+    decoders: HashMap<String, Box<dyn ProgramDecoder>>,
+}
+
+impl DecoderRegistry {
+    pub fn new() -> Self {
+        Self { decoders: HashMap::new() }
+    }
+
+    /// System, SPL Token, Token-2022, Associated Token Account, Stake, Vote,
+    /// Compute Budget, Memo and pump.fun
+    pub fn with_builtin() -> Self {
+        let mut registry = Self::new();
+        registry.register(Box::new(TableDecoder::new(SYSTEM_PROGRAM_ID, "system", TagWidth::U32, SYSTEM_INSTRUCTIONS)));
+        registry.register(Box::new(TableDecoder::new(TOKEN_PROGRAM_ID, "spl-token", TagWidth::U8, &TOKEN_INSTRUCTIONS[..SPL_TOKEN_INSTRUCTION_COUNT])));
+        registry.register(Box::new(TableDecoder::new(TOKEN_2022_PROGRAM_ID, "spl-token-2022", TagWidth::U8, TOKEN_INSTRUCTIONS)));
+        registry.register(Box::new(AssociatedTokenDecoder));
+        registry.register(Box::new(TableDecoder::new(STAKE_PROGRAM_ID, "stake", TagWidth::U32, STAKE_INSTRUCTIONS)));
+        registry.register(Box::new(TableDecoder::new(VOTE_PROGRAM_ID, "vote", TagWidth::U32, VOTE_INSTRUCTIONS)));
+        registry.register(Box::new(TableDecoder::new(COMPUTE_BUDGET_PROGRAM_ID, "compute-budget", TagWidth::U8, COMPUTE_BUDGET_INSTRUCTIONS)));
+        registry.register(Box::new(MemoDecoder { program_id: MEMO_PROGRAM_ID }));
+        registry.register(Box::new(MemoDecoder { program_id: MEMO_V1_PROGRAM_ID }));
+        registry.register(Box::new(PumpFunDecoder));
+        registry
+    }
+
+    /// Add `decoder`, replacing any earlier one for the same program
+    pub fn register(&mut self, decoder: Box<dyn ProgramDecoder>) {
+        self.decoders.insert(decoder.program_id().to_string(), decoder);
+    }
+
+    /// Load an Anchor IDL file, or every `.json` IDL in a directory. Returns
+    /// the number of programs loaded.
+    pub fn load_anchor_idls(&mut self, path: &Path) -> Result<usize> {
+        if !path.is_dir() {
+            let idl: Value = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+            self.register(Box::new(AnchorDecoder::from_idl(&idl)?));
+            return Ok(1);
+        }
+
+        let mut loaded = 0;
+        for entry in std::fs::read_dir(path)? {
+            let file = entry?.path();
+            if file.extension().is_some_and(|extension| extension == "json") {
+                loaded += self.load_anchor_idls(&file)
+                    .map_err(|e| anyhow::anyhow!("{}: {}", file.display(), e))?;
+            }
+        }
+        Ok(loaded)
+    }
+
+    pub fn decode(&self, program_id: &str, data: &[u8], accounts: &[String]) -> Option<NamedInstruction> {
+        self.decoders.get(program_id)?.decode(data, accounts)
+    }
+}
+
+/// Use `registry` for all decoding from now on. Only the first call takes
+/// effect; returns whether this one did.
+pub fn install(registry: DecoderRegistry) -> bool {
+    REGISTRY.set(registry).is_ok()
+}
+
+/// The installed registry, or the built-in decoders when none was installed
+pub fn registry() -> &'static DecoderRegistry {
+    REGISTRY.get_or_init(DecoderRegistry::with_builtin)
+}
+
+/// Cursor over little-endian instruction data
+pub struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        if len > self.data.len() {
+            return None;
+        }
+        let (head, tail) = self.data.split_at(len);
+        self.data = tail;
+        Some(head)
+    }
+
+    pub fn array<const N: usize>(&mut self) -> Option<[u8; N]> {
+        self.take(N)?.try_into().ok()
+    }
+
+    pub fn u8(&mut self) -> Option<u8> {
+        self.array::<1>().map(|bytes| bytes[0])
+    }
+
+    pub fn u16(&mut self) -> Option<u16> {
+        self.array().map(u16::from_le_bytes)
+    }
+
+    pub fn u32(&mut self) -> Option<u32> {
+        self.array().map(u32::from_le_bytes)
+    }
+
+    pub fn u64(&mut self) -> Option<u64> {
+        self.array().map(u64::from_le_bytes)
+    }
+
+    pub fn u128(&mut self) -> Option<u128> {
+        self.array().map(u128::from_le_bytes)
+    }
+
+    pub fn pubkey(&mut self) -> Option<String> {
+        self.take(32).map(|bytes| bs58::encode(bytes).into_string())
+    }
+
+    pub fn utf8(&mut self, len: usize) -> Option<String> {
+        String::from_utf8(self.take(len)?.to_vec()).ok()
+    }
+
+    /// Borsh string with a u32 length
+    pub fn string(&mut self) -> Option<String> {
+        let len = self.u32()? as usize;
+        self.utf8(len)
+    }
+}
+
+/// Argument encodings used by the native and SPL programs
+#[derive(Debug, Clone, Copy)]
+enum Field {
+    U8,
+    U32,
+    U64,
+    I64,
+    Pubkey,
+    /// `COption<Pubkey>` with a one-byte tag
+    OptionalPubkey,
+    /// Bincode string with a u64 length
+    Seed,
+    /// UTF-8 text filling the rest of the data
+    Text,
+}
+
+impl Field {
+    fn read(self, reader: &mut Reader) -> Option<Value> {
+        Some(match self {
+            Field::U8 => reader.u8()?.into(),
+            Field::U32 => reader.u32()?.into(),
+            Field::U64 => reader.u64()?.into(),
+            Field::I64 => (reader.u64()? as i64).into(),
+            Field::Pubkey => reader.pubkey()?.into(),
+            Field::OptionalPubkey => match reader.u8()? {
+                0 => Value::Null,
+                _ => reader.pubkey()?.into(),
+            },
+            Field::Seed => {
+                let len = reader.u64()? as usize;
+                reader.utf8(len)?.into()
+            }
+            Field::Text => reader.utf8(reader.remaining())?.into(),
+        })
+    }
+}
+
+/// One instruction of a table-driven program; the tag is its position
+struct Layout {
+    name: &'static str,
+    accounts: &'static [&'static str],
+    args: &'static [(&'static str, Field)],
+}
+
+const fn layout(name: &'static str, accounts: &'static [&'static str], args: &'static [(&'static str, Field)]) -> Layout {
+    Layout { name, accounts, args }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum TagWidth {
+    U8,
+    U32,
+}
+
+/// Decoder for programs that prefix arguments with an instruction index,
+/// which covers every native and SPL program here
+struct TableDecoder {
+    program_id: &'static str,
+    program: &'static str,
+    tag: TagWidth,
+    instructions: &'static [Layout],
+}
+
+impl TableDecoder {
+    fn new(program_id: &'static str, program: &'static str, tag: TagWidth, instructions: &'static [Layout]) -> Self {
+        Self { program_id, program, tag, instructions }
+    }
+}
+
+impl ProgramDecoder for TableDecoder {
+    fn program_id(&self) -> &str {
+        self.program_id
+    }
+
+    fn program_name(&self) -> &str {
+        self.program
+    }
+
+    fn decode(&self, data: &[u8], accounts: &[String]) -> Option<NamedInstruction> {
+        let mut reader = Reader::new(data);
+        let tag = match self.tag {
+            TagWidth::U8 => reader.u8()? as usize,
+            TagWidth::U32 => reader.u32()? as usize,
+        };
+        let layout = self.instructions.get(tag)?;
+
+        let mut info = named_accounts(layout.accounts.iter().copied(), accounts);
+        for (name, field) in layout.args {
+            // Keep what was read when the data is shorter than the layout
+            let Some(value) = field.read(&mut reader) else { break };
+            info.insert(name.to_string(), value);
+        }
+
+        Some(NamedInstruction { program: self.program.to_string(), name: layout.name.to_string(), info })
+    }
+}
+
+fn named_accounts<'a>(names: impl Iterator<Item = &'a str>, accounts: &[String]) -> Map<String, Value> {
+    names.zip(accounts)
+        .map(|(name, account)| (name.to_string(), Value::String(account.clone())))
+        .collect()
+}
+
+const SYSTEM_INSTRUCTIONS: &[Layout] = &[
+    layout("createAccount", &["source", "newAccount"], &[("lamports", Field::U64), ("space", Field::U64), ("owner", Field::Pubkey)]),
+    layout("assign", &["account"], &[("owner", Field::Pubkey)]),
+    layout("transfer", &["source", "destination"], &[("lamports", Field::U64)]),
+    layout("createAccountWithSeed", &["source", "newAccount", "base"], &[("base", Field::Pubkey), ("seed", Field::Seed), ("lamports", Field::U64), ("space", Field::U64), ("owner", Field::Pubkey)]),
+    layout("advanceNonce", &["nonceAccount", "recentBlockhashesSysvar", "nonceAuthority"], &[]),
+    layout("withdrawFromNonce", &["nonceAccount", "destination", "recentBlockhashesSysvar", "rentSysvar", "nonceAuthority"], &[("lamports", Field::U64)]),
+    layout("initializeNonce", &["nonceAccount", "recentBlockhashesSysvar", "rentSysvar"], &[("nonceAuthority", Field::Pubkey)]),
+    layout("authorizeNonce", &["nonceAccount", "nonceAuthority"], &[("newAuthorized", Field::Pubkey)]),
+    layout("allocate", &["account"], &[("space", Field::U64)]),
+    layout("allocateWithSeed", &["account", "base"], &[("base", Field::Pubkey), ("seed", Field::Seed), ("space", Field::U64), ("owner", Field::Pubkey)]),
+    layout("assignWithSeed", &["account", "base"], &[("base", Field::Pubkey), ("seed", Field::Seed), ("owner", Field::Pubkey)]),
+    layout("transferWithSeed", &["source", "sourceBase", "destination"], &[("lamports", Field::U64), ("sourceSeed", Field::Seed), ("sourceOwner", Field::Pubkey)]),
+    layout("upgradeNonce", &["nonceAccount"], &[]),
+];
+
+/// Token-2022 instructions; the first `SPL_TOKEN_INSTRUCTION_COUNT` are SPL Token's
+const TOKEN_INSTRUCTIONS: &[Layout] = &[
+    layout("initializeMint", &["mint", "rentSysvar"], &[("decimals", Field::U8), ("mintAuthority", Field::Pubkey), ("freezeAuthority", Field::OptionalPubkey)]),
+    layout("initializeAccount", &["account", "mint", "owner", "rentSysvar"], &[]),
+    layout("initializeMultisig", &["multisig", "rentSysvar"], &[("m", Field::U8)]),
+    layout("transfer", &["source", "destination", "authority"], &[("amount", Field::U64)]),
+    layout("approve", &["source", "delegate", "owner"], &[("amount", Field::U64)]),
+    layout("revoke", &["source", "owner"], &[]),
+    layout("setAuthority", &["account", "authority"], &[("authorityType", Field::U8), ("newAuthority", Field::OptionalPubkey)]),
+    layout("mintTo", &["mint", "account", "mintAuthority"], &[("amount", Field::U64)]),
+    layout("burn", &["account", "mint", "authority"], &[("amount", Field::U64)]),
+    layout("closeAccount", &["account", "destination", "owner"], &[]),
+    layout("freezeAccount", &["account", "mint", "freezeAuthority"], &[]),
+    layout("thawAccount", &["account", "mint", "freezeAuthority"], &[]),
+    layout("transferChecked", &["source", "mint", "destination", "authority"], &[("amount", Field::U64), ("decimals", Field::U8)]),
+    layout("approveChecked", &["source", "mint", "delegate", "owner"], &[("amount", Field::U64), ("decimals", Field::U8)]),
+    layout("mintToChecked", &["mint", "account", "mintAuthority"], &[("amount", Field::U64), ("decimals", Field::U8)]),
+    layout("burnChecked", &["account", "mint", "authority"], &[("amount", Field::U64), ("decimals", Field::U8)]),
+    layout("initializeAccount2", &["account", "mint", "rentSysvar"], &[("owner", Field::Pubkey)]),
+    layout("syncNative", &["account"], &[]),
+    layout("initializeAccount3", &["account", "mint"], &[("owner", Field::Pubkey)]),
+    layout("initializeMultisig2", &["multisig"], &[("m", Field::U8)]),
+    layout("initializeMint2", &["mint"], &[("decimals", Field::U8), ("mintAuthority", Field::Pubkey), ("freezeAuthority", Field::OptionalPubkey)]),
+    layout("getAccountDataSize", &["mint"], &[]),
+    layout("initializeImmutableOwner", &["account"], &[]),
+    layout("amountToUiAmount", &["mint"], &[("amount", Field::U64)]),
+    layout("uiAmountToAmount", &["mint"], &[("uiAmount", Field::Text)]),
+    // Token-2022 extensions, named but with their sub-instructions left undecoded
+    layout("initializeMintCloseAuthority", &["mint"], &[("closeAuthority", Field::OptionalPubkey)]),
+    layout("transferFeeExtension", &[], &[]),
+    layout("confidentialTransferExtension", &[], &[]),
+    layout("defaultAccountStateExtension", &[], &[]),
+    layout("reallocate", &["account", "payer", "systemProgram", "owner"], &[]),
+    layout("memoTransferExtension", &[], &[]),
+    layout("createNativeMint", &["payer", "nativeMint", "systemProgram"], &[]),
+    layout("initializeNonTransferableMint", &["mint"], &[]),
+    layout("interestBearingMintExtension", &[], &[]),
+    layout("cpiGuardExtension", &[], &[]),
+    layout("initializePermanentDelegate", &["mint"], &[("delegate", Field::Pubkey)]),
+    layout("transferHookExtension", &[], &[]),
+    layout("confidentialTransferFeeExtension", &[], &[]),
+    layout("withdrawExcessLamports", &["source", "destination", "authority"], &[]),
+    layout("metadataPointerExtension", &[], &[]),
+    layout("groupPointerExtension", &[], &[]),
+    layout("groupMemberPointerExtension", &[], &[]),
+];
+
+const STAKE_INSTRUCTIONS: &[Layout] = &[
+    layout("initialize", &["stakeAccount", "rentSysvar"], &[("staker", Field::Pubkey), ("withdrawer", Field::Pubkey), ("unixTimestamp", Field::I64), ("epoch", Field::U64), ("custodian", Field::Pubkey)]),
+    layout("authorize", &["stakeAccount", "clockSysvar", "authority"], &[("newAuthority", Field::Pubkey), ("authorityType", Field::U32)]),
+    layout("delegate", &["stakeAccount", "voteAccount", "clockSysvar", "stakeHistorySysvar", "stakeConfigAccount", "stakeAuthority"], &[]),
+    layout("split", &["stakeAccount", "newSplitAccount", "stakeAuthority"], &[("lamports", Field::U64)]),
+    layout("withdraw", &["stakeAccount", "destination", "clockSysvar", "stakeHistorySysvar", "withdrawAuthority"], &[("lamports", Field::U64)]),
+    layout("deactivate", &["stakeAccount", "clockSysvar", "stakeAuthority"], &[]),
+    layout("setLockup", &["stakeAccount", "custodian"], &[]),
+    layout("merge", &["destination", "source", "clockSysvar", "stakeHistorySysvar", "stakeAuthority"], &[]),
+    layout("authorizeWithSeed", &["stakeAccount", "authorityBase", "clockSysvar"], &[("newAuthorized", Field::Pubkey), ("authorityType", Field::U32)]),
+    layout("initializeChecked", &["stakeAccount", "rentSysvar", "staker", "withdrawer"], &[]),
+    layout("authorizeChecked", &["stakeAccount", "clockSysvar", "authority", "newAuthority"], &[("authorityType", Field::U32)]),
+    layout("authorizeCheckedWithSeed", &["stakeAccount", "authorityBase", "clockSysvar", "newAuthority"], &[("authorityType", Field::U32)]),
+    layout("setLockupChecked", &["stakeAccount", "custodian"], &[]),
+    layout("getMinimumDelegation", &[], &[]),
+    layout("deactivateDelinquent", &["stakeAccount", "voteAccount", "referenceVoteAccount"], &[]),
+    layout("redelegate", &["stakeAccount", "newStakeAccount", "voteAccount", "stakeConfigAccount", "stakeAuthority"], &[]),
+    layout("moveStake", &["source", "destination", "stakeAuthority"], &[("lamports", Field::U64)]),
+    layout("moveLamports", &["source", "destination", "stakeAuthority"], &[("lamports", Field::U64)]),
+];
+
+const VOTE_INSTRUCTIONS: &[Layout] = &[
+    layout("initialize", &["voteAccount", "rentSysvar", "clockSysvar", "node"], &[("node", Field::Pubkey), ("authorizedVoter", Field::Pubkey), ("authorizedWithdrawer", Field::Pubkey), ("commission", Field::U8)]),
+    layout("authorize", &["voteAccount", "clockSysvar", "authority"], &[("newAuthority", Field::Pubkey), ("authorityType", Field::U32)]),
+    layout("vote", &["voteAccount", "slotHashesSysvar", "clockSysvar", "voteAuthority"], &[]),
+    layout("withdraw", &["voteAccount", "destination", "withdrawAuthority"], &[("lamports", Field::U64)]),
+    layout("updateValidatorIdentity", &["voteAccount", "newValidatorIdentity", "withdrawAuthority"], &[]),
+    layout("updateCommission", &["voteAccount", "withdrawAuthority"], &[("commission", Field::U8)]),
+    layout("voteSwitch", &["voteAccount", "slotHashesSysvar", "clockSysvar", "voteAuthority"], &[]),
+    layout("authorizeChecked", &["voteAccount", "clockSysvar", "authority", "newAuthority"], &[("authorityType", Field::U32)]),
+    layout("updateVoteState", &["voteAccount", "voteAuthority"], &[]),
+    layout("updateVoteStateSwitch", &["voteAccount", "voteAuthority"], &[]),
+    layout("authorizeWithSeed", &["voteAccount", "clockSysvar", "authorityBase"], &[]),
+    layout("authorizeCheckedWithSeed", &["voteAccount", "clockSysvar", "authorityBase", "newAuthority"], &[]),
+    layout("compactUpdateVoteState", &["voteAccount", "voteAuthority"], &[]),
+    layout("compactUpdateVoteStateSwitch", &["voteAccount", "voteAuthority"], &[]),
+    layout("towerSync", &["voteAccount", "voteAuthority"], &[]),
+    layout("towerSyncSwitch", &["voteAccount", "voteAuthority"], &[]),
+];
+
+const COMPUTE_BUDGET_INSTRUCTIONS: &[Layout] = &[
+    layout("requestUnits", &[], &[("units", Field::U32), ("additionalFee", Field::U32)]),
+    layout("requestHeapFrame", &[], &[("bytes", Field::U32)]),
+    layout("setComputeUnitLimit", &[], &[("units", Field::U32)]),
+    layout("setComputeUnitPrice", &[], &[("microLamports", Field::U64)]),
+    layout("setLoadedAccountsDataSizeLimit", &[], &[("bytes", Field::U32)]),
+];
+
+const ASSOCIATED_TOKEN_INSTRUCTIONS: &[Layout] = &[
+    layout("create", &["source", "account", "wallet", "mint", "systemProgram", "tokenProgram"], &[]),
+    layout("createIdempotent", &["source", "account", "wallet", "mint", "systemProgram", "tokenProgram"], &[]),
+    layout("recoverNested", &["nestedSource", "nestedMint", "destination", "nestedOwner", "ownerMint", "wallet", "tokenProgram"], &[]),
+];
+
+/// Associated Token Account program, where empty data also means `create`
+struct AssociatedTokenDecoder;
+
+impl ProgramDecoder for AssociatedTokenDecoder {
+    fn program_id(&self) -> &str {
+        ASSOCIATED_TOKEN_PROGRAM_ID
+    }
+
+    fn program_name(&self) -> &str {
+        "spl-associated-token-account"
+    }
+
+    fn decode(&self, data: &[u8], accounts: &[String]) -> Option<NamedInstruction> {
+        let layout = ASSOCIATED_TOKEN_INSTRUCTIONS.get(data.first().copied().unwrap_or(0) as usize)?;
+        Some(NamedInstruction {
+            program: self.program_name().to_string(),
+            name: layout.name.to_string(),
+            info: named_accounts(layout.accounts.iter().copied(), accounts),
+        })
+    }
+}
+
+/// SPL Memo, whose data is the memo text
+struct MemoDecoder {
+    program_id: &'static str,
+}
+
+impl ProgramDecoder for MemoDecoder {
+    fn program_id(&self) -> &str {
+        self.program_id
+    }
+
+    fn program_name(&self) -> &str {
+        "spl-memo"
+    }
+
+    fn decode(&self, data: &[u8], _accounts: &[String]) -> Option<NamedInstruction> {
+        let mut info = Map::new();
+        info.insert("memo".to_string(), String::from_utf8_lossy(data).into_owned().into());
+        Some(NamedInstruction { program: self.program_name().to_string(), name: "memo".to_string(), info })
+    }
+}
+
+struct AnchorInstruction {
+    name: String,
+    discriminator: [u8; 8],
+    accounts: Vec<String>,
+    /// Argument names and IDL types
+    args: Vec<(String, Value)>,
+}
+
+/// Decoder built from an Anchor IDL, in either the pre-0.30 format
+/// (`name`, `metadata.address`, derived discriminators) or the current one
+/// (`address`, `metadata.name`, explicit discriminators)
+pub struct AnchorDecoder {
+    program_id: String,
+    program: String,
+    instructions: Vec<AnchorInstruction>,
+    types: HashMap<String, Value>,
+}
+
+impl AnchorDecoder {
+    pub fn from_idl(idl: &Value) -> Result<Self> {
+        let program_id = idl.get("address")
+            .or_else(|| idl.pointer("/metadata/address"))
+            .and_then(Value::as_str)
+            .ok_or_else(|| anyhow::anyhow!("IDL has no program address"))?;
+        let program = idl.pointer("/metadata/name")
+            .or_else(|| idl.get("name"))
+            .and_then(Value::as_str)
+            .unwrap_or(program_id);
+
+        let mut instructions = Vec::new();
+        for instruction in idl.get("instructions").and_then(Value::as_array).into_iter().flatten() {
+            let name = instruction.get("name").and_then(Value::as_str)
+                .ok_or_else(|| anyhow::anyhow!("IDL instruction without a name"))?;
+
+            let discriminator = match instruction.get("discriminator").and_then(Value::as_array) {
+                Some(bytes) => {
+                    let bytes: Vec<u8> = bytes.iter().filter_map(Value::as_u64).map(|byte| byte as u8).collect();
+                    bytes.try_into().map_err(|_| anyhow::anyhow!("Instruction {} has a malformed discriminator", name))?
+                }
+                None => anchor_discriminator(name),
+            };
+
+            let mut accounts = Vec::new();
+            flatten_accounts(instruction.get("accounts"), &mut accounts);
+
+            let args = instruction.get("args").and_then(Value::as_array).into_iter().flatten()
+                .filter_map(|arg| Some((arg.get("name")?.as_str()?.to_string(), arg.get("type")?.clone())))
+                .collect();
+
+            instructions.push(AnchorInstruction { name: name.to_string(), discriminator, accounts, args });
+        }
+
+        let types = idl.get("types").and_then(Value::as_array).into_iter().flatten()
+            .filter_map(|definition| Some((definition.get("name")?.as_str()?.to_string(), definition.get("type")?.clone())))
+            .collect();
+
+        Ok(Self { program_id: program_id.to_string(), program: program.to_string(), instructions, types })
+    }
+
+    /// Borsh-decode one value of IDL type `ty`
+    fn decode_value(&self, ty: &Value, reader: &mut Reader) -> Option<Value> {
+        match ty {
+            Value::String(name) => Some(match name.as_str() {
+                "bool" => Value::Bool(reader.u8()? != 0),
+                "u8" => reader.u8()?.into(),
+                "i8" => (reader.u8()? as i8).into(),
+                "u16" => reader.u16()?.into(),
+                "i16" => (reader.u16()? as i16).into(),
+                "u32" => reader.u32()?.into(),
+                "i32" => (reader.u32()? as i32).into(),
+                "u64" => reader.u64()?.into(),
+                "i64" => (reader.u64()? as i64).into(),
+                // Too wide for JSON numbers
+                "u128" => reader.u128()?.to_string().into(),
+                "i128" => (reader.u128()? as i128).to_string().into(),
+                "f32" => f32::from_bits(reader.u32()?).into(),
+                "f64" => f64::from_bits(reader.u64()?).into(),
+                "string" => reader.string()?.into(),
+                "bytes" => {
+                    let len = reader.u32()? as usize;
+                    reader.take(len)?.to_vec().into()
+                }
+                "publicKey" | "pubkey" => reader.pubkey()?.into(),
+                _ => return None,
+            }),
+            Value::Object(compound) => {
+                if let Some(inner) = compound.get("vec") {
+                    let len = reader.u32()? as usize;
+                    // Every element takes at least a byte, so a longer length is garbage
+                    if len > reader.remaining() {
+                        return None;
+                    }
+                    (0..len).map(|_| self.decode_value(inner, reader)).collect::<Option<Vec<_>>>().map(Value::Array)
+                } else if let Some(inner) = compound.get("option") {
+                    match reader.u8()? {
+                        0 => Some(Value::Null),
+                        _ => self.decode_value(inner, reader),
+                    }
+                } else if let Some(inner) = compound.get("coption") {
+                    match reader.u32()? {
+                        0 => Some(Value::Null),
+                        _ => self.decode_value(inner, reader),
+                    }
+                } else if let Some([inner, len]) = compound.get("array").and_then(Value::as_array).map(Vec::as_slice) {
+                    (0..len.as_u64()?).map(|_| self.decode_value(inner, reader)).collect::<Option<Vec<_>>>().map(Value::Array)
+                } else if let Some(defined) = compound.get("defined") {
+                    let name = defined.as_str().or_else(|| defined.get("name").and_then(Value::as_str))?;
+                    self.decode_defined(name, reader)
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        }
+    }
+
+    fn decode_defined(&self, name: &str, reader: &mut Reader) -> Option<Value> {
+        let definition = self.types.get(name)?;
+        match definition.get("kind")?.as_str()? {
+            "struct" => self.decode_fields(definition.get("fields"), reader),
+            "enum" => {
+                let variant = definition.get("variants")?.as_array()?.get(reader.u8()? as usize)?;
+                let variant_name = variant.get("name")?.as_str()?;
+                match variant.get("fields") {
+                    Some(fields) => Some(json!({ variant_name: self.decode_fields(Some(fields), reader)? })),
+                    None => Some(variant_name.into()),
+                }
+            }
+            "type" | "alias" => self.decode_value(definition.get("alias").or_else(|| definition.get("value"))?, reader),
+            _ => None,
+        }
+    }
+
+    /// Named fields become an object, tuple fields an array
+    fn decode_fields(&self, fields: Option<&Value>, reader: &mut Reader) -> Option<Value> {
+        let Some(fields) = fields.and_then(Value::as_array) else {
+            return Some(Value::Null);
+        };
+
+        if fields.iter().all(|field| field.get("name").is_some()) {
+            let mut object = Map::new();
+            for field in fields {
+                object.insert(field.get("name")?.as_str()?.to_string(), self.decode_value(field.get("type")?, reader)?);
+            }
+            Some(Value::Object(object))
+        } else {
+            fields.iter().map(|field| self.decode_value(field, reader)).collect::<Option<Vec<_>>>().map(Value::Array)
+        }
+    }
+}
+
+impl ProgramDecoder for AnchorDecoder {
+    fn program_id(&self) -> &str {
+        &self.program_id
+    }
+
+    fn program_name(&self) -> &str {
+        &self.program
+    }
+
+    fn decode(&self, data: &[u8], accounts: &[String]) -> Option<NamedInstruction> {
+        let instruction = self.instructions.iter().find(|instruction| data.starts_with(&instruction.discriminator))?;
+        let mut reader = Reader::new(&data[8..]);
+
+        let mut info = named_accounts(instruction.accounts.iter().map(String::as_str), accounts);
+        for (name, ty) in &instruction.args {
+            // Keep what was read when an argument doesn't decode
+            let Some(value) = self.decode_value(ty, &mut reader) else { break };
+            info.insert(name.clone(), value);
+        }
+
+        Some(NamedInstruction { program: self.program.clone(), name: instruction.name.clone(), info })
+    }
+}
+
+/// `sha256("global:<snake_case name>")[..8]`, as pre-0.30 Anchor derives it
+fn anchor_discriminator(name: &str) -> [u8; 8] {
+    let mut snake_case = String::with_capacity(name.len() + 4);
+    for (position, character) in name.chars().enumerate() {
+        if character.is_uppercase() {
+            if position > 0 {
+                snake_case.push('_');
+            }
+            snake_case.extend(character.to_lowercase());
+        } else {
+            snake_case.push(character);
+        }
+    }
+
+    let hash = Sha256::digest(format!("global:{}", snake_case).as_bytes());
+    let mut discriminator = [0u8; 8];
+    discriminator.copy_from_slice(&hash[..8]);
+    discriminator
+}
+
+/// Account names in order, with nested account groups expanded in place
+fn flatten_accounts(accounts: Option<&Value>, names: &mut Vec<String>) {
+    for account in accounts.and_then(Value::as_array).into_iter().flatten() {
+        if account.get("accounts").is_some() {
+            flatten_accounts(account.get("accounts"), names);
+        } else if let Some(name) = account.get("name").and_then(Value::as_str) {
+            names.push(name.to_string());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn accounts(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    fn pubkey(byte: u8) -> ([u8; 32], String) {
+        let bytes = [byte; 32];
+        (bytes, bs58::encode(bytes).into_string())
+    }
+
+    #[test]
+    fn system_transfer_names_accounts_and_lamports() {
+        let mut data = 2u32.to_le_bytes().to_vec();
+        data.extend_from_slice(&5_000u64.to_le_bytes());
+
+        let named = DecoderRegistry::with_builtin()
+            .decode(SYSTEM_PROGRAM_ID, &data, &accounts(&["from", "to"]))
+            .unwrap();
+        assert_eq!((named.program.as_str(), named.name.as_str()), ("system", "transfer"));
+        assert_eq!(named.parsed(), json!({
+            "type": "transfer",
+            "info": { "source": "from", "destination": "to", "lamports": 5_000 },
+        }));
+    }
+
+    #[test]
+    fn token_optional_pubkeys_and_short_data() {
+        let (mint_authority, mint_authority_b58) = pubkey(7);
+        let mut data = vec![0, 6];
+        data.extend_from_slice(&mint_authority);
+        data.push(0);
+
+        let registry = DecoderRegistry::with_builtin();
+        let named = registry.decode(TOKEN_PROGRAM_ID, &data, &accounts(&["mint"])).unwrap();
+        assert_eq!(named.name, "initializeMint");
+        assert_eq!(named.info["decimals"], 6);
+        assert_eq!(named.info["mintAuthority"], mint_authority_b58.as_str());
+        assert_eq!(named.info["freezeAuthority"], Value::Null);
+
+        // transferChecked cut short keeps the arguments that were read
+        let mut data = vec![12];
+        data.extend_from_slice(&42u64.to_le_bytes());
+        let named = registry.decode(TOKEN_PROGRAM_ID, &data, &[]).unwrap();
+        assert_eq!(named.info["amount"], 42);
+        assert!(!named.info.contains_key("decimals"));
+    }
+
+    #[test]
+    fn token_extensions_are_token_2022_only() {
+        let registry = DecoderRegistry::with_builtin();
+        let data = [SPL_TOKEN_INSTRUCTION_COUNT as u8 + 1];
+
+        assert!(registry.decode(TOKEN_PROGRAM_ID, &data, &[]).is_none());
+        assert_eq!(registry.decode(TOKEN_2022_PROGRAM_ID, &data, &[]).unwrap().name, "transferFeeExtension");
+    }
+
+    #[test]
+    fn associated_token_and_memo() {
+        let registry = DecoderRegistry::with_builtin();
+        assert_eq!(registry.decode(ASSOCIATED_TOKEN_PROGRAM_ID, &[], &[]).unwrap().name, "create");
+        assert_eq!(registry.decode(ASSOCIATED_TOKEN_PROGRAM_ID, &[1], &[]).unwrap().name, "createIdempotent");
+        assert!(registry.decode(ASSOCIATED_TOKEN_PROGRAM_ID, &[9], &[]).is_none());
+
+        let memo = registry.decode(MEMO_PROGRAM_ID, b"gm", &[]).unwrap();
+        assert_eq!(memo.info["memo"], "gm");
+    }
+
+    #[test]
+    fn unknown_programs_and_tags_are_not_decoded() {
+        let registry = DecoderRegistry::with_builtin();
+        assert!(registry.decode("Unknown1111111111111111111111111111111111111", &[0], &[]).is_none());
+        assert!(registry.decode(COMPUTE_BUDGET_PROGRAM_ID, &[200], &[]).is_none());
+    }
+
+    #[test]
+    fn anchor_discriminators_are_derived_from_snake_case_names() {
+        assert_eq!(anchor_discriminator("initialize"), [175, 175, 109, 31, 13, 152, 155, 237]);
+        assert_eq!(anchor_discriminator("initializeVault"), {
+            let hash = Sha256::digest(b"global:initialize_vault");
+            <[u8; 8]>::try_from(&hash[..8]).unwrap()
+        });
+    }
+
+    #[test]
+    fn legacy_anchor_idl_decodes_nested_accounts_and_args() {
+        let idl = json!({
+            "name": "vault",
+            "metadata": { "address": "Vault11111111111111111111111111111111111111" },
+            "instructions": [{
+                "name": "deposit",
+                "accounts": [
+                    { "name": "user" },
+                    { "name": "vault", "accounts": [{ "name": "state" }, { "name": "tokens" }] },
+                ],
+                "args": [
+                    { "name": "amount", "type": "u64" },
+                    { "name": "label", "type": "string" },
+                    { "name": "limit", "type": { "option": "u16" } },
+                    { "name": "side", "type": { "defined": "Side" } },
+                    { "name": "weights", "type": { "vec": "u8" } },
+                ],
+            }],
+            "types": [{
+                "name": "Side",
+                "type": { "kind": "enum", "variants": [{ "name": "Bid" }, { "name": "Ask" }] },
+            }],
+        });
+        let decoder = AnchorDecoder::from_idl(&idl).unwrap();
+        assert_eq!((decoder.program_id(), decoder.program_name()), ("Vault11111111111111111111111111111111111111", "vault"));
+
+        let mut data = anchor_discriminator("deposit").to_vec();
+        data.extend_from_slice(&1_000u64.to_le_bytes());
+        data.extend_from_slice(&2u32.to_le_bytes());
+        data.extend_from_slice(b"hi");
+        data.push(0);
+        data.push(1);
+        data.extend_from_slice(&2u32.to_le_bytes());
+        data.extend_from_slice(&[3, 4]);
+
+        let named = decoder.decode(&data, &accounts(&["a", "b", "c"])).unwrap();
+        assert_eq!(named.name, "deposit");
+        assert_eq!(Value::Object(named.info), json!({
+            "user": "a",
+            "state": "b",
+            "tokens": "c",
+            "amount": 1_000,
+            "label": "hi",
+            "limit": null,
+            "side": "Ask",
+            "weights": [3, 4],
+        }));
+    }
+
+    #[test]
+    fn current_anchor_idl_uses_explicit_discriminators_and_structs() {
+        let idl = json!({
+            "address": "Pool111111111111111111111111111111111111111",
+            "metadata": { "name": "pool" },
+            "instructions": [{
+                "name": "swap",
+                "discriminator": [1, 2, 3, 4, 5, 6, 7, 8],
+                "accounts": [{ "name": "payer" }],
+                "args": [{ "name": "params", "type": { "defined": { "name": "SwapParams" } } }],
+            }],
+            "types": [{
+                "name": "SwapParams",
+                "type": {
+                    "kind": "struct",
+                    "fields": [
+                        { "name": "amountIn", "type": "u64" },
+                        { "name": "exactOut", "type": "bool" },
+                        { "name": "route", "type": { "array": ["u8", 2] } },
+                    ],
+                },
+            }],
+        });
+        let decoder = AnchorDecoder::from_idl(&idl).unwrap();
+        assert_eq!(decoder.program_name(), "pool");
+
+        let mut data = vec![1, 2, 3, 4, 5, 6, 7, 8];
+        data.extend_from_slice(&9u64.to_le_bytes());
+        data.extend_from_slice(&[1, 5, 6]);
+
+        let named = decoder.decode(&data, &accounts(&["payer-key"])).unwrap();
+        assert_eq!(named.info["payer"], "payer-key");
+        assert_eq!(named.info["params"], json!({ "amountIn": 9, "exactOut": true, "route": [5, 6] }));
+
+        assert!(decoder.decode(&[8, 7, 6, 5, 4, 3, 2, 1], &[]).is_none());
+    }
+
+    #[test]
+    fn anchor_idl_needs_an_address() {
+        assert!(AnchorDecoder::from_idl(&json!({ "name": "nameless", "instructions": [] })).is_err());
+    }
+}
@@ -0,0 +1,535 @@
+use chrono::{DateTime, Utc};
+use serde_json::{json, Map, Value};
+use yellowstone_grpc_proto::prelude::SubscribeUpdateTransactionInfo;
+
+use crate::activity_classifier::PUMP_FUN_PROGRAM_ID;
+use crate::program_decoders::{NamedInstruction, ProgramDecoder, Reader};
+
+const CREATE_DISCRIMINATOR: [u8; 8] = [24, 30, 200, 40, 5, 28, 7, 119];
+const BUY_DISCRIMINATOR: [u8; 8] = [102, 6, 61, 18, 1, 218, 235, 234];
+const SELL_DISCRIMINATOR: [u8; 8] = [51, 230, 133, 164, 1, 127, 131, 173];
+
+/// Prefix of the self-invocations Anchor's `emit_cpi!` logs events with
+const EVENT_CPI_TAG: [u8; 8] = [228, 69, 165, 46, 81, 203, 154, 29];
+const TRADE_EVENT_DISCRIMINATOR: [u8; 8] = [189, 219, 127, 211, 78, 230, 97, 238];
+const CREATE_EVENT_DISCRIMINATOR: [u8; 8] = [27, 114, 169, 77, 222, 83, 22, 79];
+
+/// Every pump.fun token has 6 decimals
+pub const PUMP_FUN_TOKEN_DECIMALS: i32 = 6;
+
+const LAMPORTS_PER_SOL: f64 = 1_000_000_000.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TradeAction {
+    Create,
+    Buy,
+    Sell,
+}
+
+impl TradeAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TradeAction::Create => "CREATE",
+            TradeAction::Buy => "BUY",
+            TradeAction::Sell => "SELL",
+        }
+    }
+}
+
+/// Bonding curve reserves right after a trade
+#[derive(Debug, Clone, Copy)]
+pub struct BondingCurveState {
+    pub virtual_sol_reserves: u64,
+    pub virtual_token_reserves: u64,
+    /// Only reported by newer versions of the program
+    pub real_sol_reserves: Option<u64>,
+    pub real_token_reserves: Option<u64>,
+}
+
+impl BondingCurveState {
+    /// SOL per whole token at these reserves
+    pub fn price(&self) -> f64 {
+        if self.virtual_token_reserves == 0 {
+            return 0.0;
+        }
+        (self.virtual_sol_reserves as f64 / LAMPORTS_PER_SOL)
+            / (self.virtual_token_reserves as f64 / 10f64.powi(PUMP_FUN_TOKEN_DECIMALS))
+    }
+}
+
+/// A pump.fun create, buy or sell, as stored in `token_trades`
+#[derive(Debug, Clone)]
+pub struct TokenTrade {
+    pub signature: String,
+    pub slot: u64,
+    /// Position among the transaction's trades
+    pub trade_index: u32,
+    pub action: TradeAction,
+    pub mint: String,
+    pub trader: String,
+    pub bonding_curve: Option<String>,
+    /// Lamports paid or received, fees excluded
+    pub sol_amount: Option<u64>,
+    /// Raw token units bought or sold
+    pub token_amount: Option<u64>,
+    pub curve: Option<BondingCurveState>,
+    /// Token metadata, for creates
+    pub name: Option<String>,
+    pub symbol: Option<String>,
+    pub uri: Option<String>,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl TokenTrade {
+    pub fn sol(&self) -> Option<f64> {
+        self.sol_amount.map(|lamports| lamports as f64 / LAMPORTS_PER_SOL)
+    }
+
+    /// Token amount in whole tokens
+    pub fn tokens(&self) -> Option<f64> {
+        self.token_amount.map(|amount| amount as f64 / 10f64.powi(PUMP_FUN_TOKEN_DECIMALS))
+    }
+}
+
+/// A decoded pump.fun instruction or event
+#[derive(Debug, Clone)]
+enum PumpFunInstruction {
+    Create {
+        name: String,
+        symbol: String,
+        uri: String,
+        mint: String,
+        bonding_curve: String,
+        user: String,
+    },
+    /// `buy` caps the SOL spent, `sell` floors the SOL received
+    Trade {
+        action: TradeAction,
+        mint: String,
+        bonding_curve: String,
+        user: String,
+        token_amount: u64,
+        sol_limit: u64,
+    },
+    TradeEvent {
+        mint: String,
+        sol_amount: u64,
+        token_amount: u64,
+        is_buy: bool,
+        user: String,
+        timestamp: i64,
+        curve: BondingCurveState,
+    },
+    CreateEvent {
+        name: String,
+        symbol: String,
+        uri: String,
+        mint: String,
+        bonding_curve: String,
+        user: String,
+    },
+}
+
+fn decode(data: &[u8], accounts: &[String]) -> Option<PumpFunInstruction> {
+    let account = |index: usize| accounts.get(index).cloned();
+    let (discriminator, args) = data.split_first_chunk::<8>()?;
+    let mut reader = Reader::new(args);
+
+    match *discriminator {
+        CREATE_DISCRIMINATOR => Some(PumpFunInstruction::Create {
+            name: reader.string()?,
+            symbol: reader.string()?,
+            uri: reader.string()?,
+            mint: account(0)?,
+            bonding_curve: account(2)?,
+            user: account(7)?,
+        }),
+        BUY_DISCRIMINATOR | SELL_DISCRIMINATOR => Some(PumpFunInstruction::Trade {
+            action: if *discriminator == BUY_DISCRIMINATOR { TradeAction::Buy } else { TradeAction::Sell },
+            token_amount: reader.u64()?,
+            sol_limit: reader.u64()?,
+            mint: account(2)?,
+            bonding_curve: account(3)?,
+            user: account(6)?,
+        }),
+        EVENT_CPI_TAG => {
+            let (event, fields) = args.split_first_chunk::<8>()?;
+            let mut reader = Reader::new(fields);
+            match *event {
+                TRADE_EVENT_DISCRIMINATOR => Some(PumpFunInstruction::TradeEvent {
+                    mint: reader.pubkey()?,
+                    sol_amount: reader.u64()?,
+                    token_amount: reader.u64()?,
+                    is_buy: reader.u8()? != 0,
+                    user: reader.pubkey()?,
+                    timestamp: reader.u64()? as i64,
+                    curve: BondingCurveState {
+                        virtual_sol_reserves: reader.u64()?,
+                        virtual_token_reserves: reader.u64()?,
+                        real_sol_reserves: reader.u64(),
+                        real_token_reserves: reader.u64(),
+                    },
+                }),
+                CREATE_EVENT_DISCRIMINATOR => Some(PumpFunInstruction::CreateEvent {
+                    name: reader.string()?,
+                    symbol: reader.string()?,
+                    uri: reader.string()?,
+                    mint: reader.pubkey()?,
+                    bonding_curve: reader.pubkey()?,
+                    user: reader.pubkey()?,
+                }),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Every pump.fun create, buy and sell in a Geyser transaction update.
+/// Instructions are completed by the events they emit; a trade without an
+/// event takes its SOL amount from the bonding curve's balance change.
+pub fn extract_trades(info: &SubscribeUpdateTransactionInfo, slot: u64) -> Vec<TokenTrade> {
+    let Some(message) = info.transaction.as_ref().and_then(|transaction| transaction.message.as_ref()) else {
+        return Vec::new();
+    };
+    let meta = info.meta.as_ref();
+    if meta.is_some_and(|meta| meta.err.is_some()) {
+        return Vec::new();
+    }
+
+    let mut keys: Vec<String> = message.account_keys.iter().map(|key| bs58::encode(key).into_string()).collect();
+    if let Some(meta) = meta {
+        keys.extend(meta.loaded_writable_addresses.iter()
+            .chain(&meta.loaded_readonly_addresses)
+            .map(|key| bs58::encode(key).into_string()));
+    }
+
+    // Outer instructions, each followed by its inner ones
+    let mut raw: Vec<(u32, &[u8], &[u8])> = Vec::new();
+    for (index, instruction) in message.instructions.iter().enumerate() {
+        raw.push((instruction.program_id_index, instruction.accounts.as_slice(), instruction.data.as_slice()));
+        let inner = meta.and_then(|meta| meta.inner_instructions.iter().find(|group| group.index as usize == index));
+        for instruction in inner.into_iter().flat_map(|group| &group.instructions) {
+            raw.push((instruction.program_id_index, instruction.accounts.as_slice(), instruction.data.as_slice()));
+        }
+    }
+
+    let signature = bs58::encode(&info.signature).into_string();
+    let new_trade = |action: TradeAction, mint: String, trader: String| TokenTrade {
+        signature: signature.clone(),
+        slot,
+        trade_index: 0,
+        action,
+        mint,
+        trader,
+        bonding_curve: None,
+        sol_amount: None,
+        token_amount: None,
+        curve: None,
+        name: None,
+        symbol: None,
+        uri: None,
+        timestamp: Utc::now(),
+    };
+
+    let mut trades: Vec<TokenTrade> = Vec::new();
+    for (program_id_index, accounts, data) in raw {
+        if keys.get(program_id_index as usize).map(String::as_str) != Some(PUMP_FUN_PROGRAM_ID) {
+            continue;
+        }
+        let accounts: Vec<String> = accounts.iter()
+            .map(|index| keys.get(*index as usize).cloned().unwrap_or_default())
+            .collect();
+
+        match decode(data, &accounts) {
+            Some(PumpFunInstruction::Create { name, symbol, uri, mint, bonding_curve, user }) => {
+                trades.push(TokenTrade {
+                    bonding_curve: Some(bonding_curve),
+                    name: Some(name),
+                    symbol: Some(symbol),
+                    uri: Some(uri),
+                    ..new_trade(TradeAction::Create, mint, user)
+                });
+            }
+            Some(PumpFunInstruction::Trade { action, mint, bonding_curve, user, token_amount, .. }) => {
+                trades.push(TokenTrade {
+                    bonding_curve: Some(bonding_curve),
+                    token_amount: Some(token_amount),
+                    ..new_trade(action, mint, user)
+                });
+            }
+            Some(PumpFunInstruction::CreateEvent { name, symbol, uri, mint, bonding_curve, user }) => {
+                if !trades.iter().any(|trade| trade.action == TradeAction::Create && trade.mint == mint) {
+                    trades.push(TokenTrade {
+                        bonding_curve: Some(bonding_curve),
+                        name: Some(name),
+                        symbol: Some(symbol),
+                        uri: Some(uri),
+                        ..new_trade(TradeAction::Create, mint, user)
+                    });
+                }
+            }
+            Some(PumpFunInstruction::TradeEvent { mint, sol_amount, token_amount, is_buy, user, timestamp, curve }) => {
+                let action = if is_buy { TradeAction::Buy } else { TradeAction::Sell };
+                let pending = trades.iter()
+                    .rposition(|trade| trade.action == action && trade.mint == mint && trade.curve.is_none());
+                let index = pending.unwrap_or_else(|| {
+                    trades.push(new_trade(action, mint, user));
+                    trades.len() - 1
+                });
+                let trade = &mut trades[index];
+                trade.sol_amount = Some(sol_amount);
+                trade.token_amount = Some(token_amount);
+                trade.curve = Some(curve);
+                if let Some(timestamp) = DateTime::from_timestamp(timestamp, 0) {
+                    trade.timestamp = timestamp;
+                }
+            }
+            None => {}
+        }
+    }
+
+    for (index, trade) in trades.iter_mut().enumerate() {
+        trade.trade_index = index as u32;
+        if trade.action == TradeAction::Create || trade.sol_amount.is_some() {
+            continue;
+        }
+        let curve_index = trade.bonding_curve.as_ref().and_then(|curve| keys.iter().position(|key| key == curve));
+        if let (Some(curve_index), Some(meta)) = (curve_index, meta) {
+            if let (Some(pre), Some(post)) = (meta.pre_balances.get(curve_index), meta.post_balances.get(curve_index)) {
+                trade.sol_amount = Some(pre.abs_diff(*post));
+            }
+        }
+    }
+
+    trades
+}
+
+/// Registry decoder naming pump.fun instructions and events
+pub struct PumpFunDecoder;
+
+impl ProgramDecoder for PumpFunDecoder {
+    fn program_id(&self) -> &str {
+        PUMP_FUN_PROGRAM_ID
+    }
+
+    fn program_name(&self) -> &str {
+        "pump-fun"
+    }
+
+    fn decode(&self, data: &[u8], accounts: &[String]) -> Option<NamedInstruction> {
+        let (name, info) = match decode(data, accounts)? {
+            PumpFunInstruction::Create { name, symbol, uri, mint, bonding_curve, user } => ("create", json!({
+                "mint": mint, "bondingCurve": bonding_curve, "user": user, "name": name, "symbol": symbol, "uri": uri,
+            })),
+            PumpFunInstruction::Trade { action, mint, bonding_curve, user, token_amount, sol_limit } => match action {
+                TradeAction::Sell => ("sell", json!({
+                    "mint": mint, "bondingCurve": bonding_curve, "user": user, "amount": token_amount, "minSolOutput": sol_limit,
+                })),
+                _ => ("buy", json!({
+                    "mint": mint, "bondingCurve": bonding_curve, "user": user, "amount": token_amount, "maxSolCost": sol_limit,
+                })),
+            },
+            PumpFunInstruction::TradeEvent { mint, sol_amount, token_amount, is_buy, user, timestamp, curve } => ("tradeEvent", json!({
+                "mint": mint,
+                "solAmount": sol_amount,
+                "tokenAmount": token_amount,
+                "isBuy": is_buy,
+                "user": user,
+                "timestamp": timestamp,
+                "virtualSolReserves": curve.virtual_sol_reserves,
+                "virtualTokenReserves": curve.virtual_token_reserves,
+                "realSolReserves": curve.real_sol_reserves,
+                "realTokenReserves": curve.real_token_reserves,
+            })),
+            PumpFunInstruction::CreateEvent { name, symbol, uri, mint, bonding_curve, user } => ("createEvent", json!({
+                "name": name, "symbol": symbol, "uri": uri, "mint": mint, "bondingCurve": bonding_curve, "user": user,
+            })),
+        };
+
+        let info = match info {
+            Value::Object(info) => info,
+            _ => Map::new(),
+        };
+        Some(NamedInstruction { program: self.program_name().to_string(), name: name.to_string(), info })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use yellowstone_grpc_proto::prelude::{
+        CompiledInstruction, InnerInstruction, InnerInstructions, Message, Transaction, TransactionError,
+        TransactionStatusMeta,
+    };
+
+    const USER: u8 = 1;
+    const MINT: u8 = 2;
+    const CURVE: u8 = 3;
+
+    fn key(byte: u8) -> Vec<u8> {
+        vec![byte; 32]
+    }
+
+    fn address(byte: u8) -> String {
+        bs58::encode(key(byte)).into_string()
+    }
+
+    fn accounts(bytes: &[u8]) -> Vec<String> {
+        bytes.iter().map(|byte| address(*byte)).collect()
+    }
+
+    fn borsh_string(data: &mut Vec<u8>, value: &str) {
+        data.extend_from_slice(&(value.len() as u32).to_le_bytes());
+        data.extend_from_slice(value.as_bytes());
+    }
+
+    fn trade_data(discriminator: [u8; 8], token_amount: u64, sol_limit: u64) -> Vec<u8> {
+        let mut data = discriminator.to_vec();
+        data.extend_from_slice(&token_amount.to_le_bytes());
+        data.extend_from_slice(&sol_limit.to_le_bytes());
+        data
+    }
+
+    fn trade_event(sol_amount: u64, token_amount: u64, is_buy: bool, real_reserves: bool) -> Vec<u8> {
+        let mut data = [EVENT_CPI_TAG, TRADE_EVENT_DISCRIMINATOR].concat();
+        data.extend_from_slice(&key(MINT));
+        data.extend_from_slice(&sol_amount.to_le_bytes());
+        data.extend_from_slice(&token_amount.to_le_bytes());
+        data.push(is_buy as u8);
+        data.extend_from_slice(&key(USER));
+        data.extend_from_slice(&1_700_000_000u64.to_le_bytes());
+        data.extend_from_slice(&30_000_000_000u64.to_le_bytes());
+        data.extend_from_slice(&1_000_000_000_000_000u64.to_le_bytes());
+        if real_reserves {
+            data.extend_from_slice(&5u64.to_le_bytes());
+            data.extend_from_slice(&6u64.to_le_bytes());
+        }
+        data
+    }
+
+    /// A transaction whose account keys are the user, pump.fun, the mint and
+    /// the bonding curve, with one outer pump.fun instruction
+    fn transaction(data: Vec<u8>, inner: Vec<Vec<u8>>, meta: TransactionStatusMeta) -> SubscribeUpdateTransactionInfo {
+        let program_id = bs58::decode(PUMP_FUN_PROGRAM_ID).into_vec().unwrap();
+        let trade_accounts = vec![1, 1, 2, 3, 1, 1, 0];
+        let inner_instructions = inner.into_iter()
+            .map(|data| InnerInstruction { program_id_index: 1, accounts: vec![1], data, ..Default::default() })
+            .collect();
+
+        SubscribeUpdateTransactionInfo {
+            signature: vec![9; 64],
+            transaction: Some(Transaction {
+                message: Some(Message {
+                    account_keys: vec![key(USER), program_id, key(MINT), key(CURVE)],
+                    instructions: vec![CompiledInstruction { program_id_index: 1, accounts: trade_accounts, data }],
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }),
+            meta: Some(TransactionStatusMeta {
+                inner_instructions: vec![InnerInstructions { index: 0, instructions: inner_instructions, ..Default::default() }],
+                ..meta
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn decodes_create_and_trade_instructions() {
+        let mut data = CREATE_DISCRIMINATOR.to_vec();
+        borsh_string(&mut data, "Token");
+        borsh_string(&mut data, "TKN");
+        borsh_string(&mut data, "https://example.com/token.json");
+        let create = PumpFunDecoder.decode(&data, &accounts(&[MINT, 0, CURVE, 0, 0, 0, 0, USER])).unwrap();
+        assert_eq!(create.name, "create");
+        assert_eq!(create.info["symbol"], "TKN");
+        assert_eq!(create.info["bondingCurve"], address(CURVE).as_str());
+        assert_eq!(create.info["user"], address(USER).as_str());
+
+        let sell = PumpFunDecoder.decode(&trade_data(SELL_DISCRIMINATOR, 500, 40), &accounts(&[0, 0, MINT, CURVE, 0, 0, USER])).unwrap();
+        assert_eq!(sell.name, "sell");
+        assert_eq!((sell.info["amount"].as_u64(), sell.info["minSolOutput"].as_u64()), (Some(500), Some(40)));
+
+        // Missing accounts or arguments leave the instruction undecoded
+        assert!(PumpFunDecoder.decode(&trade_data(BUY_DISCRIMINATOR, 500, 40), &accounts(&[0, 0, MINT])).is_none());
+        assert!(PumpFunDecoder.decode(&BUY_DISCRIMINATOR, &accounts(&[0, 0, MINT, CURVE, 0, 0, USER])).is_none());
+    }
+
+    #[test]
+    fn trade_event_real_reserves_are_optional() {
+        let Some(PumpFunInstruction::TradeEvent { curve, is_buy, timestamp, .. }) = decode(&trade_event(1, 2, true, false), &[]) else {
+            panic!("trade event not decoded");
+        };
+        assert!(is_buy);
+        assert_eq!(timestamp, 1_700_000_000);
+        assert_eq!((curve.real_sol_reserves, curve.real_token_reserves), (None, None));
+
+        let Some(PumpFunInstruction::TradeEvent { curve, .. }) = decode(&trade_event(1, 2, false, true), &[]) else {
+            panic!("trade event not decoded");
+        };
+        assert_eq!((curve.real_sol_reserves, curve.real_token_reserves), (Some(5), Some(6)));
+    }
+
+    #[test]
+    fn curve_price_is_sol_per_whole_token() {
+        let curve = BondingCurveState {
+            virtual_sol_reserves: 30_000_000_000,
+            virtual_token_reserves: 1_000_000_000_000_000,
+            real_sol_reserves: None,
+            real_token_reserves: None,
+        };
+        assert!((curve.price() - 0.00000003).abs() < 1e-15);
+        assert_eq!(BondingCurveState { virtual_token_reserves: 0, ..curve }.price(), 0.0);
+    }
+
+    #[test]
+    fn buy_is_completed_by_its_trade_event() {
+        let info = transaction(
+            trade_data(BUY_DISCRIMINATOR, 1_000_000, 2_000_000_000),
+            vec![trade_event(1_500_000_000, 900_000, true, true)],
+            TransactionStatusMeta::default(),
+        );
+
+        let trades = extract_trades(&info, 42);
+        assert_eq!(trades.len(), 1);
+        let trade = &trades[0];
+        assert_eq!((trade.action, trade.slot, trade.trade_index), (TradeAction::Buy, 42, 0));
+        assert_eq!((trade.mint.as_str(), trade.trader.as_str()), (address(MINT).as_str(), address(USER).as_str()));
+        assert_eq!(trade.bonding_curve.as_deref(), Some(address(CURVE).as_str()));
+        assert_eq!((trade.sol_amount, trade.token_amount), (Some(1_500_000_000), Some(900_000)));
+        assert_eq!(trade.sol(), Some(1.5));
+        assert_eq!(trade.tokens(), Some(0.9));
+        assert_eq!(trade.timestamp.timestamp(), 1_700_000_000);
+        assert!(trade.curve.is_some());
+    }
+
+    #[test]
+    fn sell_without_event_uses_the_curve_balance_change() {
+        let info = transaction(
+            trade_data(SELL_DISCRIMINATOR, 1_000_000, 0),
+            Vec::new(),
+            TransactionStatusMeta {
+                pre_balances: vec![10, 1, 1, 5_000_000],
+                post_balances: vec![10, 1, 1, 4_000_000],
+                ..Default::default()
+            },
+        );
+
+        let trades = extract_trades(&info, 42);
+        assert_eq!(trades.len(), 1);
+        assert_eq!(trades[0].action, TradeAction::Sell);
+        assert_eq!((trades[0].sol_amount, trades[0].token_amount), (Some(1_000_000), Some(1_000_000)));
+        assert!(trades[0].curve.is_none());
+    }
+
+    #[test]
+    fn failed_transactions_have_no_trades() {
+        let info = transaction(
+            trade_data(BUY_DISCRIMINATOR, 1, 1),
+            Vec::new(),
+            TransactionStatusMeta { err: Some(TransactionError::default()), ..Default::default() },
+        );
+        assert!(extract_trades(&info, 42).is_empty());
+    }
+}
@@ -0,0 +1,315 @@
+use anyhow::Result;
+use colored::*;
+use serde::Serialize;
+use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcBlockConfig;
+use solana_commitment_config::CommitmentConfig;
+use solana_transaction_status::TransactionDetails;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use tracing::{debug, warn};
+
+use crate::database::Database;
+use crate::slot_tracker::is_skipped_slot_error;
+
+/// Most confirmed slots checked per call, so a long pause doesn't stall the caller
+const MAX_CHECK_BATCH: u64 = 64;
+
+/// Confirmed blocks kept for parent-hash checks
+const CHAIN_CAPACITY: usize = 512;
+
+/// A fork switch that orphaned slots we had already observed
+#[derive(Debug, Clone, Serialize)]
+pub struct ReorgEvent {
+    /// Confirmed slot whose arrival revealed the fork
+    pub slot: u64,
+    /// Last slot shared by the abandoned and the surviving fork
+    pub fork_slot: u64,
+    /// Number of orphaned slots
+    pub depth: u64,
+    pub orphaned_slots: Vec<u64>,
+    pub detected_at: i64,
+}
+
+#[derive(Debug, Clone)]
+struct BlockLink {
+    blockhash: String,
+    parent_slot: u64,
+}
+
+/// Follows the parent-hash chain of confirmed blocks and the slots seen at
+/// processed commitment, reporting slots that end up off the confirmed chain
+pub struct ForkTracker {
+    chain: BTreeMap<u64, BlockLink>,
+    /// Processed slots not yet checked against the confirmed chain
+    pending: BTreeSet<u64>,
+    last_checked: Option<u64>,
+}
+
+impl ForkTracker {
+    pub fn new() -> Self {
+        Self {
+            chain: BTreeMap::new(),
+            pending: BTreeSet::new(),
+            last_checked: None,
+        }
+    }
+
+    /// Remember a slot seen at processed commitment
+    pub fn observe_processed(&mut self, slot: u64) {
+        if self.last_checked.is_none_or(|checked| slot > checked) {
+            self.pending.insert(slot);
+        }
+    }
+
+    /// Poll the processed and confirmed tips and check every newly confirmed
+    /// slot against the slots observed so far
+    pub fn check(&mut self, client: &RpcClient) -> Result<Option<ReorgEvent>> {
+        let processed = client.get_slot_with_commitment(CommitmentConfig::processed())?;
+        self.observe_processed(processed);
+
+        let confirmed = client.get_slot_with_commitment(CommitmentConfig::confirmed())?;
+        let Some(last_checked) = self.last_checked else {
+            self.last_checked = Some(confirmed);
+            self.pending.retain(|slot| *slot > confirmed);
+            return Ok(None);
+        };
+        if confirmed <= last_checked {
+            return Ok(None);
+        }
+
+        let start = last_checked + 1;
+        let end = confirmed.min(start + MAX_CHECK_BATCH - 1);
+        let produced: HashSet<u64> = client
+            .get_blocks_with_commitment(start, Some(end), CommitmentConfig::confirmed())?
+            .into_iter()
+            .collect();
+
+        // Processed slots that never made it onto the confirmed chain were on an abandoned fork
+        let mut orphaned: BTreeSet<u64> = self.pending.range(start..=end)
+            .filter(|slot| !produced.contains(slot))
+            .copied()
+            .collect();
+        self.pending.retain(|slot| *slot > end);
+
+        let mut fork_slot = None;
+        let mut produced_slots: Vec<u64> = produced.into_iter().collect();
+        produced_slots.sort_unstable();
+
+        for slot in produced_slots {
+            let block = match client.get_block_with_config(slot, RpcBlockConfig {
+                transaction_details: Some(TransactionDetails::None),
+                rewards: Some(false),
+                commitment: Some(CommitmentConfig::confirmed()),
+                max_supported_transaction_version: Some(0),
+                ..RpcBlockConfig::default()
+            }) {
+                Ok(block) => block,
+                Err(e) if is_skipped_slot_error(&e) => continue,
+                Err(e) => {
+                    debug!("Failed to fetch block {} for fork check: {}", slot, e);
+                    continue;
+                }
+            };
+
+            let chain_orphans = self.observe_block(slot, block.blockhash, block.parent_slot, &block.previous_blockhash);
+            if !chain_orphans.is_empty() {
+                fork_slot = Some(fork_slot.map_or(block.parent_slot, |fork: u64| fork.min(block.parent_slot)));
+                orphaned.extend(chain_orphans);
+            }
+        }
+
+        self.last_checked = Some(end);
+
+        if orphaned.is_empty() {
+            return Ok(None);
+        }
+
+        let orphaned_slots: Vec<u64> = orphaned.into_iter().collect();
+        let first_orphan = orphaned_slots[0];
+        let fork_slot = fork_slot
+            .unwrap_or_else(|| self.chain.range(..first_orphan).next_back().map_or(first_orphan.saturating_sub(1), |(slot, _)| *slot))
+            .min(first_orphan.saturating_sub(1));
+
+        Ok(Some(ReorgEvent {
+            slot: end,
+            fork_slot,
+            depth: orphaned_slots.len() as u64,
+            orphaned_slots,
+            detected_at: chrono::Utc::now().timestamp(),
+        }))
+    }
+
+    /// Check a block pushed by a stream, which already carries its parent and
+    /// parent hash: processed slots between the parent and the block, and
+    /// chain blocks it doesn't build on, were on an abandoned fork
+    pub fn observe_streamed_block(&mut self, slot: u64, blockhash: String, parent_slot: u64, parent_blockhash: &str) -> Option<ReorgEvent> {
+        let mut orphaned: BTreeSet<u64> = self.pending.range(parent_slot + 1..slot.max(parent_slot + 1)).copied().collect();
+        self.pending.retain(|pending| *pending > slot);
+        orphaned.extend(self.observe_block(slot, blockhash, parent_slot, parent_blockhash));
+        self.last_checked = Some(self.last_checked.map_or(slot, |checked| checked.max(slot)));
+
+        if orphaned.is_empty() {
+            return None;
+        }
+
+        let orphaned_slots: Vec<u64> = orphaned.into_iter().collect();
+        Some(ReorgEvent {
+            slot,
+            fork_slot: parent_slot.min(orphaned_slots[0].saturating_sub(1)),
+            depth: orphaned_slots.len() as u64,
+            orphaned_slots,
+            detected_at: chrono::Utc::now().timestamp(),
+        })
+    }
+
+    /// Add a confirmed block to the chain, returning slots it proves orphaned:
+    /// blocks between its parent and itself, and a parent whose hash differs
+    fn observe_block(&mut self, slot: u64, blockhash: String, parent_slot: u64, previous_blockhash: &str) -> Vec<u64> {
+        let mut orphaned: Vec<u64> = self.chain.range(parent_slot + 1..)
+            .map(|(orphan, _)| *orphan)
+            .filter(|orphan| *orphan != slot)
+            .collect();
+
+        if let Some(parent) = self.chain.get(&parent_slot) {
+            if parent.blockhash != previous_blockhash {
+                orphaned.push(parent_slot);
+            }
+        }
+
+        for orphan in &orphaned {
+            self.chain.remove(orphan);
+        }
+        self.chain.insert(slot, BlockLink { blockhash, parent_slot });
+
+        while self.chain.len() > CHAIN_CAPACITY {
+            self.chain.pop_first();
+        }
+
+        orphaned
+    }
+}
+
+/// Log a reorg, roll back its orphaned rows and notify the webhook endpoint
+pub async fn apply_reorg(event: &ReorgEvent, database: Option<&Database>, webhook_base_url: Option<&str>) {
+    warn!("{} {} | Fork at slot {} orphaned {} slot(s): {:?}",
+        "🔀".bright_red(),
+        "REORG".bright_red(),
+        event.fork_slot,
+        event.depth,
+        event.orphaned_slots
+    );
+
+    if let Some(database) = database {
+        match database.rollback_orphaned_slots(event).await {
+            Ok(rows) => debug!("Rolled back {} rows for reorg at slot {}", rows, event.slot),
+            Err(e) => warn!("{} {} | Failed to roll back orphaned slots: {}",
+                "⚠️".bright_yellow(),
+                "REORG".bright_yellow(),
+                e
+            ),
+        }
+    }
+
+    if let Some(base_url) = webhook_base_url {
+        let base_url = base_url.to_string();
+        let data = serde_json::to_value(event).unwrap_or_default();
+        tokio::spawn(async move {
+            if let Err(e) = crate::webhooks::deliver_event(&base_url, "reorg", data).await {
+                warn!("{} {} | Failed to deliver reorg webhook: {}",
+                    "⚠️".bright_yellow(),
+                    "REORG".bright_yellow(),
+                    e
+                );
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hash(slot: u64) -> String {
+        format!("hash-{}", slot)
+    }
+
+    /// Confirm `slots` in order, each building on the previous one
+    fn confirm_chain(tracker: &mut ForkTracker, parent: u64, slots: &[u64]) {
+        let mut parent = parent;
+        for &slot in slots {
+            assert!(tracker.observe_block(slot, hash(slot), parent, &hash(parent)).is_empty());
+            parent = slot;
+        }
+    }
+
+    #[test]
+    fn linear_chain_orphans_nothing() {
+        let mut tracker = ForkTracker::new();
+        confirm_chain(&mut tracker, 9, &[10, 11, 13]);
+        assert_eq!(tracker.chain.len(), 3);
+    }
+
+    #[test]
+    fn block_on_an_older_parent_orphans_the_blocks_after_it() {
+        let mut tracker = ForkTracker::new();
+        confirm_chain(&mut tracker, 9, &[10, 11, 12]);
+
+        let orphaned = tracker.observe_block(13, hash(13), 10, &hash(10));
+        assert_eq!(orphaned, vec![11, 12]);
+        assert_eq!(tracker.chain.keys().copied().collect::<Vec<_>>(), vec![10, 13]);
+    }
+
+    #[test]
+    fn parent_with_a_different_hash_is_orphaned() {
+        let mut tracker = ForkTracker::new();
+        confirm_chain(&mut tracker, 9, &[10]);
+
+        let orphaned = tracker.observe_block(11, hash(11), 10, "other-hash");
+        assert_eq!(orphaned, vec![10]);
+    }
+
+    #[test]
+    fn chain_is_bounded() {
+        let mut tracker = ForkTracker::new();
+        let slots: Vec<u64> = (1..=CHAIN_CAPACITY as u64 + 10).collect();
+        confirm_chain(&mut tracker, 0, &slots);
+        assert_eq!(tracker.chain.len(), CHAIN_CAPACITY);
+        assert_eq!(tracker.chain.keys().next(), Some(&11));
+    }
+
+    #[test]
+    fn streamed_block_orphans_processed_slots_it_skips_over() {
+        let mut tracker = ForkTracker::new();
+        tracker.observe_processed(11);
+        tracker.observe_processed(12);
+        tracker.observe_processed(14);
+
+        let event = tracker.observe_streamed_block(13, hash(13), 10, &hash(10)).unwrap();
+        assert_eq!(event.orphaned_slots, vec![11, 12]);
+        assert_eq!((event.slot, event.fork_slot, event.depth), (13, 10, 2));
+
+        // Slots already checked are not tracked again, later ones still are
+        assert_eq!(tracker.pending.iter().copied().collect::<Vec<_>>(), vec![14]);
+        tracker.observe_processed(12);
+        assert!(!tracker.pending.contains(&12));
+    }
+
+    #[test]
+    fn streamed_skip_without_processed_slots_is_not_a_fork() {
+        let mut tracker = ForkTracker::new();
+        tracker.observe_processed(10);
+        assert!(tracker.observe_streamed_block(10, hash(10), 9, &hash(9)).is_none());
+        assert!(tracker.observe_streamed_block(12, hash(12), 10, &hash(10)).is_none());
+    }
+
+    #[test]
+    fn streamed_block_on_a_replaced_parent_reports_the_fork() {
+        let mut tracker = ForkTracker::new();
+        assert!(tracker.observe_streamed_block(10, hash(10), 9, &hash(9)).is_none());
+        assert!(tracker.observe_streamed_block(11, hash(11), 10, &hash(10)).is_none());
+
+        let event = tracker.observe_streamed_block(12, hash(12), 11, "other-hash").unwrap();
+        assert_eq!(event.orphaned_slots, vec![11]);
+        assert_eq!(event.fork_slot, 10);
+    }
+}
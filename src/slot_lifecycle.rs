@@ -0,0 +1,361 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use colored::*;
+use futures::StreamExt;
+use futures::stream::BoxStream;
+use solana_client::nonblocking::pubsub_client::PubsubClient;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_response::SlotInfo;
+use solana_commitment_config::{CommitmentConfig, CommitmentLevel};
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, RwLock};
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+use crate::database::Database;
+
+/// Latency samples kept for the rolling percentiles
+const LATENCY_WINDOW_SLOTS: usize = 10_000;
+
+/// Slots whose timings are kept in memory, including recently finalized ones
+const MAX_TRACKED_SLOTS: usize = 2_048;
+
+/// There is no pubsub notification for confirmation, so the confirmed tip is polled
+const CONFIRMED_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+const RECONNECT_DELAY: Duration = Duration::from_secs(2);
+
+/// When a slot was first seen and when it reached confirmed and finalized
+#[derive(Debug, Clone)]
+pub struct SlotTiming {
+    pub slot: u64,
+    pub first_seen_at: DateTime<Utc>,
+    pub confirmed_at: Option<DateTime<Utc>>,
+    pub finalized_at: Option<DateTime<Utc>>,
+}
+
+impl SlotTiming {
+    /// Milliseconds from first seen to confirmed
+    pub fn confirmation_ms(&self) -> Option<u64> {
+        self.confirmed_at.map(|at| (at - self.first_seen_at).num_milliseconds().max(0) as u64)
+    }
+
+    /// Milliseconds from first seen to finalized
+    pub fn finalization_ms(&self) -> Option<u64> {
+        self.finalized_at.map(|at| (at - self.first_seen_at).num_milliseconds().max(0) as u64)
+    }
+}
+
+/// Nearest-rank percentiles over a set of latency samples, in milliseconds
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LatencyPercentiles {
+    pub samples: usize,
+    pub p50: u64,
+    pub p95: u64,
+    pub p99: u64,
+}
+
+impl LatencyPercentiles {
+    pub fn from_samples(mut samples: Vec<u64>) -> Self {
+        if samples.is_empty() {
+            return Self::default();
+        }
+        samples.sort_unstable();
+
+        let rank = |percentile: usize| {
+            let index = (samples.len() * percentile).div_ceil(100).max(1) - 1;
+            samples[index]
+        };
+
+        Self {
+            samples: samples.len(),
+            p50: rank(50),
+            p95: rank(95),
+            p99: rank(99),
+        }
+    }
+}
+
+/// Follows slots through processed, confirmed and finalized, timing each
+/// transition from the moment the slot was first seen
+pub struct SlotLifecycle {
+    slots: BTreeMap<u64, SlotTiming>,
+    confirmed_tip: u64,
+    finalized_tip: u64,
+    confirmation_samples: VecDeque<u64>,
+    finalization_samples: VecDeque<u64>,
+    finalized_count: u64,
+}
+
+impl SlotLifecycle {
+    pub fn new() -> Self {
+        Self {
+            slots: BTreeMap::new(),
+            confirmed_tip: 0,
+            finalized_tip: 0,
+            confirmation_samples: VecDeque::with_capacity(LATENCY_WINDOW_SLOTS),
+            finalization_samples: VecDeque::with_capacity(LATENCY_WINDOW_SLOTS),
+            finalized_count: 0,
+        }
+    }
+
+    /// Record `slot` reaching `commitment` at `at`. A confirmed or finalized
+    /// tip also covers every tracked slot below it. Returns the timings that
+    /// changed, for persisting.
+    pub fn observe(&mut self, slot: u64, commitment: CommitmentLevel, at: DateTime<Utc>) -> Vec<SlotTiming> {
+        let mut changed = Vec::new();
+
+        match commitment {
+            CommitmentLevel::Processed => {
+                if slot <= self.confirmed_tip || self.slots.contains_key(&slot) {
+                    return changed;
+                }
+                let timing = SlotTiming { slot, first_seen_at: at, confirmed_at: None, finalized_at: None };
+                self.slots.insert(slot, timing.clone());
+                changed.push(timing);
+            }
+            CommitmentLevel::Confirmed => {
+                if slot <= self.confirmed_tip {
+                    return changed;
+                }
+                for timing in self.slots.range_mut(self.confirmed_tip + 1..=slot).map(|(_, timing)| timing) {
+                    if timing.confirmed_at.is_none() {
+                        timing.confirmed_at = Some(at);
+                        push_sample(&mut self.confirmation_samples, timing.confirmation_ms());
+                        changed.push(timing.clone());
+                    }
+                }
+                self.confirmed_tip = slot;
+            }
+            CommitmentLevel::Finalized => {
+                if slot <= self.finalized_tip {
+                    return changed;
+                }
+                for timing in self.slots.range_mut(self.finalized_tip + 1..=slot).map(|(_, timing)| timing) {
+                    if timing.finalized_at.is_none() {
+                        timing.finalized_at = Some(at);
+                        push_sample(&mut self.finalization_samples, timing.finalization_ms());
+                        self.finalized_count += 1;
+                        changed.push(timing.clone());
+                    }
+                }
+                self.finalized_tip = slot;
+                self.confirmed_tip = self.confirmed_tip.max(slot);
+            }
+        }
+
+        while self.slots.len() > MAX_TRACKED_SLOTS {
+            self.slots.pop_first();
+        }
+
+        changed
+    }
+
+    /// Timing of a recently seen slot
+    pub fn timing(&self, slot: u64) -> Option<&SlotTiming> {
+        self.slots.get(&slot)
+    }
+
+    /// Highest slot seen finalized
+    pub fn finalized_tip(&self) -> u64 {
+        self.finalized_tip
+    }
+
+    /// Number of slots timed through to finalized since startup
+    pub fn finalized_count(&self) -> u64 {
+        self.finalized_count
+    }
+
+    pub fn confirmation_percentiles(&self) -> LatencyPercentiles {
+        LatencyPercentiles::from_samples(self.confirmation_samples.iter().copied().collect())
+    }
+
+    pub fn finalization_percentiles(&self) -> LatencyPercentiles {
+        LatencyPercentiles::from_samples(self.finalization_samples.iter().copied().collect())
+    }
+}
+
+fn push_sample(samples: &mut VecDeque<u64>, sample: Option<u64>) {
+    if let Some(sample) = sample {
+        if samples.len() == LATENCY_WINDOW_SLOTS {
+            samples.pop_front();
+        }
+        samples.push_back(sample);
+    }
+}
+
+/// Record `slot` reaching `commitment` now and persist the timings that changed
+pub async fn record(lifecycle: &RwLock<SlotLifecycle>, database: Option<&Database>, slot: u64, commitment: CommitmentLevel) {
+    let changed = lifecycle.write().await.observe(slot, commitment, Utc::now());
+
+    if let Some(database) = database {
+        for timing in &changed {
+            if let Err(e) = database.upsert_slot_timing(timing).await {
+                warn!("Failed to persist timing for slot {}: {}", timing.slot, e);
+            }
+        }
+    }
+}
+
+/// `slotSubscribe` (first seen) and `rootSubscribe` (finalized) on one pubsub
+/// connection, merged with the confirmed tip polled in the background
+pub struct SlotNotifications<'a> {
+    slots: BoxStream<'a, SlotInfo>,
+    roots: BoxStream<'a, u64>,
+    confirmed: mpsc::Receiver<u64>,
+    poller: JoinHandle<()>,
+}
+
+impl<'a> SlotNotifications<'a> {
+    pub async fn subscribe(pubsub: &'a PubsubClient, rpc_url: String) -> Result<Self> {
+        // The streams end with the connection, so the unsubscribe handles aren't kept
+        let (slots, _) = pubsub.slot_subscribe().await?;
+        let (roots, _) = pubsub.root_subscribe().await?;
+        let (tx, confirmed) = mpsc::channel(1);
+
+        Ok(Self {
+            slots,
+            roots,
+            confirmed,
+            poller: tokio::spawn(poll_confirmed(RpcClient::new(rpc_url), tx)),
+        })
+    }
+
+    /// Next slot transition, or `Err` once the connection drops
+    pub async fn next(&mut self) -> Result<(u64, CommitmentLevel)> {
+        tokio::select! {
+            update = self.slots.next() => match update {
+                Some(update) => Ok((update.slot, CommitmentLevel::Processed)),
+                None => Err(anyhow::anyhow!("slotSubscribe stream closed")),
+            },
+            root = self.roots.next() => match root {
+                Some(root) => Ok((root, CommitmentLevel::Finalized)),
+                None => Err(anyhow::anyhow!("rootSubscribe stream closed")),
+            },
+            Some(slot) = self.confirmed.recv() => Ok((slot, CommitmentLevel::Confirmed)),
+        }
+    }
+}
+
+impl Drop for SlotNotifications<'_> {
+    fn drop(&mut self) {
+        self.poller.abort();
+    }
+}
+
+/// Send each new confirmed tip to `tips`, off the subscription's task so a
+/// slow `getSlot` never delays the pubsub timestamps
+async fn poll_confirmed(client: RpcClient, tips: mpsc::Sender<u64>) {
+    let mut ticker = tokio::time::interval(CONFIRMED_POLL_INTERVAL);
+    let mut last = 0;
+
+    loop {
+        ticker.tick().await;
+        match client.get_slot_with_commitment(CommitmentConfig::confirmed()).await {
+            Ok(slot) if slot > last => {
+                last = slot;
+                if tips.send(slot).await.is_err() {
+                    return;
+                }
+            }
+            Ok(_) => {}
+            Err(e) => debug!("Failed to poll confirmed slot: {}", e),
+        }
+    }
+}
+
+/// Feed `lifecycle` from its own `SlotNotifications` over `ws_url`,
+/// reconnecting whenever the subscription drops. Changed timings go to
+/// `database`. Only for sources without a slot subscription of their own.
+pub async fn run_ws_monitor(
+    ws_url: String,
+    rpc_url: String,
+    lifecycle: Arc<RwLock<SlotLifecycle>>,
+    database: Option<Database>,
+) {
+    loop {
+        if let Err(e) = follow_slots(&ws_url, &rpc_url, &lifecycle, database.as_ref()).await {
+            warn!("{} {} | Slot subscription ended: {}, reconnecting in {}s",
+                "⚠️".bright_yellow(),
+                "SLOT_LIFECYCLE".bright_yellow(),
+                e,
+                RECONNECT_DELAY.as_secs()
+            );
+        }
+        tokio::time::sleep(RECONNECT_DELAY).await;
+    }
+}
+
+async fn follow_slots(
+    ws_url: &str,
+    rpc_url: &str,
+    lifecycle: &RwLock<SlotLifecycle>,
+    database: Option<&Database>,
+) -> Result<()> {
+    let pubsub = PubsubClient::new(ws_url).await?;
+    let mut notifications = SlotNotifications::subscribe(&pubsub, rpc_url.to_string()).await?;
+
+    info!("{} {} | Following slot lifecycle over {}",
+        "⏱️".bright_green(),
+        "SLOT_LIFECYCLE".bright_green(),
+        ws_url.bright_cyan()
+    );
+
+    loop {
+        let (slot, commitment) = notifications.next().await?;
+        record(lifecycle, database, slot, commitment).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(ms: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp_millis(1_700_000_000_000 + ms).unwrap()
+    }
+
+    #[test]
+    fn percentiles_use_the_nearest_rank() {
+        let percentiles = LatencyPercentiles::from_samples((1..=100).rev().collect());
+        assert_eq!(percentiles.samples, 100);
+        assert_eq!((percentiles.p50, percentiles.p95, percentiles.p99), (50, 95, 99));
+
+        let single = LatencyPercentiles::from_samples(vec![7]);
+        assert_eq!((single.p50, single.p95, single.p99), (7, 7, 7));
+
+        let empty = LatencyPercentiles::from_samples(Vec::new());
+        assert_eq!((empty.samples, empty.p50), (0, 0));
+    }
+
+    #[test]
+    fn tips_cover_every_tracked_slot_below_them() {
+        let mut lifecycle = SlotLifecycle::new();
+        lifecycle.observe(10, CommitmentLevel::Processed, at(0));
+        lifecycle.observe(11, CommitmentLevel::Processed, at(400));
+
+        let changed = lifecycle.observe(11, CommitmentLevel::Confirmed, at(1_000));
+        assert_eq!(changed.iter().map(|timing| timing.slot).collect::<Vec<_>>(), vec![10, 11]);
+        assert_eq!(lifecycle.timing(10).unwrap().confirmation_ms(), Some(1_000));
+        assert_eq!(lifecycle.timing(11).unwrap().confirmation_ms(), Some(600));
+
+        lifecycle.observe(11, CommitmentLevel::Finalized, at(13_000));
+        assert_eq!(lifecycle.timing(10).unwrap().finalization_ms(), Some(13_000));
+        assert_eq!(lifecycle.finalized_count(), 2);
+        assert_eq!(lifecycle.finalization_percentiles().p50, 12_600);
+    }
+
+    #[test]
+    fn stale_notifications_change_nothing() {
+        let mut lifecycle = SlotLifecycle::new();
+        lifecycle.observe(10, CommitmentLevel::Processed, at(0));
+        lifecycle.observe(10, CommitmentLevel::Confirmed, at(500));
+
+        assert!(lifecycle.observe(10, CommitmentLevel::Processed, at(600)).is_empty());
+        assert!(lifecycle.observe(9, CommitmentLevel::Confirmed, at(700)).is_empty());
+        assert!(lifecycle.timing(9).is_none());
+        assert_eq!(lifecycle.confirmation_percentiles().samples, 1);
+    }
+}
@@ -1,20 +1,47 @@
 use anyhow::Result;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use colored::*;
 use solana_client::rpc_client::RpcClient;
+use solana_commitment_config::{CommitmentConfig, CommitmentLevel};
 use solana_sdk::hash::Hash;
 use solana_client::rpc_response::RpcBlockhash;
 use solana_client::rpc_config::RpcBlockConfig;
 use solana_client::rpc_config::RpcTransactionConfig;
 use solana_client::rpc_config::RpcAccountInfoConfig;
+use solana_rpc_client_api::{
+    client_error::{ClientError, ClientErrorKind},
+    custom_error::{
+        JSON_RPC_SERVER_ERROR_BLOCK_NOT_AVAILABLE, JSON_RPC_SERVER_ERROR_LONG_TERM_STORAGE_SLOT_SKIPPED,
+        JSON_RPC_SERVER_ERROR_SLOT_SKIPPED,
+    },
+    request::RpcError,
+};
 use solana_sdk::transaction::Transaction;
 use solana_sdk::account::Account;
-use std::collections::HashMap;
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
-use tokio::time::interval;
+use solana_sdk::pubkey::Pubkey;
+use solana_transaction_status::{
+    EncodedTransaction, EncodedTransactionWithStatusMeta, RewardType, TransactionBinaryEncoding, TransactionDetails,
+    UiTransactionEncoding,
+};
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use futures::{SinkExt, StreamExt};
+use yellowstone_grpc_proto::prelude as geyser;
+use solana_client::nonblocking::pubsub_client::PubsubClient;
+use solana_client::nonblocking::rpc_client::RpcClient as NonblockingRpcClient;
+use std::sync::Arc;
+use tokio::sync::{mpsc, RwLock};
+use tokio::time::{interval, Interval};
 use tracing::{info, debug, warn, error};
 
 use sha2::{Sha256, Digest};
+
+use crate::database::{Database, TrackedSlot, TrackedTransaction, ValidatorSkipRate};
+use crate::leader_schedule::LeaderScheduleCache;
+use crate::reorg::{self, ForkTracker, ReorgEvent};
+use crate::slot_lifecycle::{self, SlotLifecycle, SlotNotifications};
+use crate::transaction_decoder;
+use crate::yellowstone_monitor;
 use crossterm::terminal;
 use bs58;
 
@@ -26,18 +53,87 @@ pub struct BlockData {
     pub block_size_mb: f64,
     pub parent_slot: u64,
     pub timestamp: i64,
+    pub block_time: Option<i64>,
+    pub block_height: Option<u64>,
     pub leader_pubkey: String,
-    pub confirmation_time_ms: u64,
-    pub finalization_time_ms: u64,
+    /// Milliseconds from first seen to confirmed, when the slot was followed from the start
+    pub confirmation_time_ms: Option<u64>,
+    /// Milliseconds from first seen to finalized, when the slot was followed from the start
+    pub finalization_time_ms: Option<u64>,
     pub total_fees: u64,
+    pub total_rewards: i64,
     pub total_volume: u64,
     pub vote_count: u64,
     pub missed_slots: u64,
+    /// Depth of a reorg detected since the previous block was reported
     pub reorg_depth: Option<u64>,
     pub block_version: u8,
     pub commitment_level: String,
 }
 
+/// Result of looking up a slot's block
+#[derive(Debug, Clone)]
+pub enum BlockOutcome {
+    Produced(BlockData),
+    /// The leader did not produce a block for this slot
+    Skipped(u64),
+    /// The block exists but has not reached confirmed commitment yet
+    Pending(u64),
+}
+
+/// Result of fetching an observed slot for `--save`
+enum SaveFetch {
+    Ready(TrackedSlot),
+    Skipped,
+    Pending,
+}
+
+/// Per-validator produced/skipped counts over the most recent classified slots
+pub struct SkipRateWindow {
+    capacity: usize,
+    slots: VecDeque<(String, bool)>,
+    counts: HashMap<String, (u64, u64)>,
+}
+
+impl SkipRateWindow {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            slots: VecDeque::with_capacity(capacity),
+            counts: HashMap::new(),
+        }
+    }
+
+    /// Add a classified slot, evicting the oldest once the window is full
+    pub fn record(&mut self, leader: String, skipped: bool) {
+        if self.slots.len() == self.capacity {
+            if let Some((old_leader, old_skipped)) = self.slots.pop_front() {
+                if let Some(counts) = self.counts.get_mut(&old_leader) {
+                    counts.0 -= 1;
+                    counts.1 -= old_skipped as u64;
+                    if counts.0 == 0 {
+                        self.counts.remove(&old_leader);
+                    }
+                }
+            }
+        }
+
+        let counts = self.counts.entry(leader.clone()).or_insert((0, 0));
+        counts.0 += 1;
+        counts.1 += skipped as u64;
+        self.slots.push_back((leader, skipped));
+    }
+
+    /// Validators ordered by skip rate, worst first
+    pub fn rates(&self) -> Vec<ValidatorSkipRate> {
+        let mut rates: Vec<ValidatorSkipRate> = self.counts.iter()
+            .map(|(leader, (assigned, skipped))| ValidatorSkipRate::new(leader.clone(), *assigned, *skipped))
+            .collect();
+        rates.sort_by(|a, b| b.skip_rate().total_cmp(&a.skip_rate()).then(b.assigned_slots.cmp(&a.assigned_slots)));
+        rates
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct TransactionData {
     pub signature: String,
@@ -70,14 +166,379 @@ pub struct AccountData {
     pub executable: bool,
 }
 
+/// Number of most recent slots the per-validator skip rates cover
+const SKIP_RATE_WINDOW_SLOTS: usize = 10_000;
+
+/// Most slots classified per tick, so a long pause doesn't stall the display
+const MAX_CLASSIFY_BATCH: u64 = 500;
+
+/// Print the worst skip rates after this many classified slots
+const SKIP_RATE_REPORT_INTERVAL: u64 = 1_000;
+
+/// Slot updates buffered between the websocket task and the tracker loop
+const WS_SLOT_BUFFER_SIZE: usize = 256;
+
+/// Websocket resubscribe backoff bounds; the tracker polls in between
+const WS_RECONNECT_MIN_DELAY: Duration = Duration::from_secs(1);
+const WS_RECONNECT_MAX_DELAY: Duration = Duration::from_secs(30);
+
+/// Finalized slots timed between commitment latency reports
+const LATENCY_REPORT_INTERVAL: u64 = 1_000;
+
+/// Saved slots written per database transaction
+const SAVE_BATCH_SIZE: usize = 32;
+
+/// Longest a partial batch or a finalized upgrade waits before being written
+const SAVE_FLUSH_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Blocks fetched for saving per tick, so a backlog doesn't stall the display
+const MAX_SAVE_FETCHES_PER_TICK: usize = 8;
+
+/// Observed slots kept waiting for their block before the oldest are dropped
+const MAX_PENDING_SAVES: usize = 1_024;
+
+const VOTE_PROGRAM_ID: Pubkey = Pubkey::from_str_const("Vote111111111111111111111111111111111111111");
+
+/// Whether an RPC error means the slot was skipped (no block was produced)
+pub fn is_skipped_slot_error(error: &ClientError) -> bool {
+    matches!(
+        error.kind(),
+        ClientErrorKind::RpcError(RpcError::RpcResponseError { code, .. })
+            if *code == JSON_RPC_SERVER_ERROR_SLOT_SKIPPED
+                || *code == JSON_RPC_SERVER_ERROR_LONG_TERM_STORAGE_SLOT_SKIPPED
+    )
+}
+
+/// Whether the RPC node answered with an error, e.g. a slot outside the
+/// leader schedule, rather than the request failing to reach it
+pub fn is_rpc_response_error(error: &ClientError) -> bool {
+    matches!(error.kind(), ClientErrorKind::RpcError(RpcError::RpcResponseError { .. }))
+}
+
+/// Whether an RPC error means the block isn't available at the requested commitment yet
+fn is_block_not_available_error(error: &ClientError) -> bool {
+    matches!(
+        error.kind(),
+        ClientErrorKind::RpcError(RpcError::RpcResponseError { code, .. })
+            if *code == JSON_RPC_SERVER_ERROR_BLOCK_NOT_AVAILABLE
+    )
+}
+
+/// Where `SlotTracker` learns about new slots
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SlotSource {
+    /// Poll `getSlot` every `--interval` ms
+    Poll,
+    /// Push updates from `slotSubscribe` over the websocket, polling only while it is down
+    Ws,
+    /// Geyser `slots` + `blocks_meta` over Yellowstone gRPC, without JSON-RPC polling
+    Yellowstone,
+}
+
+/// What the tracker loop reacts to next
+enum FeedUpdate {
+    /// A new tip slot
+    Slot(u64),
+    /// A block pushed by the source, so it doesn't have to be fetched, with
+    /// its parent's blockhash for fork checks
+    Block(BlockData, String),
+}
+
+/// Next-slot source for the tracker loop
+enum SlotFeed {
+    Poll(Interval),
+    Subscription(mpsc::Receiver<FeedUpdate>),
+}
+
+impl SlotFeed {
+    async fn next(&mut self, client: &RpcClient) -> Result<FeedUpdate> {
+        match self {
+            SlotFeed::Poll(interval) => {
+                interval.tick().await;
+                Ok(FeedUpdate::Slot(client.get_slot()?))
+            }
+            SlotFeed::Subscription(updates) => updates.recv().await
+                .ok_or_else(|| anyhow::anyhow!("Slot subscription closed")),
+        }
+    }
+}
+
+/// Forward slots from `slotSubscribe` (or `rootSubscribe` when `finalized_only`)
+/// to `updates`, timing every transition in `lifecycle` off the same socket.
+/// Resubscribes with backoff after a disconnect and polls `getSlot` at
+/// `poll_interval` while the socket is unavailable.
+async fn run_slot_subscription(
+    ws_url: String,
+    rpc_url: String,
+    finalized_only: bool,
+    poll_interval: Duration,
+    lifecycle: Arc<RwLock<SlotLifecycle>>,
+    database: Option<Database>,
+    updates: mpsc::Sender<FeedUpdate>,
+) {
+    let commitment = if finalized_only { CommitmentConfig::finalized() } else { CommitmentConfig::processed() };
+    let client = NonblockingRpcClient::new_with_commitment(rpc_url.clone(), commitment);
+    let mut backoff = WS_RECONNECT_MIN_DELAY;
+
+    loop {
+        match forward_subscription(&ws_url, &rpc_url, finalized_only, &lifecycle, database.as_ref(), &updates, &mut backoff).await {
+            Ok(()) => return, // Tracker stopped
+            Err(e) => warn!("{} {} | Slot subscription unavailable: {}, polling for {}s",
+                "⚠️".bright_yellow(),
+                "SLOT_WS".bright_yellow(),
+                e,
+                backoff.as_secs()
+            ),
+        }
+
+        // Poll until it's time to retry the socket
+        let retry_at = tokio::time::Instant::now() + backoff;
+        let mut ticker = interval(poll_interval);
+        while tokio::time::Instant::now() < retry_at {
+            ticker.tick().await;
+            match client.get_slot().await {
+                Ok(slot) => {
+                    if updates.send(FeedUpdate::Slot(slot)).await.is_err() {
+                        return;
+                    }
+                }
+                Err(e) => debug!("Fallback slot poll failed: {}", e),
+            }
+        }
+        backoff = (backoff * 2).min(WS_RECONNECT_MAX_DELAY);
+    }
+}
+
+/// Subscribe once and forward until the socket drops (`Err`) or the tracker
+/// goes away (`Ok`). `backoff` is reset once the subscription delivers.
+async fn forward_subscription(
+    ws_url: &str,
+    rpc_url: &str,
+    finalized_only: bool,
+    lifecycle: &RwLock<SlotLifecycle>,
+    database: Option<&Database>,
+    updates: &mpsc::Sender<FeedUpdate>,
+    backoff: &mut Duration,
+) -> Result<()> {
+    let pubsub = PubsubClient::new(ws_url).await?;
+    let mut notifications = SlotNotifications::subscribe(&pubsub, rpc_url.to_string()).await?;
+    let tip_commitment = if finalized_only { CommitmentLevel::Finalized } else { CommitmentLevel::Processed };
+
+    info!("{} {} | Subscribed to {} over {}",
+        "🔌".bright_green(),
+        "SLOT_WS".bright_green(),
+        if finalized_only { "rootSubscribe" } else { "slotSubscribe" },
+        ws_url.bright_cyan()
+    );
+
+    loop {
+        let (slot, commitment) = notifications.next().await?;
+        *backoff = WS_RECONNECT_MIN_DELAY;
+        slot_lifecycle::record(lifecycle, database, slot, commitment).await;
+
+        if commitment == tip_commitment && updates.send(FeedUpdate::Slot(slot)).await.is_err() {
+            return Ok(());
+        }
+    }
+}
+
+/// Forward Geyser slot status transitions and block metadata from Yellowstone
+/// to `updates`, timing every slot in `lifecycle` from its first shred.
+/// Reconnects after a fixed delay when the stream drops.
+async fn run_geyser_slots(
+    endpoint: String,
+    auth_token: String,
+    finalized_only: bool,
+    lifecycle: Arc<RwLock<SlotLifecycle>>,
+    database: Option<Database>,
+    updates: mpsc::Sender<FeedUpdate>,
+) {
+    loop {
+        match forward_geyser_slots(&endpoint, &auth_token, finalized_only, &lifecycle, database.as_ref(), &updates).await {
+            Ok(()) => return, // Tracker stopped
+            Err(e) => warn!("{} {} | Geyser slot stream ended: {}, reconnecting in {}s",
+                "⚠️".bright_yellow(),
+                "SLOT_GEYSER".bright_yellow(),
+                e,
+                WS_RECONNECT_MIN_DELAY.as_secs()
+            ),
+        }
+        tokio::time::sleep(WS_RECONNECT_MIN_DELAY).await;
+    }
+}
+
+async fn forward_geyser_slots(
+    endpoint: &str,
+    auth_token: &str,
+    finalized_only: bool,
+    lifecycle: &RwLock<SlotLifecycle>,
+    database: Option<&Database>,
+    updates: &mpsc::Sender<FeedUpdate>,
+) -> Result<()> {
+    let (block_commitment, commitment_label) = if finalized_only {
+        (geyser::CommitmentLevel::Finalized, "finalized")
+    } else {
+        (geyser::CommitmentLevel::Confirmed, "confirmed")
+    };
+    let request = geyser::SubscribeRequest {
+        slots: HashMap::from([("slot_tracker".to_string(), geyser::SubscribeRequestFilterSlots {
+            // Every status transition, not only those at the request commitment
+            filter_by_commitment: Some(false),
+            interslot_updates: Some(true),
+        })]),
+        blocks_meta: HashMap::from([("slot_tracker".to_string(), geyser::SubscribeRequestFilterBlocksMeta {})]),
+        commitment: Some(block_commitment as i32),
+        ..Default::default()
+    };
+
+    let mut client = yellowstone_monitor::connect_client(endpoint, auth_token).await?;
+    let (mut subscribe_tx, mut stream) = client.subscribe_with_request(Some(request)).await?;
+
+    info!("{} {} | Subscribed to slots and blocks_meta on {}",
+        "🔌".bright_green(),
+        "SLOT_GEYSER".bright_green(),
+        endpoint.bright_cyan()
+    );
+
+    let mut ping_id = 0;
+    while let Some(message) = stream.next().await {
+        let update = match message?.update_oneof {
+            Some(update) => update,
+            None => continue,
+        };
+
+        match update {
+            geyser::subscribe_update::UpdateOneof::Slot(slot_update) => {
+                let slot = slot_update.slot;
+                let status = geyser::SlotStatus::try_from(slot_update.status).ok();
+                let commitment = match status {
+                    Some(geyser::SlotStatus::SlotFirstShredReceived)
+                    | Some(geyser::SlotStatus::SlotCreatedBank)
+                    | Some(geyser::SlotStatus::SlotProcessed) => CommitmentLevel::Processed,
+                    Some(geyser::SlotStatus::SlotConfirmed) => CommitmentLevel::Confirmed,
+                    Some(geyser::SlotStatus::SlotFinalized) => CommitmentLevel::Finalized,
+                    Some(geyser::SlotStatus::SlotDead) => {
+                        debug!("Slot {} is dead: {}", slot, slot_update.dead_error.unwrap_or_default());
+                        continue;
+                    }
+                    _ => continue,
+                };
+
+                slot_lifecycle::record(lifecycle, database, slot, commitment).await;
+
+                let is_tip = if finalized_only {
+                    status == Some(geyser::SlotStatus::SlotFinalized)
+                } else {
+                    status == Some(geyser::SlotStatus::SlotProcessed)
+                };
+                if is_tip && updates.send(FeedUpdate::Slot(slot)).await.is_err() {
+                    return Ok(());
+                }
+            }
+            geyser::subscribe_update::UpdateOneof::BlockMeta(meta) => {
+                let parent_blockhash = meta.parent_blockhash.clone();
+                let block = block_from_geyser_meta(meta, commitment_label);
+                if updates.send(FeedUpdate::Block(block, parent_blockhash)).await.is_err() {
+                    return Ok(());
+                }
+            }
+            geyser::subscribe_update::UpdateOneof::Ping(_) => {
+                // Answer pings so load balancers keep the stream open
+                ping_id += 1;
+                subscribe_tx.send(geyser::SubscribeRequest {
+                    ping: Some(geyser::SubscribeRequestPing { id: ping_id }),
+                    ..Default::default()
+                }).await?;
+            }
+            _ => {}
+        }
+    }
+
+    Err(anyhow::anyhow!("stream closed"))
+}
+
+/// Signature, fee, status and instructions of a binary-encoded block transaction
+pub fn tracked_transaction(tx: &EncodedTransactionWithStatusMeta) -> Option<TrackedTransaction> {
+    let decoded = tx.transaction.decode()?;
+    let signature = decoded.signatures.first()?.to_string();
+    let meta = tx.meta.as_ref();
+    let instructions = transaction_decoder::decode_binary_instructions(&decoded, meta);
+
+    Some(TrackedTransaction {
+        signature,
+        fee: meta.map_or(0, |meta| meta.fee),
+        status: if meta.is_some_and(|meta| meta.err.is_none()) { "success" } else { "failed" },
+        program_ids: transaction_decoder::program_ids(&instructions),
+        instructions,
+    })
+}
+
+/// `BlockData` from Geyser block metadata. Metadata carries no transactions,
+/// so size, fees and vote counts stay zero.
+fn block_from_geyser_meta(meta: geyser::SubscribeUpdateBlockMeta, commitment_level: &str) -> BlockData {
+    let rewards = meta.rewards.map(|rewards| rewards.rewards).unwrap_or_default();
+    let leader_pubkey = rewards.iter()
+        .find(|reward| reward.reward_type == geyser::RewardType::Fee as i32)
+        .map(|reward| reward.pubkey.clone())
+        .unwrap_or_default();
+    let block_time = meta.block_time.map(|time| time.timestamp);
+
+    BlockData {
+        slot: meta.slot,
+        blockhash: meta.blockhash,
+        transaction_count: meta.executed_transaction_count,
+        block_size_mb: 0.0,
+        parent_slot: meta.parent_slot,
+        timestamp: block_time.unwrap_or_else(|| Utc::now().timestamp()),
+        block_time,
+        block_height: meta.block_height.map(|height| height.block_height),
+        leader_pubkey,
+        confirmation_time_ms: None,
+        finalization_time_ms: None,
+        total_fees: 0,
+        total_rewards: rewards.iter().map(|reward| reward.lamports).sum(),
+        total_volume: 0,
+        vote_count: 0,
+        missed_slots: meta.slot.saturating_sub(meta.parent_slot).saturating_sub(1),
+        reorg_depth: None,
+        block_version: 0,
+        commitment_level: commitment_level.to_string(),
+    }
+}
+
 pub struct SlotTracker {
     client: RpcClient,
+    /// Loads leader schedules without blocking the runtime
+    schedule_client: NonblockingRpcClient,
     track_leaders: bool,
     finalized_only: bool,
     update_interval: Duration,
+    source: SlotSource,
+    ws_url: String,
+    yellowstone: Option<(String, String)>,
+    timings_database: Option<Database>,
     last_slot: Option<u64>,
+    last_confirmed_slot: Option<u64>,
     last_finalized_slot: Option<u64>,
     slot_leaders: HashMap<u64, String>,
+    leader_schedule: LeaderScheduleCache,
+    database: Option<Database>,
+    last_classified_slot: Option<u64>,
+    skip_rates: SkipRateWindow,
+    total_slots_classified: u64,
+    fork_tracker: ForkTracker,
+    webhook_base_url: Option<String>,
+    pending_reorg_depth: Option<u64>,
+    lifecycle: Arc<RwLock<SlotLifecycle>>,
+    last_latency_report: u64,
+    save: bool,
+    save_transactions: bool,
+    /// Observed slots whose block has not been fetched yet
+    pending_saves: BTreeSet<u64>,
+    save_buffer: Vec<TrackedSlot>,
+    /// Saved slots still waiting for finalization
+    unfinalized_saves: BTreeSet<u64>,
+    last_save_flush: Instant,
 
     // Performance tracking
     total_slots_processed: u64,
@@ -99,49 +560,537 @@ impl SlotTracker {
         update_interval_ms: u64,
     ) -> Self {
         Self {
+            schedule_client: NonblockingRpcClient::new_with_commitment(client.url(), client.commitment()),
             client,
             track_leaders,
             finalized_only,
             update_interval: Duration::from_millis(update_interval_ms),
+            source: SlotSource::Poll,
+            ws_url: String::new(),
+            yellowstone: None,
+            timings_database: None,
             last_slot: None,
+            last_confirmed_slot: None,
             last_finalized_slot: None,
             slot_leaders: HashMap::new(),
+            leader_schedule: LeaderScheduleCache::new(),
+            database: None,
+            last_classified_slot: None,
+            skip_rates: SkipRateWindow::new(SKIP_RATE_WINDOW_SLOTS),
+            total_slots_classified: 0,
+            fork_tracker: ForkTracker::new(),
+            webhook_base_url: None,
+            pending_reorg_depth: None,
+            lifecycle: Arc::new(RwLock::new(SlotLifecycle::new())),
+            last_latency_report: 0,
+            save: false,
+            save_transactions: false,
+            pending_saves: BTreeSet::new(),
+            save_buffer: Vec::new(),
+            unfinalized_saves: BTreeSet::new(),
+            last_save_flush: Instant::now(),
 
             // Initialize performance tracking
             total_slots_processed: 0,
         }
     }
 
+    /// Persist resolved slot leaders to `database`
+    pub fn with_database(mut self, database: Database) -> Self {
+        self.database = Some(database);
+        self
+    }
+
+    /// Take new slots from `source`; `ws_url` is used by the websocket source
+    pub fn with_source(mut self, source: SlotSource, ws_url: String) -> Self {
+        self.source = source;
+        self.ws_url = ws_url;
+        self
+    }
+
+    /// Yellowstone endpoint and token for the Geyser source
+    pub fn with_yellowstone(mut self, endpoint: String, auth_token: String) -> Self {
+        self.yellowstone = Some((endpoint, auth_token));
+        self
+    }
+
+    /// Persist the slot timings the ws and Yellowstone sources record
+    pub fn with_timings_database(mut self, database: Option<Database>) -> Self {
+        self.timings_database = database;
+        self
+    }
+
+    /// Post reorg events to webhooks under `base_url`
+    pub fn with_webhook_url(mut self, base_url: Option<String>) -> Self {
+        self.webhook_base_url = base_url;
+        self
+    }
+
+    /// Save every observed slot and its leader to the attached database,
+    /// with its transactions when `transactions` is set
+    pub fn with_save(mut self, transactions: bool) -> Self {
+        self.save = true;
+        self.save_transactions = transactions;
+        self
+    }
+
+    /// Commitment timings shared with the slot lifecycle monitor
+    pub fn lifecycle(&self) -> Arc<RwLock<SlotLifecycle>> {
+        self.lifecycle.clone()
+    }
+
+    /// Resolve the scheduled leader of `slot`, persisting it when tracking leaders
+    async fn resolve_leader(&mut self, slot: u64) -> Option<String> {
+        let leader = match self.leader_schedule.leader_for(&self.schedule_client, slot).await {
+            Ok(leader) => leader?,
+            Err(e) => {
+                debug!("Failed to load leader schedule for slot {}: {}", slot, e);
+                return None;
+            }
+        };
+
+        if let (true, Some(database)) = (self.track_leaders, &self.database) {
+            if let Err(e) = database.insert_slot_leader(slot, &leader, None).await {
+                warn!("Failed to persist leader for slot {}: {}", slot, e);
+            }
+        }
+
+        Some(leader)
+    }
+
+    /// Classify every confirmed slot since the last call as produced or
+    /// skipped, attributing each to its scheduled leader. Returns the skipped
+    /// slots with their leaders.
+    async fn classify_slots(&mut self) -> Result<Vec<(u64, String)>> {
+        let confirmed_tip = self.client.get_slot_with_commitment(CommitmentConfig::confirmed())?;
+
+        let Some(last_classified) = self.last_classified_slot else {
+            self.last_classified_slot = Some(confirmed_tip);
+            return Ok(Vec::new());
+        };
+        if confirmed_tip <= last_classified {
+            return Ok(Vec::new());
+        }
+
+        let start = last_classified + 1;
+        let end = confirmed_tip.min(start + MAX_CLASSIFY_BATCH - 1);
+        let produced: HashSet<u64> = self.client
+            .get_blocks_with_commitment(start, Some(end), CommitmentConfig::confirmed())?
+            .into_iter()
+            .collect();
+
+        let mut skipped_slots = Vec::new();
+        for slot in start..=end {
+            self.record_production(slot, !produced.contains(&slot), &mut skipped_slots).await;
+        }
+
+        self.last_classified_slot = Some(end);
+        Ok(skipped_slots)
+    }
+
+    /// Classify the slots a streamed block accounts for, without RPC: those
+    /// between its parent and itself were skipped, its own slot was produced
+    async fn classify_streamed_block(&mut self, slot: u64, parent_slot: u64) -> Vec<(u64, String)> {
+        let mut skipped_slots = Vec::new();
+        let first_unclassified = self.last_classified_slot.map_or(parent_slot + 1, |last| last.max(parent_slot) + 1);
+        if slot < first_unclassified {
+            return skipped_slots;
+        }
+
+        let start = first_unclassified.max(slot.saturating_sub(MAX_CLASSIFY_BATCH - 1));
+        for skipped in start..slot {
+            self.record_production(skipped, true, &mut skipped_slots).await;
+        }
+        self.record_production(slot, false, &mut skipped_slots).await;
+
+        self.last_classified_slot = Some(slot);
+        skipped_slots
+    }
+
+    /// Record whether `slot` was produced, charging it to its scheduled leader
+    async fn record_production(&mut self, slot: u64, skipped: bool, skipped_slots: &mut Vec<(u64, String)>) {
+        let leader = self.resolve_leader(slot).await.unwrap_or_else(|| "unknown".to_string());
+
+        if let Some(database) = &self.database {
+            if let Err(e) = database.record_slot_production(slot, &leader, skipped).await {
+                warn!("Failed to record production of slot {}: {}", slot, e);
+            }
+        }
+
+        if skipped {
+            skipped_slots.push((slot, leader.clone()));
+        } else if self.save {
+            self.queue_save(slot);
+        }
+        self.skip_rates.record(leader, skipped);
+        self.total_slots_classified += 1;
+    }
+
+    /// Print newly skipped slots, and the skip rates whenever another
+    /// reporting interval's worth of slots has been classified
+    fn report_classification(&self, skipped_slots: &[(u64, String)], classified_before: u64) {
+        for (slot, leader) in skipped_slots {
+            self.print_skipped_slot(*slot, leader);
+        }
+        if self.total_slots_classified / SKIP_RATE_REPORT_INTERVAL > classified_before / SKIP_RATE_REPORT_INTERVAL {
+            self.print_skip_rates();
+        }
+    }
+
+    /// Take skips and forks from a block pushed by the Yellowstone source
+    /// instead of polling getBlocks and getBlock
+    async fn observe_streamed_block(&mut self, block: &BlockData, parent_blockhash: &str) {
+        if let Some(event) = self.fork_tracker.observe_streamed_block(block.slot, block.blockhash.clone(), block.parent_slot, parent_blockhash) {
+            self.print_reorg(&event);
+            reorg::apply_reorg(&event, self.database.as_ref(), self.webhook_base_url.as_deref()).await;
+            self.pending_reorg_depth = Some(event.depth);
+        }
+
+        let classified_before = self.total_slots_classified;
+        let skipped_slots = self.classify_streamed_block(block.slot, block.parent_slot).await;
+        self.report_classification(&skipped_slots, classified_before);
+
+        if self.save {
+            self.save_pending_slots().await;
+        }
+    }
+
+    /// Per-validator skip rates over the rolling window, worst first
+    pub fn skip_rates(&self) -> Vec<ValidatorSkipRate> {
+        self.skip_rates.rates()
+    }
+
+    fn print_skipped_slot(&self, slot: u64, leader: &str) {
+        let terminal_width = Self::get_terminal_width();
+        println!("{}", "─".repeat(terminal_width).truecolor(255, 85, 85)); // Red separator
+        println!("{}", "MISSED LEADER SLOT".truecolor(255, 85, 85).bold()); // Red title
+        println!("Slot: {} | Leader: {}",
+            slot.to_string().truecolor(248, 248, 242).bold(),
+            leader.truecolor(255, 184, 108).bold()
+        );
+        println!("{}", "─".repeat(terminal_width).truecolor(255, 85, 85)); // Red separator
+    }
+
+    fn print_reorg(&self, event: &ReorgEvent) {
+        let terminal_width = Self::get_terminal_width();
+        println!("{}", "─".repeat(terminal_width).truecolor(255, 85, 85)); // Red separator
+        println!("{}", "REORG DETECTED".truecolor(255, 85, 85).bold()); // Red title
+        println!("Fork Slot: {} | Depth: {} | Confirmed At: {}",
+            event.fork_slot.to_string().truecolor(248, 248, 242).bold(),
+            event.depth.to_string().truecolor(255, 85, 85).bold(),
+            event.slot.to_string().truecolor(139, 147, 164).bold()
+        );
+        println!("Orphaned: {}", event.orphaned_slots.iter()
+            .map(|slot| slot.to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+            .truecolor(255, 184, 108)
+        );
+        println!("{}", "─".repeat(terminal_width).truecolor(255, 85, 85)); // Red separator
+    }
+
+    async fn print_commitment_latencies(&self) {
+        let (confirmation, finalization) = {
+            let lifecycle = self.lifecycle.read().await;
+            (lifecycle.confirmation_percentiles(), lifecycle.finalization_percentiles())
+        };
+
+        let terminal_width = Self::get_terminal_width();
+        println!("{}", "─".repeat(terminal_width).truecolor(139, 233, 253)); // Blue separator
+        println!("{}", "COMMITMENT LATENCY".truecolor(139, 233, 253).bold()); // Blue title
+        for (label, latency) in [("Confirmation", confirmation), ("Finalization", finalization)] {
+            println!("{}: p50 {}ms | p95 {}ms | p99 {}ms ({} slots)",
+                label.truecolor(248, 248, 242).bold(),
+                latency.p50.to_string().truecolor(80, 250, 123).bold(),
+                latency.p95.to_string().truecolor(255, 184, 108).bold(),
+                latency.p99.to_string().truecolor(255, 85, 85).bold(),
+                latency.samples
+            );
+        }
+        println!("{}", "─".repeat(terminal_width).truecolor(139, 233, 253)); // Blue separator
+    }
+
+    fn print_skip_rates(&self) {
+        let terminal_width = Self::get_terminal_width();
+        println!("{}", "─".repeat(terminal_width).truecolor(255, 184, 108)); // Orange separator
+        println!("{}", format!("SKIP RATES (last {} slots)", self.skip_rates.slots.len()).truecolor(255, 184, 108).bold());
+        for rate in self.skip_rates().iter().filter(|rate| rate.skipped_slots > 0).take(5) {
+            println!("Leader: {} | Skipped: {}/{} | Rate: {:.1}%",
+                rate.leader_pubkey.truecolor(139, 233, 253).bold(),
+                rate.skipped_slots.to_string().truecolor(255, 85, 85).bold(),
+                rate.assigned_slots.to_string().truecolor(248, 248, 242),
+                rate.skip_rate() * 100.0
+            );
+        }
+        println!("{}", "─".repeat(terminal_width).truecolor(255, 184, 108)); // Orange separator
+    }
+
+    /// Queue a produced slot for saving once its block is confirmed
+    fn queue_save(&mut self, slot: u64) {
+        self.pending_saves.insert(slot);
+        while self.pending_saves.len() > MAX_PENDING_SAVES {
+            if let Some(dropped) = self.pending_saves.pop_first() {
+                debug!("Save backlog full, dropping slot {}", dropped);
+            }
+        }
+    }
+
+    /// Highest slot known to be finalized
+    async fn finalized_tip(&self) -> u64 {
+        let tip = self.lifecycle.read().await.finalized_tip();
+        // Polled slots already carry the client's commitment
+        if self.source == SlotSource::Poll && self.client.commitment().is_finalized() {
+            return tip.max(self.last_slot.unwrap_or(0));
+        }
+        tip
+    }
+
+    /// Fetch queued slots into the save buffer and write the buffer, with any
+    /// finalized upgrades, once it is full or has waited long enough
+    async fn save_pending_slots(&mut self) {
+        let finalized_tip = self.finalized_tip().await;
+        let slots: Vec<u64> = self.pending_saves.iter().take(MAX_SAVE_FETCHES_PER_TICK).copied().collect();
+
+        for slot in slots {
+            match self.fetch_tracked_slot(slot, finalized_tip).await {
+                Ok(SaveFetch::Ready(tracked)) => {
+                    if !tracked.finalized {
+                        self.unfinalized_saves.insert(slot);
+                    }
+                    self.save_buffer.push(tracked);
+                }
+                Ok(SaveFetch::Skipped) => {}
+                // Later slots won't be confirmed either
+                Ok(SaveFetch::Pending) => break,
+                Err(e) => warn!("Failed to fetch slot {} for saving: {}", slot, e),
+            }
+            self.pending_saves.remove(&slot);
+        }
+
+        let upgrades = self.unfinalized_saves.range(..=finalized_tip).next().is_some();
+        let due = self.last_save_flush.elapsed() >= SAVE_FLUSH_INTERVAL;
+        if self.save_buffer.len() >= SAVE_BATCH_SIZE || (due && (upgrades || !self.save_buffer.is_empty())) {
+            self.flush_saves(finalized_tip).await;
+        }
+    }
+
+    async fn flush_saves(&mut self, finalized_tip: u64) {
+        self.last_save_flush = Instant::now();
+        let Some(database) = &self.database else {
+            return;
+        };
+
+        let finalized_slots: Vec<u64> = self.unfinalized_saves.range(..=finalized_tip).copied().collect();
+        match database.save_tracked_slots(&self.save_buffer, &finalized_slots).await {
+            Ok(()) => {
+                debug!("Saved {} slots, finalized {}", self.save_buffer.len(), finalized_slots.len());
+                self.unfinalized_saves.retain(|slot| *slot > finalized_tip);
+            }
+            Err(e) => warn!("{} {} | Failed to save {} slots: {}",
+                "⚠️".bright_yellow(),
+                "SAVE".bright_yellow(),
+                self.save_buffer.len(),
+                e
+            ),
+        }
+        self.save_buffer.clear();
+
+        while self.unfinalized_saves.len() > MAX_PENDING_SAVES {
+            self.unfinalized_saves.pop_first();
+        }
+    }
+
+    /// Fetch the confirmed block of `slot` with what `--save` stores for it
+    async fn fetch_tracked_slot(&mut self, slot: u64, finalized_tip: u64) -> Result<SaveFetch> {
+        let transaction_details = if self.save_transactions { TransactionDetails::Full } else { TransactionDetails::None };
+        let block = match self.client.get_block_with_config(slot, RpcBlockConfig {
+            encoding: Some(UiTransactionEncoding::Base64),
+            transaction_details: Some(transaction_details),
+            rewards: Some(false),
+            commitment: Some(CommitmentConfig::confirmed()),
+            max_supported_transaction_version: Some(0),
+        }) {
+            Ok(block) => block,
+            Err(e) if is_skipped_slot_error(&e) => return Ok(SaveFetch::Skipped),
+            Err(e) if is_block_not_available_error(&e) => return Ok(SaveFetch::Pending),
+            Err(e) => return Err(e.into()),
+        };
+
+        let transactions = block.transactions.unwrap_or_default().iter()
+            .filter_map(tracked_transaction)
+            .collect();
+
+        let leader = self.leader_schedule.leader_for(&self.schedule_client, slot).await.unwrap_or_else(|e| {
+            debug!("Failed to load leader schedule for slot {}: {}", slot, e);
+            None
+        });
+
+        Ok(SaveFetch::Ready(TrackedSlot {
+            slot,
+            blockhash: block.blockhash,
+            parent_slot: block.parent_slot,
+            finalized: slot <= finalized_tip,
+            timestamp: block.block_time.and_then(|time| DateTime::from_timestamp(time, 0)).unwrap_or_else(Utc::now),
+            leader,
+            transactions,
+        }))
+    }
+
+    /// Enrich a produced block with the schedule leader, reorg depth and
+    /// commitment timings, then print it
+    async fn report_block(&mut self, mut block_data: BlockData) {
+        block_data.reorg_depth = self.pending_reorg_depth.take();
+        if let Some(timing) = self.lifecycle.read().await.timing(block_data.slot) {
+            block_data.confirmation_time_ms = timing.confirmation_ms();
+            block_data.finalization_time_ms = timing.finalization_ms();
+        }
+
+        // Blocks without a fee reward fall back to the schedule
+        if block_data.leader_pubkey.is_empty() {
+            if let Some(leader) = self.leader_schedule.leader(block_data.slot) {
+                block_data.leader_pubkey = leader.to_string();
+            }
+        }
+
+        // Enhanced block data display with separator
+        let terminal_width = Self::get_terminal_width();
+        println!("{}", "─".repeat(terminal_width).truecolor(80, 250, 123)); // Green separator
+        println!("{}", "NEW BLOCK".truecolor(80, 250, 123).bold()); // Green title
+
+        // Display block data horizontally if space available
+        if terminal_width >= 140 {
+                                                    println!("Slot: {} | Hash: {} | Txs: {} | Size: {:.2}MB",
+                block_data.slot.to_string().truecolor(248, 248, 242).bold(),
+                block_data.blockhash.chars().take(20).collect::<String>().truecolor(80, 250, 123).bold(),
+                block_data.transaction_count.to_string().truecolor(139, 233, 253).bold(),
+                block_data.block_size_mb.to_string().truecolor(255, 184, 108).bold()
+            );
+
+            println!("Leader: {} | Parent: {} | Version: {} | Commitment: {}",
+                block_data.leader_pubkey.chars().take(20).collect::<String>().truecolor(189, 147, 249).bold(),
+                block_data.parent_slot.to_string().truecolor(139, 147, 164).bold(),
+                block_data.block_version.to_string().truecolor(80, 250, 123).bold(),
+                block_data.commitment_level.truecolor(139, 233, 253).bold()
+            );
+
+            println!("Confirmation: {} | Finalization: {} | Vote Count: {} | Missed: {}",
+                block_data.confirmation_time_ms.map_or("N/A".to_string(), |ms| format!("{}ms", ms)).truecolor(80, 250, 123).bold(),
+                block_data.finalization_time_ms.map_or("N/A".to_string(), |ms| format!("{}ms", ms)).truecolor(189, 147, 249).bold(),
+                block_data.vote_count.to_string().truecolor(80, 250, 123).bold(),
+                block_data.missed_slots.to_string().truecolor(255, 184, 108).bold()
+            );
+
+            println!("Height: {} | Block Time: {} | Total Fees: {} lamports | Rewards: {} lamports",
+                block_data.block_height.map_or("N/A".to_string(), |height| height.to_string()).truecolor(139, 147, 164).bold(),
+                block_data.block_time.map_or("N/A".to_string(), |time| time.to_string()).truecolor(139, 147, 164).bold(),
+                block_data.total_fees.to_string().truecolor(255, 184, 108).bold(),
+                block_data.total_rewards.to_string().truecolor(139, 233, 253).bold()
+            );
+        } else {
+            // Compact horizontal layout for smaller terminals
+            println!("Slot: {} | Hash: {} | Txs: {} | Size: {:.2}MB",
+                block_data.slot.to_string().truecolor(248, 248, 242).bold(),
+                block_data.blockhash.chars().take(15).collect::<String>().truecolor(80, 250, 123).bold(),
+                block_data.transaction_count.to_string().truecolor(139, 233, 253).bold(),
+                block_data.block_size_mb.to_string().truecolor(255, 184, 108).bold()
+            );
+
+            println!("Leader: {} | Parent: {} | Version: {} | Commitment: {}",
+                block_data.leader_pubkey.chars().take(15).collect::<String>().truecolor(189, 147, 249).bold(),
+                block_data.parent_slot.to_string().truecolor(139, 147, 164).bold(),
+                block_data.block_version.to_string().truecolor(80, 250, 123).bold(),
+                block_data.commitment_level.chars().take(10).collect::<String>().truecolor(139, 233, 253).bold()
+            );
+
+            println!("Confirmation: {} | Finalization: {} | Vote Count: {} | Missed: {}",
+                block_data.confirmation_time_ms.map_or("N/A".to_string(), |ms| format!("{}ms", ms)).truecolor(80, 250, 123).bold(),
+                block_data.finalization_time_ms.map_or("N/A".to_string(), |ms| format!("{}ms", ms)).truecolor(189, 147, 249).bold(),
+                block_data.vote_count.to_string().truecolor(80, 250, 123).bold(),
+                block_data.missed_slots.to_string().truecolor(255, 184, 108).bold()
+            );
+
+            println!("Height: {} | Block Time: {} | Total Fees: {} lamports | Rewards: {} lamports",
+                block_data.block_height.map_or("N/A".to_string(), |height| height.to_string()).truecolor(139, 147, 164).bold(),
+                block_data.block_time.map_or("N/A".to_string(), |time| time.to_string()).truecolor(139, 147, 164).bold(),
+                block_data.total_fees.to_string().truecolor(255, 184, 108).bold(),
+                block_data.total_rewards.to_string().truecolor(139, 233, 253).bold()
+            );
+        }
+
+        // Reorg detection
+        if let Some(reorg_depth) = block_data.reorg_depth {
+            println!("Reorg Depth: {} slots", reorg_depth.to_string().truecolor(255, 85, 85).bold()); // Red
+        }
+
+        println!("Full Hash: {}", block_data.blockhash.truecolor(80, 250, 123)); // Green full hash
+        println!("{}", "─".repeat(terminal_width).truecolor(80, 250, 123)); // Green separator
+    }
+
     pub async fn start(&mut self) -> Result<()> {
 
         println!("{}", "solana-indexer stream --live".truecolor(189, 147, 249)); // Dracula purple
         println!();
 
-        let mut interval = interval(self.update_interval);
+        let mut feed = match self.source {
+            SlotSource::Poll => SlotFeed::Poll(interval(self.update_interval)),
+            SlotSource::Ws => {
+                let (tx, rx) = mpsc::channel(WS_SLOT_BUFFER_SIZE);
+                tokio::spawn(run_slot_subscription(
+                    self.ws_url.clone(),
+                    self.client.url(),
+                    self.finalized_only,
+                    self.update_interval,
+                    self.lifecycle.clone(),
+                    self.timings_database.take(),
+                    tx,
+                ));
+                SlotFeed::Subscription(rx)
+            }
+            SlotSource::Yellowstone => {
+                let (endpoint, auth_token) = self.yellowstone.clone()
+                    .ok_or_else(|| anyhow::anyhow!("The Yellowstone source needs YELLOWSTONE_ENDPOINT"))?;
+                let (tx, rx) = mpsc::channel(WS_SLOT_BUFFER_SIZE);
+                tokio::spawn(run_geyser_slots(
+                    endpoint,
+                    auth_token,
+                    self.finalized_only,
+                    self.lifecycle.clone(),
+                    self.timings_database.take(),
+                    tx,
+                ));
+                SlotFeed::Subscription(rx)
+            }
+        };
         let mut counter = 0u64;
 
         loop {
-            interval.tick().await;
+            let next_slot = match feed.next(&self.client).await {
+                Ok(FeedUpdate::Block(block_data, parent_blockhash)) => {
+                    self.observe_streamed_block(&block_data, &parent_blockhash).await;
+                    self.report_block(block_data).await;
+                    continue;
+                }
+                Ok(FeedUpdate::Slot(slot)) => Ok(slot),
+                Err(e) => Err(e),
+            };
 
             let now = Utc::now();
             let timestamp = now.format("[%Y-%m-%dT%H:%M:%S%.3fZ]").to_string();
 
-            match self.client.get_slot() {
+            match next_slot {
                 Ok(current_slot) => {
                     // Only show updates when slot changes
                     if self.last_slot.map_or(true, |last| current_slot != last) {
 
 
-                        // Slot update with leader - generate full leader address
+                        // Slot update with the scheduled leader
                         let leader_address = if self.track_leaders {
-                            // Generate a realistic-looking full leader address (no truncation)
-                            let leader_input = format!("leader_slot_{}_timestamp_{}_validator_{}", current_slot, SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs(), current_slot % 1000);
-                            bs58::encode(sha2::Sha256::digest(leader_input.as_bytes())).into_string()
+                            self.resolve_leader(current_slot).await
                         } else {
-                            // Generate a realistic-looking full leader address (no truncation)
-                            let leader_input = format!("leader_slot_{}_timestamp_{}_validator_{}", current_slot, SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs(), current_slot % 1000);
-                            bs58::encode(sha2::Sha256::digest(leader_input.as_bytes())).into_string()
+                            None
                         };
+                        let epoch = self.leader_schedule.epoch()
+                            .map_or("N/A".to_string(), |epoch| epoch.to_string());
 
                         // Enhanced slot update display with separator
                         let terminal_width = Self::get_terminal_width();
@@ -149,89 +1098,39 @@ impl SlotTracker {
                         println!("{}", "SLOT UPDATE".truecolor(241, 250, 140).bold()); // Yellow title
 
                         // Display slot data horizontally if space available
-                        if terminal_width >= 120 {
-                            println!("Slot: {} | Leader: {}",
-                                current_slot.to_string().truecolor(248, 248, 242).bold(),
-                                leader_address.truecolor(139, 233, 253).bold()
-                            );
-                        } else {
-                            println!("Slot: {}", current_slot.to_string().truecolor(248, 248, 242).bold()); // White slot
-                            println!("Leader: {}", leader_address.truecolor(139, 233, 253).bold()); // Blue leader
+                        match &leader_address {
+                            Some(leader_address) if terminal_width >= 120 => {
+                                println!("Slot: {} | Epoch: {} | Leader: {}",
+                                    current_slot.to_string().truecolor(248, 248, 242).bold(),
+                                    epoch.truecolor(139, 147, 164).bold(),
+                                    leader_address.truecolor(139, 233, 253).bold()
+                                );
+                            }
+                            Some(leader_address) => {
+                                println!("Slot: {} | Epoch: {}", current_slot.to_string().truecolor(248, 248, 242).bold(), epoch.truecolor(139, 147, 164).bold()); // White slot
+                                println!("Leader: {}", leader_address.truecolor(139, 233, 253).bold()); // Blue leader
+                            }
+                            None => {
+                                println!("Slot: {}", current_slot.to_string().truecolor(248, 248, 242).bold()); // White slot
+                            }
                         }
                         println!("{}", "─".repeat(terminal_width).truecolor(241, 250, 140)); // Yellow separator
 
-                        // Fetch real block data every few slots
-                        if current_slot % 3 == 0 {
+                        // Fetch real block data every few slots, unless the source pushes blocks
+                        if current_slot % 3 == 0 && self.source != SlotSource::Yellowstone {
                             match self.fetch_block_data(current_slot).await {
-                                Ok(block_data) => {
-                                    // Enhanced block data display with separator
+                                Ok(BlockOutcome::Skipped(slot)) => {
                                     let terminal_width = Self::get_terminal_width();
-                                    println!("{}", "─".repeat(terminal_width).truecolor(80, 250, 123)); // Green separator
-                                    println!("{}", "NEW BLOCK".truecolor(80, 250, 123).bold()); // Green title
-
-                                    // Display block data horizontally if space available
-                                    if terminal_width >= 140 {
-                                                                                println!("Slot: {} | Hash: {} | Txs: {} | Size: {:.2}MB",
-                                            current_slot.to_string().truecolor(248, 248, 242).bold(),
-                                            block_data.blockhash.chars().take(20).collect::<String>().truecolor(80, 250, 123).bold(),
-                                            block_data.transaction_count.to_string().truecolor(139, 233, 253).bold(),
-                                            block_data.block_size_mb.to_string().truecolor(255, 184, 108).bold()
-                                        );
-
-                                        println!("Leader: {} | Parent: {} | Version: {} | Commitment: {}",
-                                            block_data.leader_pubkey.chars().take(20).collect::<String>().truecolor(189, 147, 249).bold(),
-                                            block_data.parent_slot.to_string().truecolor(139, 147, 164).bold(),
-                                            block_data.block_version.to_string().truecolor(80, 250, 123).bold(),
-                                            block_data.commitment_level.truecolor(139, 233, 253).bold()
-                                        );
-
-                                        println!("Confirmation: {}ms | Finalization: {}ms | Vote Count: {} | Missed: {}",
-                                            block_data.confirmation_time_ms.to_string().truecolor(80, 250, 123).bold(),
-                                            block_data.finalization_time_ms.to_string().truecolor(189, 147, 249).bold(),
-                                            block_data.vote_count.to_string().truecolor(80, 250, 123).bold(),
-                                            block_data.missed_slots.to_string().truecolor(255, 184, 108).bold()
-                                        );
-
-                                        println!("Total Fees: {} lamports | Total Volume: {} lamports",
-                                            block_data.total_fees.to_string().truecolor(255, 184, 108).bold(),
-                                            block_data.total_volume.to_string().truecolor(139, 233, 253).bold()
-                                        );
-                                    } else {
-                                        // Compact horizontal layout for smaller terminals
-                                        println!("Slot: {} | Hash: {} | Txs: {} | Size: {:.2}MB",
-                                            current_slot.to_string().truecolor(248, 248, 242).bold(),
-                                            block_data.blockhash.chars().take(15).collect::<String>().truecolor(80, 250, 123).bold(),
-                                            block_data.transaction_count.to_string().truecolor(139, 233, 253).bold(),
-                                            block_data.block_size_mb.to_string().truecolor(255, 184, 108).bold()
-                                        );
-
-                                        println!("Leader: {} | Parent: {} | Version: {} | Commitment: {}",
-                                            block_data.leader_pubkey.chars().take(15).collect::<String>().truecolor(189, 147, 249).bold(),
-                                            block_data.parent_slot.to_string().truecolor(139, 147, 164).bold(),
-                                            block_data.block_version.to_string().truecolor(80, 250, 123).bold(),
-                                            block_data.commitment_level.chars().take(10).collect::<String>().truecolor(139, 233, 253).bold()
-                                        );
-
-                                        println!("Confirmation: {}ms | Finalization: {}ms | Vote Count: {} | Missed: {}",
-                                            block_data.confirmation_time_ms.to_string().truecolor(80, 250, 123).bold(),
-                                            block_data.finalization_time_ms.to_string().truecolor(189, 147, 249).bold(),
-                                            block_data.vote_count.to_string().truecolor(80, 250, 123).bold(),
-                                            block_data.missed_slots.to_string().truecolor(255, 184, 108).bold()
-                                        );
-
-                                        println!("Total Fees: {} lamports | Total Volume: {} lamports",
-                                            block_data.total_fees.to_string().truecolor(255, 184, 108).bold(),
-                                            block_data.total_volume.to_string().truecolor(139, 233, 253).bold()
-                                        );
-                                    }
-
-                                    // Reorg detection
-                                    if let Some(reorg_depth) = block_data.reorg_depth {
-                                        println!("Reorg Depth: {} slots", reorg_depth.to_string().truecolor(255, 85, 85).bold()); // Red
-                                    }
-
-                                    println!("Full Hash: {}", block_data.blockhash.truecolor(80, 250, 123)); // Green full hash
-                                    println!("{}", "─".repeat(terminal_width).truecolor(80, 250, 123)); // Green separator
+                                    println!("{}", "─".repeat(terminal_width).truecolor(255, 184, 108)); // Orange separator
+                                    println!("{}", "SKIPPED SLOT".truecolor(255, 184, 108).bold()); // Orange title
+                                    println!("Slot: {} | No block was produced", slot.to_string().truecolor(248, 248, 242).bold());
+                                    println!("{}", "─".repeat(terminal_width).truecolor(255, 184, 108)); // Orange separator
+                                }
+                                Ok(BlockOutcome::Pending(slot)) => {
+                                    debug!("Block {} not confirmed yet, skipping its display", slot);
+                                }
+                                Ok(BlockOutcome::Produced(block_data)) => {
+                                    self.report_block(block_data).await;
                                 }
                                 Err(e) => {
                                     // Enhanced block error display with separator
@@ -247,11 +1146,45 @@ impl SlotTracker {
                         self.last_slot = Some(current_slot);
                         self.total_slots_processed += 1;
 
+                        if self.source == SlotSource::Yellowstone {
+                            // Skips and forks come from the streamed blocks_meta
+                            if !self.finalized_only {
+                                self.fork_tracker.observe_processed(current_slot);
+                            }
+                        } else {
+                            // Walk every confirmed slot since the last tick, not just the polled tip
+                            let classified_before = self.total_slots_classified;
+                            match self.classify_slots().await {
+                                Ok(skipped_slots) => self.report_classification(&skipped_slots, classified_before),
+                                Err(e) => debug!("Failed to classify slots: {}", e),
+                            }
+
+                            // Classification queued every produced slot it walked
+                            if self.save {
+                                self.save_pending_slots().await;
+                            }
 
+                            // Compare newly confirmed blocks against the chain seen so far
+                            match self.fork_tracker.check(&self.client) {
+                                Ok(Some(event)) => {
+                                    self.print_reorg(&event);
+                                    reorg::apply_reorg(&event, self.database.as_ref(), self.webhook_base_url.as_deref()).await;
+                                    self.pending_reorg_depth = Some(event.depth);
+                                }
+                                Ok(None) => {}
+                                Err(e) => debug!("Fork check failed: {}", e),
+                            }
+                        }
+
+                        let finalized_count = self.lifecycle.read().await.finalized_count();
+                        if finalized_count >= self.last_latency_report + LATENCY_REPORT_INTERVAL {
+                            self.last_latency_report = finalized_count;
+                            self.print_commitment_latencies().await;
+                        }
                     }
 
-                    // Fetch real transaction confirmations
-                    if counter % 2 == 0 {
+                    // Fetch real transaction confirmations; the Yellowstone source doesn't poll RPC
+                    if counter % 2 == 0 && self.source != SlotSource::Yellowstone {
                         match self.fetch_recent_transactions(current_slot).await {
                             Ok(transactions) => {
                                 for tx in transactions.iter().take(2) {
@@ -357,7 +1290,7 @@ impl SlotTracker {
                     }
 
                     // Fetch real account updates
-                    if counter % 4 == 1 {
+                    if counter % 4 == 1 && self.source != SlotSource::Yellowstone {
                         match self.fetch_recent_accounts(current_slot).await {
                             Ok(accounts) => {
                                 for account in accounts.iter().take(1) {
@@ -414,6 +1347,38 @@ impl SlotTracker {
         self.last_slot.unwrap_or(0)
     }
 
+    /// Record a slot observed by an external poller (for gRPC server)
+    pub fn observe_slot(&mut self, slot: u64) {
+        if self.last_slot.map_or(true, |last| slot > last) {
+            self.last_slot = Some(slot);
+            self.total_slots_processed += 1;
+        }
+    }
+
+    /// Get the last confirmed slot seen (for gRPC server)
+    pub fn get_confirmed_slot(&self) -> Option<u64> {
+        self.last_confirmed_slot
+    }
+
+    /// Get the last finalized slot seen (for gRPC server)
+    pub fn get_finalized_slot(&self) -> Option<u64> {
+        self.last_finalized_slot
+    }
+
+    /// Record a confirmed slot observed by an external poller (for gRPC server)
+    pub fn observe_confirmed_slot(&mut self, slot: u64) {
+        if self.last_confirmed_slot.map_or(true, |last| slot > last) {
+            self.last_confirmed_slot = Some(slot);
+        }
+    }
+
+    /// Record a finalized slot observed by an external poller (for gRPC server)
+    pub fn observe_finalized_slot(&mut self, slot: u64) {
+        if self.last_finalized_slot.map_or(true, |last| slot > last) {
+            self.last_finalized_slot = Some(slot);
+        }
+    }
+
     /// Get slot leaders for a specific slot (for gRPC server)
     pub async fn get_slot_leaders(&self, slot: u64, limit: u64) -> Result<Vec<String>> {
         match self.client.get_slot_leaders(slot, limit) {
@@ -422,61 +1387,73 @@ impl SlotTracker {
         }
     }
 
-        /// Fetch real block data from Solana RPC
-    pub async fn fetch_block_data(&self, slot: u64) -> Result<BlockData> {
-        // Try to get real block hash from Solana RPC
-        match self.client.get_latest_blockhash() {
-            Ok(blockhash) => {
-                // Generate a realistic transaction count based on slot
-                let transaction_count = if slot > 0 { (slot % 1000) + 100 } else { 100 };
-                let block_size_mb = (transaction_count * 200) as f64 / 1_000_000.0;
+    /// Fetch a slot's block from Solana RPC, reporting skipped slots as
+    /// [`BlockOutcome::Skipped`] rather than an error
+    pub async fn fetch_block_data(&self, slot: u64) -> Result<BlockOutcome> {
+        let block = match self.client.get_block_with_config(slot, RpcBlockConfig {
+            encoding: Some(UiTransactionEncoding::Base64),
+            transaction_details: Some(TransactionDetails::Full),
+            rewards: Some(true),
+            commitment: Some(CommitmentConfig::confirmed()),
+            max_supported_transaction_version: Some(0),
+        }) {
+            Ok(block) => block,
+            Err(e) if is_skipped_slot_error(&e) => return Ok(BlockOutcome::Skipped(slot)),
+            Err(e) if is_block_not_available_error(&e) => return Ok(BlockOutcome::Pending(slot)),
+            Err(e) => return Err(anyhow::anyhow!("Failed to get block {}: {}", slot, e)),
+        };
 
-                Ok(BlockData {
-                    slot,
-                    blockhash: blockhash.to_string(), // Real block hash (full length)
-                    transaction_count,
-                    block_size_mb,
-                    parent_slot: slot.saturating_sub(1),
-                    // Enhanced fields for better monitoring
-                    timestamp: SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() as i64,
-                    leader_pubkey: format!("leader_slot_{}_timestamp_{}_validator_{}", slot, SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs(), slot % 1000),
-                    confirmation_time_ms: 0, // Placeholder
-                    finalization_time_ms: 0, // Placeholder
-                    total_fees: 0, // Placeholder
-                    total_volume: 0, // Placeholder
-                    vote_count: 0, // Placeholder
-                    missed_slots: 0, // Placeholder
-                    reorg_depth: None, // Placeholder
-                    block_version: 0, // Placeholder
-                    commitment_level: "confirmed".to_string(),
-                })
-            }
-            Err(_) => {
-                // Fallback - generate a realistic hash from slot and timestamp
-                let hash_input = format!("slot_{}_timestamp_{}", slot, SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs());
-                let hash = bs58::encode(sha2::Sha256::digest(hash_input.as_bytes())).into_string();
+        let transactions = block.transactions.unwrap_or_default();
+        let mut total_fees = 0u64;
+        let mut vote_count = 0u64;
+        let mut block_bytes = 0usize;
 
-                Ok(BlockData {
-                    slot,
-                    blockhash: hash, // Generated hash (full length, not truncated)
-                    transaction_count: 0,
-                    block_size_mb: 0.0,
-                    parent_slot: slot.saturating_sub(1),
-                    // Enhanced fields for better monitoring
-                    timestamp: SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() as i64,
-                    leader_pubkey: format!("leader_slot_{}_timestamp_{}_validator_{}", slot, SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs(), slot % 1000),
-                    confirmation_time_ms: 0, // Placeholder
-                    finalization_time_ms: 0, // Placeholder
-                    total_fees: 0, // Placeholder
-                    total_volume: 0, // Placeholder
-                    vote_count: 0, // Placeholder
-                    missed_slots: 0, // Placeholder
-                    reorg_depth: None, // Placeholder
-                    block_version: 0, // Placeholder
-                    commitment_level: "confirmed".to_string(),
-                })
+        for tx in &transactions {
+            if let Some(meta) = &tx.meta {
+                total_fees += meta.fee;
+            }
+            if let EncodedTransaction::Binary(blob, TransactionBinaryEncoding::Base64) = &tx.transaction {
+                let padding = blob.bytes().rev().take_while(|b| *b == b'=').count();
+                block_bytes += blob.len() / 4 * 3 - padding;
+            }
+            let is_vote = tx.transaction.decode().is_some_and(|decoded| {
+                decoded.message.static_account_keys().contains(&VOTE_PROGRAM_ID)
+            });
+            if is_vote {
+                vote_count += 1;
             }
         }
+
+        let rewards = block.rewards.unwrap_or_default();
+        let total_rewards = rewards.iter().map(|reward| reward.lamports).sum();
+        // The leader is the validator credited with the block's fee reward
+        let leader_pubkey = rewards.iter()
+            .find(|reward| reward.reward_type == Some(RewardType::Fee))
+            .map(|reward| reward.pubkey.clone())
+            .unwrap_or_default();
+
+        Ok(BlockOutcome::Produced(BlockData {
+            slot,
+            blockhash: block.blockhash,
+            transaction_count: transactions.len() as u64,
+            block_size_mb: block_bytes as f64 / 1_000_000.0,
+            parent_slot: block.parent_slot,
+            timestamp: block.block_time.unwrap_or_else(|| Utc::now().timestamp()),
+            block_time: block.block_time,
+            block_height: block.block_height,
+            leader_pubkey,
+            confirmation_time_ms: None, // Filled from the slot lifecycle by the tracker
+            finalization_time_ms: None,
+            total_fees,
+            total_rewards,
+            total_volume: 0, // Placeholder
+            vote_count,
+            // Slots between the parent and this block were skipped
+            missed_slots: slot.saturating_sub(block.parent_slot).saturating_sub(1),
+            reorg_depth: None, // Set by the tracker when a reorg was detected
+            block_version: 0, // Placeholder
+            commitment_level: "confirmed".to_string(),
+        }))
     }
 
     /// Fetch recent transactions from Solana RPC
@@ -650,32 +1627,23 @@ impl SlotTracker {
 
     async fn update_leaders(&mut self) -> Result<()> {
         if let Some(current_slot) = self.last_slot {
-            // Use get_slot_leaders to get the leader for the current slot
-            match self.client.get_slot_leaders(current_slot, 1) {
-                Ok(leaders) => {
-                    if let Some(leader_pubkey) = leaders.first() {
-                        let leader_str = leader_pubkey.to_string();
-
-                        // Check if leader changed
-                        if let Some(previous_leader) = self.slot_leaders.get(&current_slot) {
-                            if previous_leader != &leader_str {
-                                self.print_leader_change(current_slot, &leader_str, previous_leader);
-                            }
-                        } else {
-                            self.print_new_leader(current_slot, &leader_str);
-                        }
-
-                        self.slot_leaders.insert(current_slot, leader_str);
-
-                        // Clean up old entries (keep last 100 slots)
-                        if self.slot_leaders.len() > 100 {
-                            let min_slot = current_slot.saturating_sub(100);
-                            self.slot_leaders.retain(|&slot, _| slot >= min_slot);
-                        }
+            // Look the leader up in the cached epoch schedule
+            if let Some(leader_str) = self.resolve_leader(current_slot).await {
+                // Check if leader changed
+                if let Some(previous_leader) = self.slot_leaders.get(&current_slot) {
+                    if previous_leader != &leader_str {
+                        self.print_leader_change(current_slot, &leader_str, previous_leader);
                     }
+                } else {
+                    self.print_new_leader(current_slot, &leader_str);
                 }
-                Err(e) => {
-                    debug!("Failed to get slot leader: {}", e);
+
+                self.slot_leaders.insert(current_slot, leader_str);
+
+                // Clean up old entries (keep last 100 slots)
+                if self.slot_leaders.len() > 100 {
+                    let min_slot = current_slot.saturating_sub(100);
+                    self.slot_leaders.retain(|&slot, _| slot >= min_slot);
                 }
             }
         }
@@ -748,17 +1716,71 @@ impl SlotTracker {
 
 pub async fn start_tracking(
     client: RpcClient,
+    config: &crate::config::Config,
     track_leaders: bool,
     finalized_only: bool,
     update_interval_ms: u64,
+    source: SlotSource,
+    save: bool,
+    transactions: bool,
 ) -> Result<()> {
-    let mut tracker = SlotTracker::new(client, track_leaders, finalized_only, update_interval_ms);
+    let mut tracker = SlotTracker::new(client, track_leaders, finalized_only, update_interval_ms)
+        .with_source(source, config.solana_ws_url.clone())
+        .with_webhook_url(config.get_webhook_base_url().map(str::to_string));
+
+    let timings_database = match Database::new(&config.database_config).await {
+        Ok(database) => Some(database),
+        Err(e) => {
+            warn!("Database unavailable, slot timings will not be persisted: {}", e);
+            None
+        }
+    };
+
+    match source {
+        SlotSource::Yellowstone => {
+            // Geyser reports every status transition itself
+            let (endpoint, auth_token) = config.get_yellowstone_credentials()
+                .ok_or_else(|| anyhow::anyhow!("--source yellowstone needs YELLOWSTONE_ENDPOINT"))?;
+            tracker = tracker
+                .with_yellowstone(endpoint.to_string(), auth_token.to_string())
+                .with_timings_database(timings_database);
+        }
+        // The slot subscription times commitments on its own socket
+        SlotSource::Ws => tracker = tracker.with_timings_database(timings_database),
+        SlotSource::Poll => {
+            // Commitment timings come from the websocket, independently of the poll loop
+            tokio::spawn(slot_lifecycle::run_ws_monitor(
+                config.solana_ws_url.clone(),
+                config.solana_rpc_url.clone(),
+                tracker.lifecycle(),
+                timings_database,
+            ));
+        }
+    }
+
+    // Leaders resolved from the schedule are persisted for later queries
+    if track_leaders || save {
+        match Database::new(&config.database_config).await {
+            Ok(database) => tracker = tracker.with_database(database),
+            Err(e) if save => return Err(anyhow::anyhow!("--save needs the database: {}", e)),
+            Err(e) => warn!("Database unavailable, slot leaders will not be persisted: {}", e),
+        }
+    }
+    if save {
+        tracker = tracker.with_save(transactions);
+    }
 
     info!(
-        "Configuration: {} {} {}",
+        "Configuration: {} {} {} {} {}",
         if track_leaders { "Leaders: ENABLED" } else { "Leaders: DISABLED" },
         if finalized_only { "Finalized Only: ENABLED" } else { "All Slots: ENABLED" },
-        format!("Interval: {}ms", update_interval_ms)
+        format!("Interval: {}ms", update_interval_ms),
+        format!("Source: {:?}", source),
+        match (save, transactions) {
+            (true, true) => "Save: SLOTS + TRANSACTIONS",
+            (true, false) => "Save: SLOTS",
+            (false, _) => "Save: DISABLED",
+        }
     );
 
     println!();
@@ -0,0 +1,186 @@
+use anyhow::{Context, Result};
+use axum_server::tls_rustls::RustlsConfig;
+use colored::*;
+use rcgen::{
+    BasicConstraints, CertificateParams, DnType, ExtendedKeyUsagePurpose, IsCa, KeyPair, KeyUsagePurpose,
+};
+use rustls::{
+    crypto::ring,
+    pki_types::{CertificateDer, PrivateKeyDer},
+    server::WebPkiClientVerifier,
+    RootCertStore, ServerConfig,
+};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tonic::transport::{Certificate, ClientTlsConfig, Identity, ServerTlsConfig};
+use tracing::info;
+
+/// Certificate, key and optional client CA a server listens with
+#[derive(Debug, Clone)]
+pub struct ServerTls {
+    pub cert_path: PathBuf,
+    pub key_path: PathBuf,
+    /// When set, clients must present a certificate signed by this CA (mTLS)
+    pub client_ca_path: Option<PathBuf>,
+}
+
+impl ServerTls {
+    /// Build from optional CLI paths; `None` means plaintext
+    pub fn from_paths(
+        cert_path: Option<PathBuf>,
+        key_path: Option<PathBuf>,
+        client_ca_path: Option<PathBuf>,
+    ) -> Result<Option<Self>> {
+        match (cert_path, key_path) {
+            (Some(cert_path), Some(key_path)) => Ok(Some(Self { cert_path, key_path, client_ca_path })),
+            (None, None) if client_ca_path.is_none() => Ok(None),
+            (None, None) => Err(anyhow::anyhow!("--tls-client-ca requires --tls-cert and --tls-key")),
+            _ => Err(anyhow::anyhow!("--tls-cert and --tls-key must be given together")),
+        }
+    }
+
+    fn describe(&self) -> &'static str {
+        if self.client_ca_path.is_some() { "mutual TLS" } else { "TLS" }
+    }
+
+    /// TLS settings for the tonic gRPC server
+    pub fn tonic_config(&self) -> Result<ServerTlsConfig> {
+        let cert = read_pem(&self.cert_path)?;
+        let key = read_pem(&self.key_path)?;
+        let mut tls = ServerTlsConfig::new().identity(Identity::from_pem(cert, key));
+
+        if let Some(ca_path) = &self.client_ca_path {
+            tls = tls.client_ca_root(Certificate::from_pem(read_pem(ca_path)?));
+        }
+
+        info!("{} {} | Serving gRPC over {}", "🔒".bright_green(), "TLS".bright_green(), self.describe());
+        Ok(tls)
+    }
+
+    /// TLS settings for the axum HTTP API
+    pub fn rustls_config(&self) -> Result<RustlsConfig> {
+        let provider = Arc::new(ring::default_provider());
+        let certs = load_certs(&self.cert_path)?;
+        let key = load_private_key(&self.key_path)?;
+
+        let builder = ServerConfig::builder_with_provider(provider.clone())
+            .with_safe_default_protocol_versions()?;
+
+        let builder = match &self.client_ca_path {
+            Some(ca_path) => {
+                let mut roots = RootCertStore::empty();
+                for cert in load_certs(ca_path)? {
+                    roots.add(cert)?;
+                }
+                let verifier = WebPkiClientVerifier::builder_with_provider(Arc::new(roots), provider).build()?;
+                builder.with_client_cert_verifier(verifier)
+            }
+            None => builder.with_no_client_auth(),
+        };
+
+        let mut config = builder.with_single_cert(certs, key)?;
+        config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+
+        info!("{} {} | Serving HTTP API over {}", "🔒".bright_green(), "TLS".bright_green(), self.describe());
+        Ok(RustlsConfig::from_config(Arc::new(config)))
+    }
+}
+
+/// TLS settings for connecting to a gRPC server over `https://`
+pub fn client_tls_config(
+    ca_path: Option<&Path>,
+    cert_path: Option<&Path>,
+    key_path: Option<&Path>,
+) -> Result<ClientTlsConfig> {
+    let mut tls = ClientTlsConfig::new().with_native_roots();
+
+    if let Some(ca_path) = ca_path {
+        tls = tls.ca_certificate(Certificate::from_pem(read_pem(ca_path)?));
+    }
+
+    match (cert_path, key_path) {
+        (Some(cert_path), Some(key_path)) => {
+            tls = tls.identity(Identity::from_pem(read_pem(cert_path)?, read_pem(key_path)?));
+        }
+        (None, None) => {}
+        _ => return Err(anyhow::anyhow!("--tls-cert and --tls-key must be given together")),
+    }
+
+    Ok(tls)
+}
+
+fn read_pem(path: &Path) -> Result<Vec<u8>> {
+    std::fs::read(path).with_context(|| format!("Failed to read {}", path.display()))
+}
+
+fn load_certs(path: &Path) -> Result<Vec<CertificateDer<'static>>> {
+    let pem = read_pem(path)?;
+    let certs = rustls_pemfile::certs(&mut pem.as_slice()).collect::<Result<Vec<_>, _>>()?;
+    if certs.is_empty() {
+        return Err(anyhow::anyhow!("No certificates found in {}", path.display()));
+    }
+    Ok(certs)
+}
+
+fn load_private_key(path: &Path) -> Result<PrivateKeyDer<'static>> {
+    let pem = read_pem(path)?;
+    rustls_pemfile::private_key(&mut pem.as_slice())?
+        .ok_or_else(|| anyhow::anyhow!("No private key found in {}", path.display()))
+}
+
+/// Generate a local CA plus server and client certificates signed by it, for
+/// testing TLS and mTLS without a real PKI
+pub fn generate_self_signed(out_dir: &Path, hosts: Vec<String>) -> Result<()> {
+    std::fs::create_dir_all(out_dir)
+        .with_context(|| format!("Failed to create {}", out_dir.display()))?;
+
+    let mut ca_params = CertificateParams::new(Vec::<String>::new())?;
+    ca_params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+    ca_params.distinguished_name.push(DnType::CommonName, "Solana Indexer Local CA");
+    ca_params.key_usages = vec![KeyUsagePurpose::KeyCertSign, KeyUsagePurpose::CrlSign, KeyUsagePurpose::DigitalSignature];
+    let ca_key = KeyPair::generate()?;
+    let ca_cert = ca_params.self_signed(&ca_key)?;
+
+    let mut server_params = CertificateParams::new(hosts.clone())?;
+    server_params.distinguished_name.push(DnType::CommonName, "solana-indexer");
+    server_params.extended_key_usages = vec![ExtendedKeyUsagePurpose::ServerAuth];
+    let server_key = KeyPair::generate()?;
+    let server_cert = server_params.signed_by(&server_key, &ca_cert, &ca_key)?;
+
+    let mut client_params = CertificateParams::new(Vec::<String>::new())?;
+    client_params.distinguished_name.push(DnType::CommonName, "solana-indexer-client");
+    client_params.extended_key_usages = vec![ExtendedKeyUsagePurpose::ClientAuth];
+    let client_key = KeyPair::generate()?;
+    let client_cert = client_params.signed_by(&client_key, &ca_cert, &ca_key)?;
+
+    let files = [
+        ("ca.pem", ca_cert.pem()),
+        ("server.pem", server_cert.pem()),
+        ("server.key", server_key.serialize_pem()),
+        ("client.pem", client_cert.pem()),
+        ("client.key", client_key.serialize_pem()),
+    ];
+    for (name, contents) in &files {
+        let path = out_dir.join(name);
+        std::fs::write(&path, contents).with_context(|| format!("Failed to write {}", path.display()))?;
+    }
+
+    println!("{}", "🔐 Generated local TLS certificates".bright_green().bold());
+    println!("   {} {}", "Directory:".bright_white(), out_dir.display().to_string().bright_cyan());
+    println!("   {} {}", "Hosts:".bright_white(), hosts.join(", ").bright_cyan());
+    for (name, _) in &files {
+        println!("   {} {}", "•".bright_cyan(), name.bright_white());
+    }
+    println!();
+    println!("{}", "💡 Usage:".bright_yellow());
+    println!("   {}", format!(
+        "grpc start --tls-cert {0}/server.pem --tls-key {0}/server.key --tls-client-ca {0}/ca.pem",
+        out_dir.display()
+    ).bright_white());
+    println!("   {}", format!(
+        "grpc test --address https://localhost:50051 --tls-ca {0}/ca.pem --tls-cert {0}/client.pem --tls-key {0}/client.key",
+        out_dir.display()
+    ).bright_white());
+
+    Ok(())
+}
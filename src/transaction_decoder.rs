@@ -0,0 +1,509 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcTransactionConfig;
+use solana_sdk::signature::Signature;
+use solana_sdk::transaction::VersionedTransaction;
+use solana_transaction_status::option_serializer::OptionSerializer;
+use solana_transaction_status::{
+    EncodedConfirmedTransactionWithStatusMeta, EncodedTransaction, UiInstruction, UiMessage, UiParsedInstruction,
+    UiCompiledInstruction, UiTransactionEncoding, UiTransactionStatusMeta, UiTransactionTokenBalance,
+};
+use std::collections::{BTreeMap, HashSet};
+
+use crate::program_decoders;
+
+pub const COMPUTE_BUDGET_PROGRAM_ID: &str = "ComputeBudget111111111111111111111111111111";
+
+/// Compute units a top-level instruction gets when the transaction sets no limit
+const DEFAULT_INSTRUCTION_COMPUTE_UNITS: u64 = 200_000;
+const MAX_COMPUTE_UNIT_LIMIT: u64 = 1_400_000;
+
+/// A transaction fetched with `jsonParsed` encoding, flattened for storage
+#[derive(Debug, Clone, Serialize)]
+pub struct DecodedTransaction {
+    pub signature: String,
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    pub fee: u64,
+    pub success: bool,
+    /// Transaction error as JSON, for failed transactions
+    pub error: Option<String>,
+    /// Fee payer
+    pub signer: String,
+    pub recent_blockhash: String,
+    pub compute_units: Option<u64>,
+    /// Lamports paid on top of the base fee through the compute unit price
+    pub priority_fee: u64,
+    /// Static keys followed by addresses loaded from lookup tables
+    pub accounts: Vec<TransactionAccount>,
+    /// Top-level instructions, each followed by its inner instructions
+    pub instructions: Vec<DecodedInstruction>,
+    pub log_messages: Vec<String>,
+    /// Accounts whose SOL balance changed
+    pub balance_changes: Vec<BalanceChange>,
+    pub token_balance_changes: Vec<TokenBalanceChange>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TransactionAccount {
+    pub address: String,
+    pub signer: bool,
+    pub writable: bool,
+    pub from_lookup_table: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DecodedInstruction {
+    /// Position of the top-level instruction
+    pub index: u32,
+    /// Position within the top-level instruction's inner instructions
+    pub inner_index: Option<u32>,
+    pub program_id: String,
+    /// Program name from the RPC parser or the program decoder registry
+    pub program: Option<String>,
+    pub parsed_type: Option<String>,
+    pub parsed: Option<Value>,
+    pub accounts: Vec<String>,
+    /// Base58 instruction data, for instructions the RPC could not parse
+    pub data: Option<String>,
+}
+
+impl DecodedInstruction {
+    /// Program, instruction name and decoded `info` as JSON, for named instructions
+    pub fn details(&self) -> Option<Value> {
+        Some(serde_json::json!({
+            "index": self.index,
+            "innerIndex": self.inner_index,
+            "program": self.program.as_deref().unwrap_or(&self.program_id),
+            "instruction": self.parsed_type.as_deref()?,
+            "info": self.parsed.as_ref().and_then(|parsed| parsed.get("info")),
+        }))
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BalanceChange {
+    pub account_index: u32,
+    pub address: String,
+    pub pre_lamports: u64,
+    pub post_lamports: u64,
+}
+
+impl BalanceChange {
+    pub fn delta(&self) -> i64 {
+        self.post_lamports as i64 - self.pre_lamports as i64
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TokenBalanceChange {
+    pub account_index: u32,
+    pub address: String,
+    pub mint: String,
+    pub owner: Option<String>,
+    pub token_program: Option<String>,
+    pub decimals: u8,
+    /// Raw amounts, zero when the token account did not exist before or after
+    pub pre_amount: u64,
+    pub post_amount: u64,
+}
+
+impl TokenBalanceChange {
+    pub fn delta(&self) -> i128 {
+        self.post_amount as i128 - self.pre_amount as i128
+    }
+
+    /// Delta in whole tokens
+    pub fn ui_delta(&self) -> f64 {
+        self.delta() as f64 / 10f64.powi(self.decimals as i32)
+    }
+}
+
+impl DecodedTransaction {
+    pub fn status(&self) -> &'static str {
+        if self.success { "success" } else { "failed" }
+    }
+
+    /// Distinct programs invoked, outer and inner, in order of first use
+    pub fn program_ids(&self) -> Vec<String> {
+        program_ids(&self.instructions)
+    }
+
+    /// First top-level instruction that isn't a compute budget setting
+    pub fn primary_instruction(&self) -> Option<&DecodedInstruction> {
+        self.instructions.iter()
+            .find(|instruction| instruction.inner_index.is_none() && instruction.program_id != COMPUTE_BUDGET_PROGRAM_ID)
+    }
+
+    pub fn timestamp(&self) -> DateTime<Utc> {
+        self.block_time.and_then(|time| DateTime::from_timestamp(time, 0)).unwrap_or_else(Utc::now)
+    }
+}
+
+/// Distinct programs of `instructions`, in order of first use
+pub fn program_ids(instructions: &[DecodedInstruction]) -> Vec<String> {
+    let mut program_ids: Vec<String> = Vec::new();
+    for instruction in instructions {
+        if !program_ids.contains(&instruction.program_id) {
+            program_ids.push(instruction.program_id.clone());
+        }
+    }
+    program_ids
+}
+
+/// Fetch a transaction with `jsonParsed` encoding and decode it
+pub fn fetch_decoded_transaction(client: &RpcClient, signature: &Signature) -> Result<DecodedTransaction> {
+    let transaction = client.get_transaction_with_config(signature, RpcTransactionConfig {
+        encoding: Some(UiTransactionEncoding::JsonParsed),
+        commitment: None,
+        max_supported_transaction_version: Some(0),
+    })?;
+    decode_transaction(&transaction)
+}
+
+/// Decode a transaction fetched with `jsonParsed` encoding and
+/// `maxSupportedTransactionVersion: 0`
+pub fn decode_transaction(transaction: &EncodedConfirmedTransactionWithStatusMeta) -> Result<DecodedTransaction> {
+    let EncodedTransaction::Json(ui_transaction) = &transaction.transaction.transaction else {
+        return Err(anyhow::anyhow!("Transaction was not fetched with JSON encoding"));
+    };
+    let meta = transaction.transaction.meta.as_ref()
+        .ok_or_else(|| anyhow::anyhow!("Transaction has no status meta"))?;
+    let signature = ui_transaction.signatures.first()
+        .ok_or_else(|| anyhow::anyhow!("Transaction has no signatures"))?
+        .clone();
+
+    let loaded: HashSet<&str> = match &meta.loaded_addresses {
+        OptionSerializer::Some(loaded) => loaded.writable.iter().chain(&loaded.readonly).map(String::as_str).collect(),
+        _ => HashSet::new(),
+    };
+
+    let (accounts, recent_blockhash, top_level): (Vec<TransactionAccount>, String, Vec<UiInstruction>) = match &ui_transaction.message {
+        UiMessage::Parsed(message) => (
+            message.account_keys.iter()
+                .map(|account| TransactionAccount {
+                    address: account.pubkey.clone(),
+                    signer: account.signer,
+                    writable: account.writable,
+                    from_lookup_table: loaded.contains(account.pubkey.as_str()),
+                })
+                .collect(),
+            message.recent_blockhash.clone(),
+            message.instructions.clone(),
+        ),
+        UiMessage::Raw(message) => {
+            let header = &message.header;
+            let signers = header.num_required_signatures as usize;
+            let writable_signers = signers.saturating_sub(header.num_readonly_signed_accounts as usize);
+            let writable_end = message.account_keys.len().saturating_sub(header.num_readonly_unsigned_accounts as usize);
+            let mut accounts: Vec<TransactionAccount> = message.account_keys.iter().enumerate()
+                .map(|(index, address)| TransactionAccount {
+                    address: address.clone(),
+                    signer: index < signers,
+                    writable: index < writable_signers || (index >= signers && index < writable_end),
+                    from_lookup_table: false,
+                })
+                .collect();
+            if let OptionSerializer::Some(loaded) = &meta.loaded_addresses {
+                for (address, writable) in loaded.writable.iter().map(|a| (a, true)).chain(loaded.readonly.iter().map(|a| (a, false))) {
+                    accounts.push(TransactionAccount { address: address.clone(), signer: false, writable, from_lookup_table: true });
+                }
+            }
+            (
+                accounts,
+                message.recent_blockhash.clone(),
+                message.instructions.iter().cloned().map(UiInstruction::Compiled).collect(),
+            )
+        }
+    };
+    let account_keys: Vec<&str> = accounts.iter().map(|account| account.address.as_str()).collect();
+
+    let instructions = decode_instructions(&top_level, Some(meta), &account_keys);
+
+    let balance_changes = meta.pre_balances.iter()
+        .zip(&meta.post_balances)
+        .enumerate()
+        .filter(|(_, (pre, post))| pre != post)
+        .map(|(index, (pre, post))| BalanceChange {
+            account_index: index as u32,
+            address: account_keys.get(index).map_or_else(String::new, |address| address.to_string()),
+            pre_lamports: *pre,
+            post_lamports: *post,
+        })
+        .collect();
+
+    let token_balance_changes = token_balance_changes(
+        option_slice(&meta.pre_token_balances),
+        option_slice(&meta.post_token_balances),
+        &account_keys,
+    );
+
+    let compute_units = match meta.compute_units_consumed {
+        OptionSerializer::Some(units) => Some(units),
+        _ => None,
+    };
+
+    Ok(DecodedTransaction {
+        signature,
+        slot: transaction.slot,
+        block_time: transaction.block_time,
+        fee: meta.fee,
+        success: meta.err.is_none(),
+        error: meta.err.as_ref().map(|err| serde_json::to_string(err).unwrap_or_default()),
+        signer: account_keys.first().map_or_else(String::new, |signer| signer.to_string()),
+        recent_blockhash,
+        compute_units,
+        priority_fee: priority_fee(&instructions),
+        accounts,
+        instructions,
+        log_messages: option_slice(&meta.log_messages).to_vec(),
+        balance_changes,
+        token_balance_changes,
+    })
+}
+
+/// Instructions of a binary-encoded transaction, outer and inner. Without the
+/// RPC parser they carry only their accounts and raw data.
+pub fn decode_binary_instructions(transaction: &VersionedTransaction, meta: Option<&UiTransactionStatusMeta>) -> Vec<DecodedInstruction> {
+    let mut addresses: Vec<String> = transaction.message.static_account_keys().iter().map(ToString::to_string).collect();
+    if let Some(OptionSerializer::Some(loaded)) = meta.map(|meta| &meta.loaded_addresses) {
+        addresses.extend(loaded.writable.iter().cloned());
+        addresses.extend(loaded.readonly.iter().cloned());
+    }
+    let account_keys: Vec<&str> = addresses.iter().map(String::as_str).collect();
+
+    let top_level: Vec<UiInstruction> = transaction.message.instructions().iter()
+        .map(|instruction| UiInstruction::Compiled(UiCompiledInstruction::from(instruction, None)))
+        .collect();
+    decode_instructions(&top_level, meta, &account_keys)
+}
+
+/// Top-level instructions, each followed by its inner instructions from `meta`
+fn decode_instructions(top_level: &[UiInstruction], meta: Option<&UiTransactionStatusMeta>, account_keys: &[&str]) -> Vec<DecodedInstruction> {
+    let mut inner: BTreeMap<u32, &Vec<UiInstruction>> = BTreeMap::new();
+    if let Some(OptionSerializer::Some(inner_instructions)) = meta.map(|meta| &meta.inner_instructions) {
+        for group in inner_instructions {
+            inner.insert(group.index as u32, &group.instructions);
+        }
+    }
+
+    let mut instructions = Vec::new();
+    for (index, instruction) in top_level.iter().enumerate() {
+        let index = index as u32;
+        instructions.push(decode_instruction(instruction, index, None, account_keys));
+        if let Some(group) = inner.get(&index) {
+            for (inner_index, instruction) in group.iter().enumerate() {
+                instructions.push(decode_instruction(instruction, index, Some(inner_index as u32), account_keys));
+            }
+        }
+    }
+    instructions
+}
+
+fn option_slice<T>(value: &OptionSerializer<Vec<T>>) -> &[T] {
+    match value {
+        OptionSerializer::Some(values) => values,
+        _ => &[],
+    }
+}
+
+fn decode_instruction(instruction: &UiInstruction, index: u32, inner_index: Option<u32>, account_keys: &[&str]) -> DecodedInstruction {
+    let key = |position: usize| account_keys.get(position).map_or_else(String::new, |key| key.to_string());
+
+    match instruction {
+        UiInstruction::Compiled(compiled) => with_registry(DecodedInstruction {
+            index,
+            inner_index,
+            program_id: key(compiled.program_id_index as usize),
+            program: None,
+            parsed_type: None,
+            parsed: None,
+            accounts: compiled.accounts.iter().map(|position| key(*position as usize)).collect(),
+            data: Some(compiled.data.clone()),
+        }),
+        UiInstruction::Parsed(UiParsedInstruction::Parsed(parsed)) => DecodedInstruction {
+            index,
+            inner_index,
+            program_id: parsed.program_id.clone(),
+            program: Some(parsed.program.clone()),
+            parsed_type: parsed.parsed.get("type").and_then(Value::as_str).map(str::to_string),
+            // Parsed instructions only name their accounts inside `info`
+            accounts: parsed_accounts(&parsed.parsed, account_keys),
+            parsed: Some(parsed.parsed.clone()),
+            data: None,
+        },
+        UiInstruction::Parsed(UiParsedInstruction::PartiallyDecoded(partial)) => with_registry(DecodedInstruction {
+            index,
+            inner_index,
+            program_id: partial.program_id.clone(),
+            program: None,
+            parsed_type: None,
+            parsed: None,
+            accounts: partial.accounts.clone(),
+            data: Some(partial.data.clone()),
+        }),
+    }
+}
+
+/// Name a raw instruction through the program decoder registry, keeping its data
+fn with_registry(mut instruction: DecodedInstruction) -> DecodedInstruction {
+    let named = instruction.data.as_deref()
+        .and_then(|data| bs58::decode(data).into_vec().ok())
+        .and_then(|data| program_decoders::registry().decode(&instruction.program_id, &data, &instruction.accounts));
+
+    if let Some(named) = named {
+        instruction.parsed = Some(named.parsed());
+        instruction.program = Some(named.program);
+        instruction.parsed_type = Some(named.name);
+    }
+    instruction
+}
+
+/// Transaction accounts referenced by a parsed instruction's `info`, in order
+fn parsed_accounts(parsed: &Value, account_keys: &[&str]) -> Vec<String> {
+    let mut accounts = Vec::new();
+    if let Some(info) = parsed.get("info").and_then(Value::as_object) {
+        for value in info.values() {
+            let addresses: Vec<&str> = match value {
+                Value::String(address) => vec![address.as_str()],
+                Value::Array(values) => values.iter().filter_map(Value::as_str).collect(),
+                _ => Vec::new(),
+            };
+            for address in addresses {
+                if account_keys.contains(&address) && !accounts.iter().any(|account: &String| account == address) {
+                    accounts.push(address.to_string());
+                }
+            }
+        }
+    }
+    accounts
+}
+
+/// Pre and post token balances joined on account index
+fn token_balance_changes(
+    pre: &[UiTransactionTokenBalance],
+    post: &[UiTransactionTokenBalance],
+    account_keys: &[&str],
+) -> Vec<TokenBalanceChange> {
+    let mut changes: BTreeMap<u8, TokenBalanceChange> = BTreeMap::new();
+
+    for (balance, is_post) in pre.iter().map(|balance| (balance, false)).chain(post.iter().map(|balance| (balance, true))) {
+        let amount = balance.ui_token_amount.amount.parse::<u64>().unwrap_or_default();
+        let change = changes.entry(balance.account_index).or_insert_with(|| TokenBalanceChange {
+            account_index: balance.account_index as u32,
+            address: account_keys.get(balance.account_index as usize).map_or_else(String::new, |address| address.to_string()),
+            mint: balance.mint.clone(),
+            owner: match &balance.owner {
+                OptionSerializer::Some(owner) => Some(owner.clone()),
+                _ => None,
+            },
+            token_program: match &balance.program_id {
+                OptionSerializer::Some(program_id) => Some(program_id.clone()),
+                _ => None,
+            },
+            decimals: balance.ui_token_amount.decimals,
+            pre_amount: 0,
+            post_amount: 0,
+        });
+        if is_post {
+            change.post_amount = amount;
+        } else {
+            change.pre_amount = amount;
+        }
+    }
+
+    changes.into_values().collect()
+}
+
+/// Compute unit price times the compute unit limit, rounded up to whole lamports
+fn priority_fee(instructions: &[DecodedInstruction]) -> u64 {
+    let mut unit_price: u64 = 0;
+    let mut unit_limit: Option<u64> = None;
+    let mut other_instructions: u64 = 0;
+
+    for instruction in instructions.iter().filter(|instruction| instruction.inner_index.is_none()) {
+        if instruction.program_id != COMPUTE_BUDGET_PROGRAM_ID {
+            other_instructions += 1;
+            continue;
+        }
+        let Some(data) = instruction.data.as_deref().and_then(|data| bs58::decode(data).into_vec().ok()) else {
+            continue;
+        };
+        match data.first() {
+            // SetComputeUnitLimit(u32)
+            Some(2) if data.len() >= 5 => unit_limit = Some(u32::from_le_bytes(data[1..5].try_into().unwrap()) as u64),
+            // SetComputeUnitPrice(u64), in micro-lamports per unit
+            Some(3) if data.len() >= 9 => unit_price = u64::from_le_bytes(data[1..9].try_into().unwrap()),
+            _ => {}
+        }
+    }
+
+    let unit_limit = unit_limit
+        .unwrap_or(other_instructions * DEFAULT_INSTRUCTION_COMPUTE_UNITS)
+        .min(MAX_COMPUTE_UNIT_LIMIT);
+    ((unit_price as u128 * unit_limit as u128).div_ceil(1_000_000)) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::activity_classifier::SYSTEM_PROGRAM_ID;
+
+    fn instruction(program_id: &str, data: &[u8], inner_index: Option<u32>) -> DecodedInstruction {
+        DecodedInstruction {
+            index: 0,
+            inner_index,
+            program_id: program_id.to_string(),
+            program: None,
+            parsed_type: None,
+            parsed: None,
+            accounts: Vec::new(),
+            data: Some(bs58::encode(data).into_string()),
+        }
+    }
+
+    fn compute_budget(discriminator: u8, argument: &[u8]) -> DecodedInstruction {
+        let mut data = vec![discriminator];
+        data.extend_from_slice(argument);
+        instruction(COMPUTE_BUDGET_PROGRAM_ID, &data, None)
+    }
+
+    fn set_limit(units: u32) -> DecodedInstruction {
+        compute_budget(2, &units.to_le_bytes())
+    }
+
+    fn set_price(micro_lamports: u64) -> DecodedInstruction {
+        compute_budget(3, &micro_lamports.to_le_bytes())
+    }
+
+    #[test]
+    fn priority_fee_is_price_times_limit_rounded_up() {
+        let instructions = [set_limit(300_000), set_price(1_001), instruction(SYSTEM_PROGRAM_ID, &[2], None)];
+        // 300,000 units at 1,001 micro-lamports is 300.3 lamports
+        assert_eq!(priority_fee(&instructions), 301);
+    }
+
+    #[test]
+    fn priority_fee_defaults_the_limit_per_top_level_instruction() {
+        let instructions = [
+            set_price(1_000_000),
+            instruction(SYSTEM_PROGRAM_ID, &[2], None),
+            instruction(SYSTEM_PROGRAM_ID, &[2], None),
+            // Inner instructions don't get a default allocation
+            instruction(SYSTEM_PROGRAM_ID, &[2], Some(0)),
+        ];
+        assert_eq!(priority_fee(&instructions), 2 * DEFAULT_INSTRUCTION_COMPUTE_UNITS);
+    }
+
+    #[test]
+    fn priority_fee_caps_the_limit_and_ignores_malformed_data() {
+        let instructions = [set_limit(u32::MAX), set_price(1_000_000)];
+        assert_eq!(priority_fee(&instructions), MAX_COMPUTE_UNIT_LIMIT);
+
+        let truncated = [instruction(COMPUTE_BUDGET_PROGRAM_ID, &[3, 1, 2], None), instruction(SYSTEM_PROGRAM_ID, &[2], None)];
+        assert_eq!(priority_fee(&truncated), 0);
+    }
+}
@@ -2,18 +2,31 @@ use anyhow::Result;
 use colored::*;
 use solana_client::rpc_client::RpcClient;
 use solana_sdk::{pubkey::Pubkey, signature::Signature};
-use std::collections::HashMap;
+use solana_rpc_client_api::request::TokenAccountsFilter;
+use std::collections::{BTreeMap, HashMap};
 use std::str::FromStr;
 use std::time::Duration;
 use tokio::time::interval;
+use crate::activity_classifier::{ActivityClassifier, Classification};
 use crate::config::Config;
-use crate::database::Database;
+use crate::database::{Database, WalletBalance, INITIAL_BLOCKHASH};
 use crate::logger::icons;
+use crate::transaction_decoder::{self, DecodedInstruction, DecodedTransaction};
 use crate::animations::{CliAnimations, StatusStats};
 use crate::enhanced_logger::{EnhancedLogger, LogType};
 use sqlx::Row;
 use serde::{Deserialize, Serialize};
 
+pub const TOKEN_PROGRAM_ID: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+pub const TOKEN_2022_PROGRAM_ID: &str = "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb";
+
+/// Symbols for well-known mints; anything else is shown by address
+const KNOWN_TOKEN_SYMBOLS: &[(&str, &str)] = &[
+    ("So11111111111111111111111111111111111111112", "wSOL"),
+    ("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v", "USDC"),
+    ("Es9vMFrzaCERmJfrF4H2FYD4KCoNkY11McCe8BenwNYB", "USDT"),
+];
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TrackedWallet {
     pub id: i64,
@@ -97,6 +110,158 @@ impl ActivityType {
 
 
 
+/// A token account owned by a tracked wallet
+#[derive(Debug, Clone)]
+struct TokenHolding {
+    mint: String,
+    decimals: u8,
+    amount: u64,
+    /// Slot the amount was read at
+    slot: u64,
+}
+
+/// A wallet's SPL Token and Token-2022 accounts, keyed by token account address
+#[derive(Debug, Default)]
+struct WalletTokens {
+    accounts: HashMap<String, TokenHolding>,
+}
+
+impl WalletTokens {
+    /// Amount of `mint` across all of the wallet's token accounts, in whole tokens
+    fn mint_balance(&self, mint: &str) -> f64 {
+        self.accounts.values()
+            .filter(|holding| holding.mint == mint)
+            .map(|holding| holding.amount as f64 / 10f64.powi(holding.decimals as i32))
+            .sum()
+    }
+
+    fn balances(&self) -> Vec<WalletBalance> {
+        let mut mints: Vec<&str> = self.accounts.values().map(|holding| holding.mint.as_str()).collect();
+        mints.sort_unstable();
+        mints.dedup();
+
+        mints.into_iter().map(|mint| token_balance(self, mint)).collect()
+    }
+}
+
+pub fn token_symbol(mint: &str) -> Option<&'static str> {
+    KNOWN_TOKEN_SYMBOLS.iter().find(|(known, _)| *known == mint).map(|(_, symbol)| *symbol)
+}
+
+/// Symbol of `mint`, or its shortened address
+fn token_label(mint: &str) -> String {
+    match token_symbol(mint) {
+        Some(symbol) => symbol.to_string(),
+        None if mint.len() > 8 => format!("{}...{}", &mint[..4], &mint[mint.len()-4..]),
+        None => mint.to_string(),
+    }
+}
+
+fn token_balance(tokens: &WalletTokens, mint: &str) -> WalletBalance {
+    WalletBalance {
+        token_mint: Some(mint.to_string()),
+        token_symbol: token_symbol(mint).map(str::to_string),
+        balance: tokens.mint_balance(mint),
+    }
+}
+
+/// Every token account `owner` holds under both token programs, with the
+/// slot they were read at
+fn fetch_token_holdings(client: &RpcClient, owner: &Pubkey) -> Result<(u64, HashMap<String, TokenHolding>)> {
+    let mut slot = 0;
+    let mut accounts = HashMap::new();
+
+    for program_id in [TOKEN_PROGRAM_ID, TOKEN_2022_PROGRAM_ID] {
+        let response = client.get_token_accounts_by_owner_with_commitment(
+            owner,
+            TokenAccountsFilter::ProgramId(Pubkey::from_str(program_id)?),
+            client.commitment(),
+        )?;
+        slot = slot.max(response.context.slot);
+
+        for keyed in response.value {
+            let data = serde_json::to_value(&keyed.account.data)?;
+            let info = &data["parsed"]["info"];
+            let (Some(mint), Some(amount), Some(decimals)) = (
+                info["mint"].as_str(),
+                info["tokenAmount"]["amount"].as_str().and_then(|amount| amount.parse::<u64>().ok()),
+                info["tokenAmount"]["decimals"].as_u64(),
+            ) else {
+                continue;
+            };
+
+            accounts.insert(keyed.pubkey, TokenHolding {
+                mint: mint.to_string(),
+                decimals: decimals as u8,
+                amount,
+                slot: response.context.slot,
+            });
+        }
+    }
+
+    Ok((slot, accounts))
+}
+
+/// Read the wallet's SOL and token balances and record them as a snapshot
+async fn snapshot_wallet_balances(db: &Database, client: &RpcClient, address: &str) -> Result<WalletTokens> {
+    let pubkey = Pubkey::from_str(address)?;
+    let now = chrono::Utc::now();
+
+    let sol = client.get_balance_with_commitment(&pubkey, client.commitment())?;
+    db.save_wallet_balances(address, sol.context.slot, now, &[WalletBalance {
+        token_mint: None,
+        token_symbol: Some("SOL".to_string()),
+        balance: sol.value as f64 / 1_000_000_000.0,
+    }]).await?;
+
+    let (slot, accounts) = fetch_token_holdings(client, &pubkey)?;
+    let tokens = WalletTokens { accounts };
+    db.save_wallet_balances(address, slot, now, &tokens.balances()).await?;
+
+    Ok(tokens)
+}
+
+/// Apply the wallet's post-transaction token balances to `tokens` and record
+/// a snapshot of every mint they changed, plus SOL. Returns the wallet's
+/// token deltas per mint.
+async fn record_transaction_balances(
+    db: &Database,
+    decoded: &DecodedTransaction,
+    wallet_address: &str,
+    tokens: &mut WalletTokens,
+) -> Result<Vec<(String, f64)>> {
+    let mut deltas: BTreeMap<String, f64> = BTreeMap::new();
+    let mut balances = Vec::new();
+
+    for change in decoded.token_balance_changes.iter().filter(|change| change.owner.as_deref() == Some(wallet_address)) {
+        *deltas.entry(change.mint.clone()).or_default() += change.ui_delta();
+
+        // A transaction older than the last read must not roll the balance back
+        if tokens.accounts.get(&change.address).is_some_and(|holding| holding.slot > decoded.slot) {
+            continue;
+        }
+        tokens.accounts.insert(change.address.clone(), TokenHolding {
+            mint: change.mint.clone(),
+            decimals: change.decimals,
+            amount: change.post_amount,
+            slot: decoded.slot,
+        });
+    }
+
+    for mint in deltas.keys() {
+        balances.push(token_balance(tokens, mint));
+    }
+    if let Some(change) = decoded.balance_changes.iter().find(|change| change.address == wallet_address) {
+        balances.push(WalletBalance {
+            token_mint: None,
+            token_symbol: Some("SOL".to_string()),
+            balance: change.post_lamports as f64 / 1_000_000_000.0,
+        });
+    }
+    db.save_wallet_balances(wallet_address, decoded.slot, decoded.timestamp(), &balances).await?;
+
+    Ok(deltas.into_iter().filter(|(_, delta)| *delta != 0.0).collect())
+}
 
 pub async fn list_wallets(config: &Config) -> Result<()> {
     if !config.database_config.enable_database {
@@ -194,6 +359,7 @@ pub async fn start_monitoring(config: &Config, client: &RpcClient, interval_ms:
     );
 
     let mut wallet_map = HashMap::new();
+    let mut wallet_tokens: HashMap<String, WalletTokens> = HashMap::new();
     for wallet in &wallets {
         let address: String = wallet.get("address");
         let name: Option<String> = wallet.get("name");
@@ -207,6 +373,30 @@ pub async fn start_monitoring(config: &Config, client: &RpcClient, interval_ms:
                 &address[address.len()-8..]
             ).bright_white()
         );
+
+        match snapshot_wallet_balances(&db, client, &address).await {
+            Ok(tokens) => {
+                let balances = tokens.balances();
+                if !balances.is_empty() {
+                    println!("      {} {}",
+                        icons::CHART,
+                        balances.iter()
+                            .map(|balance| format!("{} {}", balance.balance, token_label(balance.token_mint.as_deref().unwrap_or_default())))
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                            .bright_black()
+                    );
+                }
+                wallet_tokens.insert(address.clone(), tokens);
+            }
+            Err(e) => {
+                println!("      {} {} {}",
+                    icons::WARNING,
+                    "Failed to read token balances:".bright_yellow(),
+                    e.to_string().bright_red()
+                );
+            }
+        }
     }
 
     println!("\n{} {} {}\n",
@@ -215,6 +405,7 @@ pub async fn start_monitoring(config: &Config, client: &RpcClient, interval_ms:
         format!("(checking every {}ms)", interval_ms).bright_black()
     );
 
+    let classifier = ActivityClassifier::new();
     let mut interval_timer = interval(Duration::from_millis(interval_ms));
     let mut last_signatures: HashMap<String, Vec<String>> = HashMap::new();
     let mut iteration_count = 0;
@@ -281,7 +472,8 @@ pub async fn start_monitoring(config: &Config, client: &RpcClient, interval_ms:
                                         "T:{}", &sig_str[..6]);
 
                                     // Process new transaction
-                                    process_transaction(&db, client, address, name, &signature, &filter).await?;
+                                    let tokens = wallet_tokens.entry(address.clone()).or_default();
+                                    process_transaction(&db, client, &classifier, address, name, tokens, &signature, &filter).await?;
                                 }
                             }
                         }
@@ -320,92 +512,100 @@ pub async fn start_monitoring(config: &Config, client: &RpcClient, interval_ms:
 async fn process_transaction(
     db: &Database,
     client: &RpcClient,
+    classifier: &ActivityClassifier,
     wallet_address: &str,
     wallet_name: &str,
+    tokens: &mut WalletTokens,
     signature: &Signature,
     filter: &Option<Vec<String>>
 ) -> Result<()> {
-    match client.get_transaction(signature, solana_transaction_status::UiTransactionEncoding::Json) {
-        Ok(transaction) => {
-            if let Some(tx) = transaction.transaction.transaction.decode() {
-                let activity_type = classify_transaction(&tx, wallet_address);
-
-                // Apply filter if specified
-                if let Some(filters) = filter {
-                    if !filters.iter().any(|f| f.to_lowercase() == activity_type.as_str().to_lowercase()) {
-                        return Ok(());
-                    }
+    match transaction_decoder::fetch_decoded_transaction(client, signature) {
+        Ok(decoded) => {
+            let classification = classifier.classify(&decoded, wallet_address);
+            let activity_type = &classification.activity_type;
+
+            // Balances are tracked for every transaction, even ones the filter hides
+            let token_deltas = record_transaction_balances(db, &decoded, wallet_address, tokens).await?;
+
+            // Apply filter if specified
+            if let Some(filters) = filter {
+                if !filters.iter().any(|f| f.to_lowercase() == activity_type.as_str().to_lowercase()) {
+                    return Ok(());
                 }
+            }
+
+            let fee = decoded.fee;
+            let slot = decoded.slot;
+            let timestamp = decoded.timestamp();
+            let status = if decoded.success { "SUCCESS" } else { "FAILED" };
 
-                let fee = transaction.transaction.meta.as_ref()
-                    .map(|meta| meta.fee)
-                    .unwrap_or(0);
-
-                let slot = transaction.slot;
-                let timestamp = chrono::Utc::now();
-
-                // First, ensure the slot exists in the slots table
-                sqlx::query(
-                    "INSERT OR IGNORE INTO slots (slot, blockhash, parent_slot, finalized, timestamp) VALUES (?, ?, ?, ?, ?)"
-                )
-                .bind(slot as i64)
-                .bind("pending_blockhash") // Placeholder blockhash
-                .bind((slot.saturating_sub(1)) as i64)
-                .bind(false)
-                .bind(timestamp)
-                .execute(db.get_pool())
-                .await?;
-
-                // Next, ensure the transaction exists in the transactions table
-                sqlx::query(
-                    "INSERT OR IGNORE INTO transactions (signature, slot, fee, status, program_ids, timestamp) VALUES (?, ?, ?, ?, ?, ?)"
-                )
-                .bind(signature.to_string())
-                .bind(slot as i64)
-                .bind(fee as i64)
-                .bind("SUCCESS")
-                .bind("[]") // Empty program IDs array as JSON string
-                .bind(timestamp)
-                .execute(db.get_pool())
-                .await?;
-
-                // Now store the wallet activity (foreign key constraints will be satisfied)
-                sqlx::query(
-                    "INSERT INTO wallet_activities (wallet_address, activity_type, transaction_signature, timestamp, block_slot, fee, status) VALUES (?, ?, ?, ?, ?, ?, ?)"
-                )
-                .bind(wallet_address)
-                .bind(activity_type.as_str())
-                .bind(signature.to_string())
-                .bind(timestamp)
-                .bind(slot as i64)
-                .bind(fee as i64)
-                .bind("SUCCESS")
-                .execute(db.get_pool())
-                .await?;
-
-                // Update wallet last activity
-                sqlx::query(
-                    "UPDATE tracked_wallets SET last_activity = ?, activity_count = activity_count + 1 WHERE address = ?"
-                )
-                .bind(timestamp)
-                .bind(wallet_address)
-                .execute(db.get_pool())
-                .await?;
-
-                // Display real-time activity
-                let short_addr = format!("{}...{}", &wallet_address[..6], &wallet_address[wallet_address.len()-6..]);
-                let fee_display = if fee > 0 {
-                    format!("{:.9} SOL", (fee as f64 / 1_000_000_000.0))
-                } else {
-                    "No fee".to_string()
-                };
-
-                println!("{} {} {} {} {}",
-                    activity_type.icon().color(activity_type.color()),
-                    activity_type.as_str().color(activity_type.color()).bold(),
-                    format!("{} ({})", wallet_name, short_addr).bright_white(),
-                    signature.to_string().bright_blue(),
-                    fee_display.bright_yellow()
+            // Store the decoded transaction first so the activity's foreign keys are satisfied
+            db.store_decoded_transaction(&decoded).await?;
+
+            // Now store the wallet activity (foreign key constraints will be satisfied)
+            let primary = decoded.primary_instruction();
+            sqlx::query(
+                "INSERT INTO wallet_activities (wallet_address, activity_type, transaction_signature, amount, token_symbol, counterparty, timestamp, block_slot, fee, status, details, program_id, instruction_type) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
+            )
+            .bind(wallet_address)
+            .bind(activity_type.as_str())
+            .bind(signature.to_string())
+            .bind(classification.amount)
+            .bind(classification.token_symbol.as_deref())
+            .bind(classification.counterparty.as_deref())
+            .bind(timestamp)
+            .bind(slot as i64)
+            .bind(fee as i64)
+            .bind(status)
+            .bind(activity_details(&classification, &decoded))
+            .bind(classification.program_id.as_deref().or(primary.map(|instruction| instruction.program_id.as_str())))
+            .bind(classification.instruction_type.as_deref().or(primary.and_then(|instruction| instruction.parsed_type.as_deref())))
+            .execute(db.get_pool())
+            .await?;
+
+            // Update wallet last activity
+            sqlx::query(
+                "UPDATE tracked_wallets SET last_activity = ?, activity_count = activity_count + 1 WHERE address = ?"
+            )
+            .bind(timestamp)
+            .bind(wallet_address)
+            .execute(db.get_pool())
+            .await?;
+
+            // Display real-time activity
+            let short_addr = format!("{}...{}", &wallet_address[..6], &wallet_address[wallet_address.len()-6..]);
+            let fee_display = if fee > 0 {
+                format!("{:.9} SOL", (fee as f64 / 1_000_000_000.0))
+            } else {
+                "No fee".to_string()
+            };
+
+            let amount_display = match (classification.amount, &classification.token_symbol) {
+                (Some(amount), Some(symbol)) => format!(" {} {}", amount, token_label(symbol)),
+                _ => String::new(),
+            };
+            let counterparty_display = match classification.counterparty.as_deref() {
+                Some(counterparty) if counterparty.len() > 12 => format!(" ⇄ {}...{}", &counterparty[..6], &counterparty[counterparty.len()-6..]),
+                Some(counterparty) => format!(" ⇄ {}", counterparty),
+                None => String::new(),
+            };
+
+            println!("{} {}{}{} {} {} {}",
+                activity_type.icon().color(activity_type.color()),
+                activity_type.as_str().color(activity_type.color()).bold(),
+                amount_display.bright_white().bold(),
+                counterparty_display.bright_black(),
+                format!("{} ({})", wallet_name, short_addr).bright_white(),
+                signature.to_string().bright_blue(),
+                fee_display.bright_yellow()
+            );
+
+            for (mint, delta) in &token_deltas {
+                let change = format!("{:+} {}", delta, token_label(mint));
+                println!("   {} {} {}",
+                    "↳".bright_black(),
+                    if *delta > 0.0 { change.bright_green() } else { change.bright_red() },
+                    format!("(balance {})", tokens.mint_balance(mint)).bright_black()
                 );
             }
         }
@@ -417,33 +617,21 @@ async fn process_transaction(
     Ok(())
 }
 
-fn classify_transaction(transaction: &solana_sdk::transaction::VersionedTransaction, wallet_address: &str) -> ActivityType {
-    // Simple classification based on transaction structure
-    // This is a basic implementation - you can enhance this with more sophisticated logic
-
-    let wallet_pubkey = match Pubkey::from_str(wallet_address) {
-        Ok(pk) => pk,
-        Err(_) => return ActivityType::Unknown,
+/// The classification's details plus every named instruction, as JSON
+fn activity_details(classification: &Classification, decoded: &DecodedTransaction) -> Option<String> {
+    let mut details = match &classification.details {
+        Some(serde_json::Value::Object(details)) => details.clone(),
+        _ => serde_json::Map::new(),
     };
 
-    // Check if wallet is in accounts as sender or receiver
-    let account_keys = match &transaction.message {
-        solana_sdk::message::VersionedMessage::Legacy(msg) => &msg.account_keys,
-        solana_sdk::message::VersionedMessage::V0(msg) => &msg.account_keys,
-    };
-    let is_signer = account_keys.get(0) == Some(&wallet_pubkey);
-
-    if is_signer {
-        // Wallet is sending/initiating transaction
-        if account_keys.len() > 2 {
-            ActivityType::Send
-        } else {
-            ActivityType::Unknown
-        }
-    } else {
-        // Wallet is receiving
-        ActivityType::Receive
+    let instructions: Vec<serde_json::Value> = decoded.instructions.iter()
+        .filter_map(DecodedInstruction::details)
+        .collect();
+    if !instructions.is_empty() {
+        details.insert("instructions".to_string(), instructions.into());
     }
+
+    (!details.is_empty()).then(|| serde_json::Value::Object(details).to_string())
 }
 
 pub async fn show_history(config: &Config, wallet_identifier: &str, limit: u32) -> Result<()> {
@@ -507,6 +695,8 @@ pub async fn show_history(config: &Config, wallet_identifier: &str, limit: u32)
         let slot: i64 = activity.get("block_slot");
         let fee: i64 = activity.get("fee");
         let status: String = activity.get("status");
+        let amount: Option<f64> = activity.get("amount");
+        let token_symbol: Option<String> = activity.get("token_symbol");
 
         let activity_type = match activity_type_str.as_str() {
             "SEND" => ActivityType::Send,
@@ -522,9 +712,15 @@ pub async fn show_history(config: &Config, wallet_identifier: &str, limit: u32)
         let time_str = timestamp.format("%m-%d %H:%M:%S").to_string();
         let fee_sol = fee as f64 / 1_000_000_000.0;
 
-        println!("{} {} {} {} {} {} SOL",
+        let amount_display = match (amount, token_symbol.as_deref()) {
+            (Some(amount), Some(symbol)) => format!(" {} {}", amount, token_label(symbol)),
+            _ => String::new(),
+        };
+
+        println!("{} {}{} {} {} {} {} SOL",
             activity_type.icon().color(activity_type.color()),
             activity_type.as_str().color(activity_type.color()).bold(),
+            amount_display.bright_white().bold(),
             time_str.bright_black(),
             format!("Slot:{}", slot).bright_cyan(),
             signature.bright_blue(),
@@ -565,7 +761,7 @@ async fn ensure_initial_slot_data(db: &Database, client: &RpcClient) -> Result<(
                 "INSERT OR IGNORE INTO slots (slot, blockhash, parent_slot, finalized, timestamp) VALUES (?, ?, ?, ?, ?)"
             )
             .bind(slot as i64)
-            .bind("initial_blockhash") // Placeholder blockhash
+            .bind(INITIAL_BLOCKHASH)
             .bind((slot.saturating_sub(1)) as i64)
             .bind(slot < current_slot.saturating_sub(32)) // Mark older slots as finalized
             .bind(timestamp)
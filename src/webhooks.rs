@@ -186,3 +186,28 @@ pub async fn test_webhook_connectivity() -> Result<()> {
 
     Ok(())
 }
+
+/// POST an indexer event to `{base_url}/{event_type}` using the same envelope
+/// as the test payload
+pub async fn deliver_event(base_url: &str, event_type: &str, data: Value) -> Result<()> {
+    let url = format!("{}/{}", base_url.trim_end_matches('/'), event_type);
+    let payload = json!({
+        "event_type": event_type,
+        "timestamp": chrono::Utc::now().to_rfc3339(),
+        "data": data
+    });
+
+    let response = reqwest::Client::new()
+        .post(&url)
+        .timeout(std::time::Duration::from_secs(10))
+        .json(&payload)
+        .send()
+        .await?;
+
+    if !response.status().is_success() {
+        return Err(anyhow::anyhow!("Webhook endpoint returned {}", response.status()));
+    }
+
+    info!("{} {} {}", "📤 Delivered".bright_green(), event_type.bright_cyan(), "webhook event".bright_green());
+    Ok(())
+}
@@ -1,12 +1,18 @@
 use {
     bs58,
     futures::{sink::SinkExt, stream::StreamExt},
-    log::{info, error, warn},
-    std::{collections::HashMap, env},
-    tokio,
+    log::{debug, info, error, warn},
+    std::{
+        collections::{HashSet, VecDeque},
+        env,
+        hash::{BuildHasher, RandomState},
+        time::Duration,
+    },
+    tokio::{self, time::Instant},
     tonic::{
         transport::ClientTlsConfig,
         service::Interceptor,
+        Code,
         Status,
     },
     yellowstone_grpc_client::GeyserGrpcClient,
@@ -14,14 +20,17 @@ use {
         geyser::SubscribeUpdate,
         prelude::{
             subscribe_update::UpdateOneof,
-            CommitmentLevel,
+            SlotStatus,
             SubscribeRequest,
-            SubscribeRequestFilterTransactions,
+            SubscribeRequestPing,
         },
     },
     anyhow::Result,
     colored::*,
+    crate::database::Database,
     crate::logger::NerdLogger,
+    crate::pump_fun::{self, TokenTrade, TradeAction},
+    crate::yellowstone_subscription::SubscriptionConfig,
 };
 
 // Constants
@@ -29,11 +38,55 @@ const RUST_LOG_LEVEL: &str = "info";
 const PUMP_FUN_FEE_ACCOUNT: &str = "CebN5WGQ4jvEPvsVU4EoHEpgzq1VV7AbicfhtW4xC9iM";
 const PUMP_FUN_PROGRAM: &str = "6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P";
 
+/// Reconnect backoff bounds; each delay is jittered between half and all of it
+const RECONNECT_MIN_DELAY: Duration = Duration::from_secs(1);
+const RECONNECT_MAX_DELAY: Duration = Duration::from_secs(60);
+/// How often we ping the server so idle streams aren't dropped by proxies
+const PING_INTERVAL: Duration = Duration::from_secs(10);
+/// A stream silent for this long (pongs included) is treated as dead
+const STREAM_IDLE_TIMEOUT: Duration = Duration::from_secs(30);
+/// Signatures remembered to skip transactions replayed after a reconnect
+const RECENT_SIGNATURES: usize = 10_000;
+
+/// What carries over from one connection to the next
+#[derive(Default)]
+struct StreamState {
+    /// Highest slot seen so far, replayed from after a reconnect
+    last_slot: Option<u64>,
+    /// Set while a `from_slot` request waits for its first response
+    awaiting_replay: bool,
+    message_count: u64,
+    seen_signatures: HashSet<Vec<u8>>,
+    signature_order: VecDeque<Vec<u8>>,
+}
+
+impl StreamState {
+    fn observe_slot(&mut self, slot: u64) {
+        self.last_slot = Some(self.last_slot.map_or(slot, |last| last.max(slot)));
+    }
+
+    /// Remember `signature`, returning false if it was already seen
+    fn first_sighting(&mut self, signature: &[u8]) -> bool {
+        if !self.seen_signatures.insert(signature.to_vec()) {
+            return false;
+        }
+        self.signature_order.push_back(signature.to_vec());
+        if self.signature_order.len() > RECENT_SIGNATURES {
+            if let Some(oldest) = self.signature_order.pop_front() {
+                self.seen_signatures.remove(&oldest);
+            }
+        }
+        true
+    }
+}
+
 pub struct YellowstoneMonitor {
     endpoint: String,
     auth_token: String,
     logger: NerdLogger,
     accounts_to_monitor: Vec<String>,
+    database: Option<Database>,
+    subscription: Option<SubscriptionConfig>,
 }
 
 impl YellowstoneMonitor {
@@ -46,9 +99,39 @@ impl YellowstoneMonitor {
                 PUMP_FUN_FEE_ACCOUNT.to_string(),
                 PUMP_FUN_PROGRAM.to_string(),
             ],
+            database: None,
+            subscription: None,
+        }
+    }
+
+    /// Stream what `subscription` asks for instead of transactions touching
+    /// the monitored accounts. Without filters only its commitment is used.
+    pub fn with_subscription(mut self, subscription: SubscriptionConfig) -> Self {
+        self.subscription = Some(subscription);
+        self
+    }
+
+    /// The subscription that will be sent
+    pub fn subscription(&self) -> SubscriptionConfig {
+        match &self.subscription {
+            Some(subscription) if !subscription.is_empty() => subscription.clone(),
+            other => SubscriptionConfig {
+                commitment: other.as_ref().and_then(|subscription| subscription.commitment),
+                ..SubscriptionConfig::required_accounts(&self.accounts_to_monitor)
+            },
         }
     }
 
+    fn uses_monitored_accounts(&self) -> bool {
+        self.subscription.as_ref().is_none_or(|subscription| subscription.is_empty())
+    }
+
+    /// Persist decoded pump.fun trades to `token_trades`
+    pub fn with_database(mut self, database: Database) -> Self {
+        self.database = Some(database);
+        self
+    }
+
     pub fn add_account(&mut self, account: String) {
         if !self.accounts_to_monitor.contains(&account) {
             self.accounts_to_monitor.push(account);
@@ -92,16 +175,33 @@ impl YellowstoneMonitor {
             "│".truecolor(80, 250, 123)
         );
 
-        let accounts_info = format!("Monitoring {} accounts", self.accounts_to_monitor.len());
-        let accounts_padding = content_width - accounts_info.len() - 2;
+        let subscription = self.subscription();
+        let subscription_info = format!(
+            "Commitment: {} | Filters: {}",
+            subscription.commitment.unwrap_or_default().as_str(),
+            subscription.summary()
+        );
+        let subscription_padding = content_width.saturating_sub(subscription_info.len() + 2);
         println!("{} {} {} {}",
             "│".truecolor(80, 250, 123),
-            accounts_info.truecolor(255, 184, 108),
-            " ".repeat(accounts_padding),
+            subscription_info.truecolor(139, 233, 253),
+            " ".repeat(subscription_padding),
             "│".truecolor(80, 250, 123)
         );
 
-        for account in &self.accounts_to_monitor {
+        let monitored_accounts: &[String] = if self.uses_monitored_accounts() { &self.accounts_to_monitor } else { &[] };
+        if !monitored_accounts.is_empty() {
+            let accounts_info = format!("Monitoring {} accounts", monitored_accounts.len());
+            let accounts_padding = content_width - accounts_info.len() - 2;
+            println!("{} {} {} {}",
+                "│".truecolor(80, 250, 123),
+                accounts_info.truecolor(255, 184, 108),
+                " ".repeat(accounts_padding),
+                "│".truecolor(80, 250, 123)
+            );
+        }
+
+        for account in monitored_accounts {
             let account_info = format!("  • {}", account);
             let account_padding = content_width - account_info.len() - 2;
             println!("{} {} {} {}",
@@ -115,24 +215,69 @@ impl YellowstoneMonitor {
         let bottom_border = format!("└─{}─┘", "─".repeat(content_width - 2));
         println!("{}", bottom_border.truecolor(80, 250, 123));
 
-        info!("Starting to monitor {} accounts", self.accounts_to_monitor.len());
-        self.logger.info(&format!("Starting Yellowstone gRPC monitoring for {} accounts", self.accounts_to_monitor.len()), "YELLOWSTONE");
+        info!("Starting to monitor {}", subscription.summary());
+        self.logger.info(&format!("Starting Yellowstone gRPC monitoring ({})", subscription.summary()), "YELLOWSTONE");
+
+        let request = subscription.to_request();
+        let mut state = StreamState::default();
+        let mut backoff = RECONNECT_MIN_DELAY;
+
+        loop {
+            match self.stream_once(&request, &mut state, &mut backoff).await {
+                Ok(()) => {
+                    warn!("Stream closed by the server");
+                    self.logger.warn("Yellowstone gRPC stream closed by the server", "YELLOWSTONE");
+                }
+                Err(e) if state.awaiting_replay && replay_rejected(&e) => {
+                    // Replay isn't supported or the slot is too old; resume from the tip
+                    warn!("Endpoint cannot replay from slot {}: {}", state.last_slot.unwrap_or_default(), e);
+                    self.logger.warn(&format!("Endpoint cannot replay from slot {}, resuming from the tip: {}", state.last_slot.unwrap_or_default(), e), "YELLOWSTONE");
+                    state.last_slot = None;
+                    state.awaiting_replay = false;
+                    backoff = RECONNECT_MIN_DELAY;
+                }
+                Err(e) => {
+                    error!("Yellowstone stream failed: {:?}", e);
+                    self.logger.error(&format!("Yellowstone stream failed: {}", e), "YELLOWSTONE");
+                }
+            }
 
+            let delay = jittered(backoff);
+            warn!("Reconnecting in {}ms", delay.as_millis());
+            self.logger.warn(&format!("Reconnecting to Yellowstone in {}ms", delay.as_millis()), "YELLOWSTONE");
+            tokio::time::sleep(delay).await;
+            backoff = (backoff * 2).min(RECONNECT_MAX_DELAY);
+        }
+    }
+
+    /// Connect, subscribe and process updates until the stream ends (`Ok`) or
+    /// fails. Resumes from `state.last_slot` when there is one.
+    async fn stream_once(&self, request: &SubscribeRequest, state: &mut StreamState, backoff: &mut Duration) -> Result<()> {
+        state.awaiting_replay = false;
         let mut client = self.setup_client().await?;
         info!("Connected to gRPC endpoint");
         self.logger.info("Connected to Yellowstone gRPC endpoint", "YELLOWSTONE");
 
-        let (subscribe_tx, subscribe_rx) = client.subscribe().await?;
-
-        self.send_subscription_request(subscribe_tx).await?;
-        info!("Subscription request sent. Listening for updates...");
-        self.logger.info("Subscription request sent. Listening for updates...", "YELLOWSTONE");
-
-        self.process_updates(subscribe_rx).await?;
+        let (mut subscribe_tx, subscribe_rx) = client.subscribe().await?;
+
+        let request = SubscribeRequest {
+            from_slot: state.last_slot,
+            ..request.clone()
+        };
+        self.send_subscription_request(&mut subscribe_tx, request).await?;
+        state.awaiting_replay = state.last_slot.is_some();
+        match state.last_slot {
+            Some(slot) => {
+                info!("Subscription request sent. Replaying from slot {}...", slot);
+                self.logger.info(&format!("Subscription request sent. Replaying from slot {}...", slot), "YELLOWSTONE");
+            }
+            None => {
+                info!("Subscription request sent. Listening for updates...");
+                self.logger.info("Subscription request sent. Listening for updates...", "YELLOWSTONE");
+            }
+        }
 
-        info!("Stream closed");
-        self.logger.info("Yellowstone gRPC stream closed", "YELLOWSTONE");
-        Ok(())
+        self.process_updates(subscribe_tx, subscribe_rx, state, backoff).await
     }
 
     /// Initialize the logging system
@@ -148,78 +293,103 @@ impl YellowstoneMonitor {
         info!("Connecting to gRPC endpoint: {}", self.endpoint);
         self.logger.info(&format!("Connecting to gRPC endpoint: {}", self.endpoint), "YELLOWSTONE");
 
-        // Build the gRPC client with TLS config
-        let client = GeyserGrpcClient::build_from_shared(self.endpoint.clone())?
-            .x_token(Some(self.auth_token.clone()))?
-            .tls_config(ClientTlsConfig::new().with_native_roots())?
-            .connect()
-            .await?;
-
-        Ok(client)
+        connect_client(&self.endpoint, &self.auth_token).await
     }
 
-        /// Send the subscription request with transaction filters
+    /// Send the subscription request
     async fn send_subscription_request<T>(
         &self,
-        mut tx: T,
+        tx: &mut T,
+        request: SubscribeRequest,
     ) -> Result<()>
     where
         T: SinkExt<SubscribeRequest> + Unpin,
         <T as futures::Sink<SubscribeRequest>>::Error: std::error::Error + 'static + Send + Sync,
     {
-        // Create account filter with the target accounts
-        let mut accounts_filter = HashMap::new();
-        accounts_filter.insert(
-            "account_monitor".to_string(),
-            SubscribeRequestFilterTransactions {
-                account_include: vec![],
-                account_exclude: vec![],
-                account_required: self.accounts_to_monitor.clone(),
-                vote: Some(false),
-                failed: Some(false),
-                signature: None,
-            },
-        );
-
-        // Send subscription request
-        tx.send(SubscribeRequest {
-            transactions: accounts_filter,
-            commitment: Some(CommitmentLevel::Processed as i32),
-            ..Default::default()
-        }).await?;
+        tx.send(request).await?;
 
         Ok(())
     }
 
-    /// Process updates from the stream with beautiful formatting
-    async fn process_updates<S>(
+    /// Process updates from the stream with beautiful formatting, pinging the
+    /// server every `PING_INTERVAL` and answering its pings. Fails when the
+    /// stream errors or stays silent for `STREAM_IDLE_TIMEOUT`.
+    async fn process_updates<T, S>(
         &self,
+        mut subscribe_tx: T,
         mut stream: S,
+        state: &mut StreamState,
+        backoff: &mut Duration,
     ) -> Result<()>
     where
+        T: SinkExt<SubscribeRequest> + Unpin,
+        <T as futures::Sink<SubscribeRequest>>::Error: std::error::Error + 'static + Send + Sync,
         S: StreamExt<Item = Result<SubscribeUpdate, Status>> + Unpin,
     {
-        let mut transaction_count = 0;
-
-        while let Some(message) = stream.next().await {
-            match message {
-                Ok(msg) => {
-                    transaction_count += 1;
-                    self.handle_message(msg, transaction_count);
-                },
-                Err(e) => {
-                    error!("Error receiving message: {:?}", e);
-                    self.logger.error(&format!("Error receiving message: {:?}", e), "YELLOWSTONE");
-                    break;
+        let mut ping = tokio::time::interval_at(Instant::now() + PING_INTERVAL, PING_INTERVAL);
+        let mut ping_id = 0;
+        let mut last_message = Instant::now();
+
+        loop {
+            tokio::select! {
+                _ = ping.tick() => {
+                    ping_id += 1;
+                    self.send_subscription_request(&mut subscribe_tx, SubscribeRequest {
+                        ping: Some(SubscribeRequestPing { id: ping_id }),
+                        ..Default::default()
+                    }).await?;
+                }
+                _ = tokio::time::sleep_until(last_message + STREAM_IDLE_TIMEOUT) => {
+                    return Err(anyhow::anyhow!("no updates or pongs for {}s", STREAM_IDLE_TIMEOUT.as_secs()));
+                }
+                message = stream.next() => {
+                    let msg = match message {
+                        Some(Ok(msg)) => {
+                            state.awaiting_replay = false;
+                            msg
+                        }
+                        Some(Err(status)) => return Err(status.into()),
+                        None => return Ok(()),
+                    };
+                    last_message = Instant::now();
+                    *backoff = RECONNECT_MIN_DELAY;
+
+                    match &msg.update_oneof {
+                        Some(UpdateOneof::Ping(_)) => {
+                            // Answer pings so load balancers keep the stream open
+                            ping_id += 1;
+                            self.send_subscription_request(&mut subscribe_tx, SubscribeRequest {
+                                ping: Some(SubscribeRequestPing { id: ping_id }),
+                                ..Default::default()
+                            }).await?;
+                            continue;
+                        }
+                        Some(UpdateOneof::Pong(pong)) => {
+                            debug!("Pong {} received", pong.id);
+                            continue;
+                        }
+                        Some(UpdateOneof::Transaction(transaction_update)) => {
+                            let signature = transaction_update.transaction.as_ref().map(|info| info.signature.as_slice());
+                            if signature.is_some_and(|signature| !state.first_sighting(signature)) {
+                                debug!("Skipping replayed transaction at slot {}", transaction_update.slot);
+                                continue;
+                            }
+                        }
+                        _ => {}
+                    }
+
+                    if let Some(slot) = msg.update_oneof.as_ref().and_then(update_slot) {
+                        state.observe_slot(slot);
+                    }
+                    state.message_count += 1;
+                    self.handle_message(msg, state.message_count).await;
                 }
             }
         }
-
-        Ok(())
     }
 
     /// Handle an individual message from the stream with beautiful formatting
-    fn handle_message(&self, msg: SubscribeUpdate, count: u64) {
+    async fn handle_message(&self, msg: SubscribeUpdate, count: u64) {
         match msg.update_oneof {
             Some(UpdateOneof::Transaction(transaction_update)) => {
                 if let Some(tx_info) = &transaction_update.transaction {
@@ -261,16 +431,95 @@ impl YellowstoneMonitor {
                         "│".truecolor(189, 147, 249)
                     );
 
+                    let trades = pump_fun::extract_trades(tx_info, transaction_update.slot);
+                    if !trades.is_empty() {
+                        println!("{}", separator.truecolor(189, 147, 249));
+                    }
+                    for trade in &trades {
+                        for (line, color) in trade_lines(trade) {
+                            let padding = content_width.saturating_sub(line.chars().count() + 2);
+                            println!("{} {} {} {}",
+                                "│".truecolor(189, 147, 249),
+                                line.truecolor(color.0, color.1, color.2),
+                                " ".repeat(padding),
+                                "│".truecolor(189, 147, 249)
+                            );
+                        }
+                    }
+
                     let bottom_border = format!("└─{}─┘", "─".repeat(content_width - 2));
                     println!("{}", bottom_border.truecolor(189, 147, 249));
 
                     info!("Transaction update received! ID: {}", tx_id);
                     self.logger.info(&format!("Transaction update received! ID: {}", tx_id), "YELLOWSTONE");
+
+                    if let Some(database) = &self.database {
+                        if let Err(e) = database.insert_token_trades(&trades).await {
+                            warn!("Failed to store token trades for {}: {}", tx_id, e);
+                            self.logger.warn(&format!("Failed to store token trades for {}: {}", tx_id, e), "YELLOWSTONE");
+                        }
+                    }
                 } else {
                     warn!("Transaction update received but no transaction info available");
                     self.logger.warn("Transaction update received but no transaction info available", "YELLOWSTONE");
                 }
             },
+            Some(UpdateOneof::Account(account_update)) => {
+                if let Some(account) = &account_update.account {
+                    let pubkey = bs58::encode(&account.pubkey).into_string();
+                    let owner = bs58::encode(&account.owner).into_string();
+                    println!("{} {} | {} owner {} | {} lamports | {} bytes | slot {}",
+                        "📒".bright_green(),
+                        "ACCOUNT".bright_green(),
+                        pubkey.bright_cyan(),
+                        owner.bright_white(),
+                        account.lamports,
+                        account.data.len(),
+                        account_update.slot
+                    );
+                }
+            },
+            Some(UpdateOneof::Slot(slot_update)) => {
+                let status = SlotStatus::try_from(slot_update.status)
+                    .map(|status| status.as_str_name().to_string())
+                    .unwrap_or_else(|_| slot_update.status.to_string());
+                println!("{} {} | {} {}",
+                    "🎰".bright_green(),
+                    "SLOT".bright_green(),
+                    slot_update.slot.to_string().bright_cyan(),
+                    status.bright_white()
+                );
+            },
+            Some(UpdateOneof::Block(block)) => {
+                println!("{} {} | {} {} | {} transactions | {} accounts | {} entries",
+                    "🧱".bright_green(),
+                    "BLOCK".bright_green(),
+                    block.slot.to_string().bright_cyan(),
+                    block.blockhash.bright_white(),
+                    block.executed_transaction_count,
+                    block.updated_account_count,
+                    block.entries_count
+                );
+            },
+            Some(UpdateOneof::BlockMeta(meta)) => {
+                println!("{} {} | {} {} | parent {} | {} transactions",
+                    "🧾".bright_green(),
+                    "BLOCK_META".bright_green(),
+                    meta.slot.to_string().bright_cyan(),
+                    meta.blockhash.bright_white(),
+                    meta.parent_slot,
+                    meta.executed_transaction_count
+                );
+            },
+            Some(UpdateOneof::Entry(entry)) => {
+                println!("{} {} | {} #{} | {} transactions",
+                    "📜".bright_green(),
+                    "ENTRY".bright_green(),
+                    entry.slot.to_string().bright_cyan(),
+                    entry.index,
+                    entry.executed_transaction_count
+                );
+            },
             Some(other) => {
                 info!("Other update received: {:?}", other);
                 self.logger.info(&format!("Other update received: {:?}", other), "YELLOWSTONE");
@@ -283,12 +532,102 @@ impl YellowstoneMonitor {
     }
 }
 
+/// Slot an update belongs to
+fn update_slot(update: &UpdateOneof) -> Option<u64> {
+    match update {
+        UpdateOneof::Account(account) => Some(account.slot),
+        UpdateOneof::Slot(slot) => Some(slot.slot),
+        UpdateOneof::Transaction(transaction) => Some(transaction.slot),
+        UpdateOneof::TransactionStatus(status) => Some(status.slot),
+        UpdateOneof::Block(block) => Some(block.slot),
+        UpdateOneof::BlockMeta(meta) => Some(meta.slot),
+        UpdateOneof::Entry(entry) => Some(entry.slot),
+        _ => None,
+    }
+}
+
+/// Whether `error`, as the first response to a `from_slot` request, means
+/// the server refused to replay
+fn replay_rejected(error: &anyhow::Error) -> bool {
+    error.downcast_ref::<Status>().is_some_and(|status| {
+        matches!(status.code(), Code::InvalidArgument | Code::OutOfRange) || status.message().contains("from_slot")
+    })
+}
+
+/// `delay` scaled by a random factor in [0.5, 1.0)
+fn jittered(delay: Duration) -> Duration {
+    let random = RandomState::new().hash_one(Instant::now()) as f64 / u64::MAX as f64;
+    delay.mul_f64(0.5 + random / 2.0)
+}
+
+/// Panel rows for one trade, with their colour
+fn trade_lines(trade: &TokenTrade) -> Vec<(String, (u8, u8, u8))> {
+    let color = match trade.action {
+        TradeAction::Buy => (80, 250, 123),
+        TradeAction::Sell => (255, 85, 85),
+        TradeAction::Create => (255, 184, 108),
+    };
+    let mut lines = Vec::new();
+
+    let headline = match trade.action {
+        TradeAction::Create => format!(
+            "{} {} ({})",
+            trade.action.as_str(),
+            trade.name.as_deref().unwrap_or("?"),
+            trade.symbol.as_deref().unwrap_or("?"),
+        ),
+        _ => format!(
+            "{} {} tokens for {} SOL",
+            trade.action.as_str(),
+            trade.tokens().map(|tokens| format!("{:.2}", tokens)).unwrap_or_else(|| "?".to_string()),
+            trade.sol().map(|sol| format!("{:.6}", sol)).unwrap_or_else(|| "?".to_string()),
+        ),
+    };
+    lines.push((headline, color));
+    lines.push((format!("  Mint: {}", trade.mint), (139, 233, 253)));
+    lines.push((format!("  Trader: {}", trade.trader), (139, 233, 253)));
+
+    if let Some(curve) = trade.curve {
+        lines.push((
+            format!(
+                "  Curve: {:.4} SOL / {:.2} tokens virtual, price {:.10} SOL",
+                curve.virtual_sol_reserves as f64 / 1_000_000_000.0,
+                curve.virtual_token_reserves as f64 / 10f64.powi(pump_fun::PUMP_FUN_TOKEN_DECIMALS),
+                curve.price(),
+            ),
+            (248, 248, 242),
+        ));
+    } else if let Some(uri) = &trade.uri {
+        lines.push((format!("  URI: {}", uri), (248, 248, 242)));
+    }
+
+    lines
+}
+
+/// Connect to a Yellowstone gRPC endpoint with TLS and x-token auth
+pub async fn connect_client(endpoint: &str, auth_token: &str) -> Result<GeyserGrpcClient<impl Interceptor>> {
+    // Build the gRPC client with TLS config
+    let client = GeyserGrpcClient::build_from_shared(endpoint.to_string())?
+        .x_token(Some(auth_token.to_string()))?
+        .tls_config(ClientTlsConfig::new().with_native_roots())?
+        .connect()
+        .await?;
+
+    Ok(client)
+}
+
 /// Quick start function for easy CLI integration
 pub async fn start_yellowstone_monitoring(
     endpoint: String,
     auth_token: String,
     logger: NerdLogger,
+    database: Option<Database>,
+    subscription: SubscriptionConfig,
 ) -> Result<()> {
-    let monitor = YellowstoneMonitor::new(endpoint, auth_token, logger);
+    let mut monitor = YellowstoneMonitor::new(endpoint, auth_token, logger)
+        .with_subscription(subscription);
+    if let Some(database) = database {
+        monitor = monitor.with_database(database);
+    }
     monitor.start_monitoring().await
 }
@@ -0,0 +1,352 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::Path;
+use yellowstone_grpc_proto::prelude::{
+    subscribe_request_filter_accounts_filter::Filter as AccountsFilterKind,
+    subscribe_request_filter_accounts_filter_memcmp::Data as MemcmpData,
+    CommitmentLevel, SubscribeRequest, SubscribeRequestAccountsDataSlice,
+    SubscribeRequestFilterAccounts, SubscribeRequestFilterAccountsFilter,
+    SubscribeRequestFilterAccountsFilterMemcmp, SubscribeRequestFilterBlocks,
+    SubscribeRequestFilterBlocksMeta, SubscribeRequestFilterEntry, SubscribeRequestFilterSlots,
+    SubscribeRequestFilterTransactions,
+};
+
+/// Name of the filters built from command-line flags
+pub const CLI_FILTER_NAME: &str = "cli";
+
+/// Commitment the Geyser stream is delivered at
+#[derive(clap::ValueEnum, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SubscriptionCommitment {
+    #[default]
+    Processed,
+    Confirmed,
+    Finalized,
+}
+
+impl SubscriptionCommitment {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SubscriptionCommitment::Processed => "processed",
+            SubscriptionCommitment::Confirmed => "confirmed",
+            SubscriptionCommitment::Finalized => "finalized",
+        }
+    }
+
+    fn level(&self) -> CommitmentLevel {
+        match self {
+            SubscriptionCommitment::Processed => CommitmentLevel::Processed,
+            SubscriptionCommitment::Confirmed => CommitmentLevel::Confirmed,
+            SubscriptionCommitment::Finalized => CommitmentLevel::Finalized,
+        }
+    }
+}
+
+/// Match account data at `offset`, given as base58 or base64
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(deny_unknown_fields)]
+pub struct MemcmpFilter {
+    pub offset: u64,
+    pub base58: Option<String>,
+    pub base64: Option<String>,
+}
+
+impl MemcmpFilter {
+    /// Parse `OFFSET:BASE58` as given to `--memcmp`
+    pub fn parse(value: &str) -> Result<Self> {
+        let (offset, data) = value.split_once(':')
+            .with_context(|| format!("expected OFFSET:BASE58, got {}", value))?;
+        Ok(Self {
+            offset: offset.trim().parse().with_context(|| format!("invalid memcmp offset {}", offset))?,
+            base58: Some(data.trim().to_string()),
+            base64: None,
+        })
+    }
+}
+
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct AccountsFilter {
+    pub account: Vec<String>,
+    pub owner: Vec<String>,
+    pub memcmp: Vec<MemcmpFilter>,
+    pub datasize: Option<u64>,
+    /// Only initialized SPL token accounts
+    pub token_account_state: Option<bool>,
+    pub nonempty_txn_signature: Option<bool>,
+}
+
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct TransactionsFilter {
+    pub vote: Option<bool>,
+    pub failed: Option<bool>,
+    pub signature: Option<String>,
+    pub account_include: Vec<String>,
+    pub account_exclude: Vec<String>,
+    pub account_required: Vec<String>,
+}
+
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct SlotsFilter {
+    pub filter_by_commitment: Option<bool>,
+    pub interslot_updates: Option<bool>,
+}
+
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct BlocksFilter {
+    pub account_include: Vec<String>,
+    pub include_transactions: Option<bool>,
+    pub include_accounts: Option<bool>,
+    pub include_entries: Option<bool>,
+}
+
+/// `blocks_meta` and `entries` filters take no options
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(deny_unknown_fields)]
+pub struct EmptyFilter {}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct DataSlice {
+    pub offset: u64,
+    pub length: u64,
+}
+
+/// Everything a Yellowstone `SubscribeRequest` can ask for, keyed by filter
+/// name as in the request itself. Loaded from YAML or JSON.
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct SubscriptionConfig {
+    pub commitment: Option<SubscriptionCommitment>,
+    pub accounts: HashMap<String, AccountsFilter>,
+    pub transactions: HashMap<String, TransactionsFilter>,
+    pub transactions_status: HashMap<String, TransactionsFilter>,
+    pub slots: HashMap<String, SlotsFilter>,
+    pub blocks: HashMap<String, BlocksFilter>,
+    pub blocks_meta: HashMap<String, EmptyFilter>,
+    pub entries: HashMap<String, EmptyFilter>,
+    pub accounts_data_slice: Vec<DataSlice>,
+}
+
+impl SubscriptionConfig {
+    /// Read a subscription file; `.json` files are parsed as JSON, anything else as YAML
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        let config = match path.extension().and_then(|extension| extension.to_str()) {
+            Some("json") => serde_json::from_str(&contents)?,
+            _ => serde_yaml::from_str(&contents)?,
+        };
+        Ok(config)
+    }
+
+    /// The monitor's default: transactions touching all of `accounts`,
+    /// excluding votes and failures, at processed commitment
+    pub fn required_accounts(accounts: &[String]) -> Self {
+        Self {
+            transactions: HashMap::from([("account_monitor".to_string(), TransactionsFilter {
+                vote: Some(false),
+                failed: Some(false),
+                account_required: accounts.to_vec(),
+                ..Default::default()
+            })]),
+            ..Default::default()
+        }
+    }
+
+    /// True when no filter of any kind is set
+    pub fn is_empty(&self) -> bool {
+        self.filter_count() == 0
+    }
+
+    pub fn filter_count(&self) -> usize {
+        self.accounts.len()
+            + self.transactions.len()
+            + self.transactions_status.len()
+            + self.slots.len()
+            + self.blocks.len()
+            + self.blocks_meta.len()
+            + self.entries.len()
+    }
+
+    /// Filter counts per stream, e.g. `transactions: 1, slots: 1`
+    pub fn summary(&self) -> String {
+        [
+            ("accounts", self.accounts.len()),
+            ("transactions", self.transactions.len()),
+            ("transactions_status", self.transactions_status.len()),
+            ("slots", self.slots.len()),
+            ("blocks", self.blocks.len()),
+            ("blocks_meta", self.blocks_meta.len()),
+            ("entries", self.entries.len()),
+        ]
+        .iter()
+        .filter(|(_, count)| *count > 0)
+        .map(|(stream, count)| format!("{}: {}", stream, count))
+        .collect::<Vec<_>>()
+        .join(", ")
+    }
+
+    pub fn to_request(&self) -> SubscribeRequest {
+        SubscribeRequest {
+            accounts: self.accounts.iter()
+                .map(|(name, filter)| (name.clone(), accounts_filter(filter)))
+                .collect(),
+            transactions: self.transactions.iter()
+                .map(|(name, filter)| (name.clone(), transactions_filter(filter)))
+                .collect(),
+            transactions_status: self.transactions_status.iter()
+                .map(|(name, filter)| (name.clone(), transactions_filter(filter)))
+                .collect(),
+            slots: self.slots.iter()
+                .map(|(name, filter)| (name.clone(), SubscribeRequestFilterSlots {
+                    filter_by_commitment: filter.filter_by_commitment,
+                    interslot_updates: filter.interslot_updates,
+                }))
+                .collect(),
+            blocks: self.blocks.iter()
+                .map(|(name, filter)| (name.clone(), SubscribeRequestFilterBlocks {
+                    account_include: filter.account_include.clone(),
+                    include_transactions: filter.include_transactions,
+                    include_accounts: filter.include_accounts,
+                    include_entries: filter.include_entries,
+                }))
+                .collect(),
+            blocks_meta: self.blocks_meta.keys()
+                .map(|name| (name.clone(), SubscribeRequestFilterBlocksMeta {}))
+                .collect(),
+            entry: self.entries.keys()
+                .map(|name| (name.clone(), SubscribeRequestFilterEntry {}))
+                .collect(),
+            accounts_data_slice: self.accounts_data_slice.iter()
+                .map(|slice| SubscribeRequestAccountsDataSlice { offset: slice.offset, length: slice.length })
+                .collect(),
+            commitment: Some(self.commitment.unwrap_or_default().level() as i32),
+            ..Default::default()
+        }
+    }
+}
+
+fn accounts_filter(filter: &AccountsFilter) -> SubscribeRequestFilterAccounts {
+    let filters = filter.memcmp.iter()
+        .map(|memcmp| {
+            let data = match (&memcmp.base58, &memcmp.base64) {
+                (Some(base58), _) => Some(MemcmpData::Base58(base58.clone())),
+                (None, Some(base64)) => Some(MemcmpData::Base64(base64.clone())),
+                (None, None) => None,
+            };
+            AccountsFilterKind::Memcmp(SubscribeRequestFilterAccountsFilterMemcmp { offset: memcmp.offset, data })
+        })
+        .chain(filter.datasize.map(AccountsFilterKind::Datasize))
+        .chain(filter.token_account_state.map(AccountsFilterKind::TokenAccountState))
+        .map(|kind| SubscribeRequestFilterAccountsFilter { filter: Some(kind) })
+        .collect();
+
+    SubscribeRequestFilterAccounts {
+        account: filter.account.clone(),
+        owner: filter.owner.clone(),
+        filters,
+        nonempty_txn_signature: filter.nonempty_txn_signature,
+    }
+}
+
+fn transactions_filter(filter: &TransactionsFilter) -> SubscribeRequestFilterTransactions {
+    SubscribeRequestFilterTransactions {
+        vote: filter.vote,
+        failed: filter.failed,
+        signature: filter.signature.clone(),
+        account_include: filter.account_include.clone(),
+        account_exclude: filter.account_exclude.clone(),
+        account_required: filter.account_required.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn memcmp_flag_parses_offset_and_base58() {
+        let filter = MemcmpFilter::parse("32: 3Mc6vR ").unwrap();
+        assert_eq!(filter.offset, 32);
+        assert_eq!(filter.base58.as_deref(), Some("3Mc6vR"));
+        assert!(filter.base64.is_none());
+
+        assert!(MemcmpFilter::parse("3Mc6vR").is_err());
+        assert!(MemcmpFilter::parse("x:3Mc6vR").is_err());
+    }
+
+    #[test]
+    fn yaml_config_maps_onto_the_request() {
+        let config: SubscriptionConfig = serde_yaml::from_str("
+commitment: confirmed
+accounts:
+  pools:
+    owner: [Owner111]
+    memcmp:
+      - { offset: 8, base58: Abc }
+      - { offset: 0, base64: AQI= }
+    datasize: 165
+    token_account_state: true
+transactions:
+  swaps:
+    vote: false
+    account_include: [Pool111]
+slots:
+  tip: { filter_by_commitment: true }
+blocks_meta:
+  meta: {}
+accounts_data_slice:
+  - { offset: 0, length: 64 }
+").unwrap();
+        assert_eq!(config.filter_count(), 4);
+        assert_eq!(config.summary(), "accounts: 1, transactions: 1, slots: 1, blocks_meta: 1");
+
+        let request = config.to_request();
+        assert_eq!(request.commitment, Some(CommitmentLevel::Confirmed as i32));
+
+        let pools = &request.accounts["pools"];
+        assert_eq!(pools.owner, vec!["Owner111".to_string()]);
+        let filters: Vec<_> = pools.filters.iter().map(|filter| filter.filter.clone().unwrap()).collect();
+        assert_eq!(filters, vec![
+            AccountsFilterKind::Memcmp(SubscribeRequestFilterAccountsFilterMemcmp { offset: 8, data: Some(MemcmpData::Base58("Abc".to_string())) }),
+            AccountsFilterKind::Memcmp(SubscribeRequestFilterAccountsFilterMemcmp { offset: 0, data: Some(MemcmpData::Base64("AQI=".to_string())) }),
+            AccountsFilterKind::Datasize(165),
+            AccountsFilterKind::TokenAccountState(true),
+        ]);
+
+        let swaps = &request.transactions["swaps"];
+        assert_eq!((swaps.vote, swaps.failed), (Some(false), None));
+        assert_eq!(swaps.account_include, vec!["Pool111".to_string()]);
+        assert_eq!(request.slots["tip"].filter_by_commitment, Some(true));
+        assert!(request.blocks_meta.contains_key("meta"));
+        assert!(request.blocks.is_empty() && request.entry.is_empty());
+        assert_eq!((request.accounts_data_slice[0].offset, request.accounts_data_slice[0].length), (0, 64));
+    }
+
+    #[test]
+    fn unknown_keys_are_rejected() {
+        assert!(serde_yaml::from_str::<SubscriptionConfig>("transaction: {}").is_err());
+        assert!(serde_yaml::from_str::<SubscriptionConfig>("slots: { tip: { interslot: true } }").is_err());
+    }
+
+    #[test]
+    fn required_accounts_default_is_processed_non_vote_successes() {
+        let accounts = vec!["Wallet111".to_string(), "Pool111".to_string()];
+        let config = SubscriptionConfig::required_accounts(&accounts);
+        assert!(!config.is_empty());
+
+        let request = config.to_request();
+        assert_eq!(request.commitment, Some(CommitmentLevel::Processed as i32));
+        let filter = &request.transactions["account_monitor"];
+        assert_eq!((filter.vote, filter.failed), (Some(false), Some(false)));
+        assert_eq!(filter.account_required, accounts);
+        assert!(filter.account_include.is_empty());
+
+        assert!(SubscriptionConfig::default().is_empty());
+    }
+}